@@ -0,0 +1,56 @@
+package main
+
+import (
+	"context"
+	"testing"
+
+	"cloud.google.com/go/storage"
+	"github.com/google/uuid"
+	"github.com/stretchr/testify/assert"
+	"github.com/yuk1ty/cloud-storage-emulator-e2e/helper"
+)
+
+func TestCreateObject(t *testing.T) {
+	helper.InitTest(t)
+
+	t.Run("Create one object and get its attributes without any error", func(t *testing.T) {
+		// Arrange
+		ctx := context.Background()
+
+		assert := assert.New(t)
+
+		client, err := storage.NewClient(ctx)
+		if err != nil {
+			t.Fatalf("Failed to create client: %v", err)
+		}
+
+		testUniqID, err := uuid.NewRandom()
+		if err != nil {
+			t.Fatalf("Failed to generate a new UUID")
+		}
+		testBucketName := "test-bucket-" + testUniqID.String()
+		bucket := client.Bucket(testBucketName)
+		if err := bucket.Create(ctx, "test-project", nil); err != nil {
+			t.Fatalf("Failed to create bucket: %v", err)
+		}
+
+		// Act
+		w := bucket.Object("dir/hello.txt").NewWriter(ctx)
+		w.ContentType = "text/plain"
+		w.Metadata = map[string]string{"key": "value"}
+		if _, err := w.Write([]byte("hello")); err != nil {
+			t.Fatalf("Failed to write object: %v", err)
+		}
+		if err := w.Close(); err != nil {
+			t.Fatalf("Failed to create object: %v", err)
+		}
+		attrs, err := bucket.Object("dir/hello.txt").Attrs(ctx)
+
+		// Assert
+		assert.Nil(err)
+		assert.Equal("dir/hello.txt", attrs.Name)
+		assert.Equal("text/plain", attrs.ContentType)
+		assert.Equal(int64(5), attrs.Size)
+		assert.Equal("value", attrs.Metadata["key"])
+	})
+}
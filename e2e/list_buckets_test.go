@@ -0,0 +1,59 @@
+package main
+
+import (
+	"context"
+	"testing"
+
+	"cloud.google.com/go/storage"
+	"github.com/google/uuid"
+	"github.com/stretchr/testify/assert"
+	"github.com/yuk1ty/cloud-storage-emulator-e2e/helper"
+	"google.golang.org/api/iterator"
+)
+
+func TestListBuckets(t *testing.T) {
+	helper.InitTest(t)
+
+	t.Run("List only buckets owned by the given project", func(t *testing.T) {
+		// Arrange
+		ctx := context.Background()
+
+		assert := assert.New(t)
+
+		client, err := storage.NewClient(ctx)
+		if err != nil {
+			t.Fatalf("Failed to create client: %v", err)
+		}
+
+		testUniqID, err := uuid.NewRandom()
+		if err != nil {
+			t.Fatalf("Failed to generate a new UUID")
+		}
+		testProject := "test-project-" + testUniqID.String()
+		testBucketName := "test-bucket-" + testUniqID.String()
+		if err := client.Bucket(testBucketName).Create(ctx, testProject, nil); err != nil {
+			t.Fatalf("Failed to create bucket: %v", err)
+		}
+		otherBucketName := "other-bucket-" + testUniqID.String()
+		if err := client.Bucket(otherBucketName).Create(ctx, "other-project", nil); err != nil {
+			t.Fatalf("Failed to create bucket: %v", err)
+		}
+
+		// Act
+		var names []string
+		it := client.Buckets(ctx, testProject)
+		for {
+			attrs, err := it.Next()
+			if err == iterator.Done {
+				break
+			}
+			if err != nil {
+				t.Fatalf("Failed to list buckets: %v", err)
+			}
+			names = append(names, attrs.Name)
+		}
+
+		// Assert
+		assert.Equal([]string{testBucketName}, names)
+	})
+}
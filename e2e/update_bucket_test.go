@@ -54,6 +54,46 @@ func TestUpdateBucket(t *testing.T) {
 		assert.Equal(false, updated.DefaultEventBasedHold)
 	})
 
+	t.Run("Update only versioning then keep default event-based hold", func(t *testing.T) {
+		// Arrange
+		ctx := context.Background()
+
+		assert := assert.New(t)
+
+		client, err := storage.NewClient(ctx)
+		if err != nil {
+			t.Fatalf("Failed to create client: %v", err)
+		}
+
+		testUniqID, err := uuid.NewRandom()
+		if err != nil {
+			t.Fatalf("Failed to generate a new UUID")
+		}
+		testBucketName := "test-bucket-" + testUniqID.String()
+		bucket := client.Bucket(testBucketName)
+		attrs := storage.BucketAttrs{
+			VersioningEnabled:     false,
+			DefaultEventBasedHold: true,
+		}
+		if err := bucket.Create(ctx, "test-project", &attrs); err != nil {
+			t.Fatalf("Failed to create bucket: %v", err)
+		}
+
+		// Act
+		uattrs := storage.BucketAttrsToUpdate{
+			VersioningEnabled: true,
+		}
+		updated, err := bucket.Update(ctx, uattrs)
+		if err != nil {
+			t.Fatalf("Failed to update bucket: %v", err)
+		}
+
+		// Assert
+		assert.Nil(err)
+		assert.Equal(true, updated.VersioningEnabled)
+		assert.Equal(true, updated.DefaultEventBasedHold)
+	})
+
 	t.Run("Update a non existing bucket then get 404 not found", func(t *testing.T) {
 		// Arrange
 		ctx := context.Background()
@@ -0,0 +1,104 @@
+use axum::{extract::State, Json};
+use axum_garde::WithValidation;
+use tracing::instrument;
+
+use crate::{
+    api::models::clock::{AdvanceClock, ClockResponse, SetClock},
+    flows::clock::{advance_time, current_time, set_time},
+    storage::Storage,
+};
+
+#[instrument(skip(storage))]
+pub async fn get_clock(State(storage): State<Storage>) -> Json<ClockResponse> {
+    Json(current_time(storage))
+}
+
+#[instrument(skip(storage))]
+pub async fn set_clock(
+    State(storage): State<Storage>,
+    WithValidation(req): WithValidation<Json<SetClock>>,
+) -> Json<ClockResponse> {
+    Json(set_time(storage, req.into_inner()).await)
+}
+
+#[instrument(skip(storage))]
+pub async fn advance_clock(
+    State(storage): State<Storage>,
+    WithValidation(req): WithValidation<Json<AdvanceClock>>,
+) -> Json<ClockResponse> {
+    Json(advance_time(storage, req.into_inner()).await)
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::Body,
+        http::{header, Method, Request, StatusCode},
+        Extension,
+    };
+    use bytes::Bytes;
+    use googletest::prelude::*;
+    use tower::ServiceExt;
+
+    use crate::{
+        api::{handlers::context::AuthMode, routes::routes},
+        storage::{
+            lifecycle::{LifecycleAction, LifecycleCondition, LifecycleRule},
+            BucketStorageExt, CreateBucketAttr, CreateObjectAttr, ObjectStorageExt, Preconditions,
+            Storage,
+        },
+    };
+
+    #[googletest::test]
+    #[tokio::test]
+    async fn apply_lifecycle_rules_due_after_advancing_clock() {
+        // Arrange
+        let storage = Storage::default();
+        let attr = CreateBucketAttr {
+            project: "test-project".into(),
+            lifecycle: vec![LifecycleRule {
+                action: LifecycleAction::Delete,
+                condition: LifecycleCondition {
+                    age: Some(30),
+                    ..Default::default()
+                },
+            }],
+            ..Default::default()
+        };
+        let _ = storage.create("test_bucket", attr).await;
+        let _ = storage
+            .create_object(
+                "test_bucket",
+                CreateObjectAttr {
+                    name: "a".into(),
+                    ..Default::default()
+                },
+                Bytes::new(),
+                Preconditions::default(),
+            )
+            .await;
+        let router = routes(&storage)
+            .layer(Extension(AuthMode { enforce: false }))
+            .with_state(storage.clone());
+        let advance = |seconds: i64| {
+            Request::builder()
+                .method(Method::POST)
+                .uri("/_emulator/clock/advance")
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(format!(r#"{{"seconds":{seconds}}}"#)))
+                .unwrap()
+        };
+
+        // Act
+        let invalid = router.clone().oneshot(advance(-1)).await.unwrap();
+        let advanced = router.oneshot(advance(30 * 86400)).await.unwrap();
+        let res = storage
+            .get_object("test_bucket", "a", None, Preconditions::default())
+            .await;
+
+        // Assert
+        expect_that!(invalid.status(), not(eq(StatusCode::OK)));
+        expect_that!(advanced.status(), eq(StatusCode::OK));
+        assert_that!(res, err(anything()));
+    }
+}
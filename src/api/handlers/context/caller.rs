@@ -0,0 +1,131 @@
+use std::convert::Infallible;
+
+use axum::{
+    async_trait,
+    extract::FromRequestParts,
+    http::{header, request::Parts},
+};
+use base64::{prelude::BASE64_URL_SAFE_NO_PAD, Engine};
+
+use crate::{
+    libs::errors::{AppResult, Errors},
+    storage::{iam::IamStorageExt, Storage},
+};
+
+/// The header through which a client tells the emulator who it is, e.g. `user:alice@example.com`.
+const PRINCIPAL_HEADER: &str = "x-emulator-principal";
+
+/// Whether requests are authorized, which is given by `--enforce-auth` as a request extension.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct AuthMode {
+    pub enforce: bool,
+}
+
+/// Whom a request is signed by, which is added as a request extension once the signature is verified.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Signer {
+    pub principal: String,
+    pub project: String,
+}
+
+/// The principal making a request, or `None` for an anonymous caller.
+/// A verified signature takes precedence over the emulator header, which does over the bearer token.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Caller {
+    pub principal: Option<String>,
+    /// The project owning the key of a signed request.
+    pub project: Option<String>,
+    enforce: bool,
+}
+
+impl Caller {
+    /// Checks the permission on the bucket, or on the object if given, when `--enforce-auth` is on.
+    pub async fn authorize(
+        &self,
+        storage: &Storage,
+        bucket: &str,
+        object: Option<&str>,
+        permission: &str,
+    ) -> AppResult<(), Errors> {
+        if !self.enforce {
+            return Ok(());
+        }
+        storage
+            .authorize(bucket, object, self.principal.as_deref(), permission)
+            .await
+    }
+
+    /// The caller of a request whose signature is verified by a handler, such as a form upload with a policy document.
+    pub fn signed_by(self, signer: Signer) -> Self {
+        Caller {
+            principal: Some(signer.principal),
+            project: Some(signer.project),
+            ..self
+        }
+    }
+
+    /// Checks a project level permission such as `storage.buckets.create` when `--enforce-auth` is on.
+    /// The emulator has no project IAM policy, so any authenticated caller is allowed.
+    pub fn authorize_project(&self, permission: &str) -> AppResult<(), Errors> {
+        if !self.enforce || self.principal.is_some() {
+            return Ok(());
+        }
+        Err(Errors::Forbidden {
+            message: format!("Anonymous caller does not have {permission} access to the project."),
+        })
+    }
+}
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for Caller {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _: &S) -> Result<Self, Self::Rejection> {
+        let header = |name| {
+            parts
+                .headers
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(str::trim)
+                .filter(|v| !v.is_empty())
+        };
+        let signer = parts.extensions.get::<Signer>();
+        let principal = signer
+            .map(|s| s.principal.clone())
+            .or_else(|| header(PRINCIPAL_HEADER).map(str::to_string))
+            .or_else(|| {
+                header(header::AUTHORIZATION.as_str())
+                    .and_then(|v| v.strip_prefix("Bearer "))
+                    .and_then(principal_from_token)
+            });
+        Ok(Caller {
+            principal,
+            project: signer.map(|s| s.project.clone()),
+            enforce: parts
+                .extensions
+                .get::<AuthMode>()
+                .is_some_and(|mode| mode.enforce),
+        })
+    }
+}
+
+/// Takes the `email` claim of a JWT such as an ID token, or a token spelled as a member like `user:alice@example.com`.
+/// Tokens aren't verified, and any other token is treated as anonymous.
+fn principal_from_token(token: &str) -> Option<String> {
+    if token.contains(':') {
+        return Some(token.to_string());
+    }
+    let payload = token.split('.').nth(1)?;
+    let claims = serde_json::from_slice::<serde_json::Value>(
+        &BASE64_URL_SAFE_NO_PAD
+            .decode(payload.trim_end_matches('='))
+            .ok()?,
+    )
+    .ok()?;
+    let email = claims.get("email")?.as_str()?;
+    if email.ends_with(".gserviceaccount.com") {
+        Some(format!("serviceAccount:{email}"))
+    } else {
+        Some(format!("user:{email}"))
+    }
+}
@@ -3,6 +3,10 @@ use serde::Serialize;
 
 use crate::{libs::errors::Errors, storage::Storage};
 
+mod caller;
+
+pub use caller::{AuthMode, Caller, Signer};
+
 #[derive(Serialize)]
 #[serde(rename_all = "camelCase")]
 struct CloudStorageErrorResponse {
@@ -10,17 +14,21 @@ struct CloudStorageErrorResponse {
     pub error_message: String,
 }
 
+fn error_response(status_code: StatusCode, message: String) -> axum::response::Response {
+    (
+        status_code,
+        Json(CloudStorageErrorResponse {
+            status_code: status_code.as_u16(),
+            error_message: message,
+        }),
+    )
+        .into_response()
+}
+
 impl IntoResponse for Errors {
     fn into_response(self) -> axum::response::Response {
         match self {
-            Errors::AlreadyExists { message } => (
-                StatusCode::CONFLICT,
-                Json(CloudStorageErrorResponse {
-                    status_code: StatusCode::CONFLICT.as_u16(),
-                    error_message: message,
-                }),
-            )
-                .into_response(),
+            Errors::AlreadyExists { message } => error_response(StatusCode::CONFLICT, message),
             Errors::FailedToWriteStorage { id, message } => {
                 tracing::error!(err.message = %message, id);
                 StatusCode::INTERNAL_SERVER_ERROR.into_response()
@@ -29,6 +37,26 @@ impl IntoResponse for Errors {
                 tracing::error!(err.message = %message);
                 StatusCode::NOT_FOUND.into_response()
             }
+            Errors::BucketNotEmpty { message } => error_response(StatusCode::CONFLICT, message),
+            Errors::ObjectNotFound { message } => error_response(StatusCode::NOT_FOUND, message),
+            Errors::AccessControlNotFound { message } => {
+                error_response(StatusCode::NOT_FOUND, message)
+            }
+            Errors::NotificationConfigNotFound { message } => {
+                error_response(StatusCode::NOT_FOUND, message)
+            }
+            Errors::ChannelNotFound { message } => error_response(StatusCode::NOT_FOUND, message),
+            Errors::UploadNotFound { message } => error_response(StatusCode::NOT_FOUND, message),
+            Errors::HmacKeyNotFound { message } => error_response(StatusCode::NOT_FOUND, message),
+            Errors::Forbidden { message }
+            | Errors::InvalidAccessKeyId { message }
+            | Errors::SignatureDoesNotMatch { message } => {
+                error_response(StatusCode::FORBIDDEN, message)
+            }
+            Errors::PreconditionFailed { message } => {
+                error_response(StatusCode::PRECONDITION_FAILED, message)
+            }
+            Errors::BadRequest { message } => error_response(StatusCode::BAD_REQUEST, message),
         }
     }
 }
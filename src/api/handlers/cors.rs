@@ -0,0 +1,212 @@
+use axum::{
+    extract::{RawPathParams, Request, State},
+    http::{header, HeaderMap, HeaderValue, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+use crate::storage::{
+    cors::{matching_preflight_rule, matching_rule, CorsRule},
+    BucketStorageExt, Storage,
+};
+
+/// Answers preflight requests and adds `Access-Control-*` headers to responses by the `cors` configuration
+/// of the bucket given by the `bucket` path parameter. Requests without `Origin` are let through as they are.
+/// https://cloud.google.com/storage/docs/cross-origin
+pub async fn apply_cors(
+    State(storage): State<Storage>,
+    params: Option<RawPathParams>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let bucket = params.and_then(|p| {
+        p.iter()
+            .find(|(k, _)| *k == "bucket")
+            .map(|(_, v)| v.to_string())
+    });
+    let origin = header_str(request.headers(), header::ORIGIN.as_str()).map(str::to_string);
+    let (Some(bucket), Some(origin)) = (bucket, origin) else {
+        return next.run(request).await;
+    };
+    let rules = storage
+        .get(&bucket)
+        .await
+        .map(|b| b.cors)
+        .unwrap_or_default();
+
+    let requested_method = header_str(
+        request.headers(),
+        header::ACCESS_CONTROL_REQUEST_METHOD.as_str(),
+    );
+    if let (&Method::OPTIONS, Some(method)) = (request.method(), requested_method) {
+        return preflight(&rules, &origin, method, request.headers());
+    }
+    let method = request.method().clone();
+    let mut response = next.run(request).await;
+    if let Some(rule) = matching_rule(&rules, &origin, method.as_str()) {
+        let headers = response.headers_mut();
+        insert(headers, header::ACCESS_CONTROL_ALLOW_ORIGIN, &origin);
+        if !rule.response_headers.is_empty() {
+            insert(
+                headers,
+                header::ACCESS_CONTROL_EXPOSE_HEADERS,
+                &rule.response_headers.join(", "),
+            );
+        }
+        headers.append(header::VARY, HeaderValue::from_static("Origin"));
+    }
+    response
+}
+
+/// Responds to a preflight request. A request no rule allows gets no `Access-Control-*` headers, which the browser rejects.
+fn preflight(rules: &[CorsRule], origin: &str, method: &str, headers: &HeaderMap) -> Response {
+    let request_headers = header_str(headers, header::ACCESS_CONTROL_REQUEST_HEADERS.as_str())
+        .map(|h| {
+            h.split(',')
+                .map(str::trim)
+                .filter(|h| !h.is_empty())
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    let mut response_headers = HeaderMap::new();
+    if let Some(rule) = matching_preflight_rule(rules, origin, method, &request_headers) {
+        insert(
+            &mut response_headers,
+            header::ACCESS_CONTROL_ALLOW_ORIGIN,
+            origin,
+        );
+        insert(
+            &mut response_headers,
+            header::ACCESS_CONTROL_ALLOW_METHODS,
+            &rule.methods.join(", "),
+        );
+        if !request_headers.is_empty() {
+            insert(
+                &mut response_headers,
+                header::ACCESS_CONTROL_ALLOW_HEADERS,
+                &request_headers.join(", "),
+            );
+        }
+        if let Some(max_age) = rule.max_age_seconds {
+            insert(
+                &mut response_headers,
+                header::ACCESS_CONTROL_MAX_AGE,
+                &max_age.to_string(),
+            );
+        }
+        response_headers.insert(header::VARY, HeaderValue::from_static("Origin"));
+    }
+    (StatusCode::OK, response_headers).into_response()
+}
+
+fn header_str<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers
+        .get(name)
+        .and_then(|v| v.to_str().ok())
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+}
+
+fn insert(headers: &mut HeaderMap, name: header::HeaderName, value: &str) {
+    if let Ok(value) = HeaderValue::from_str(value) {
+        headers.insert(name, value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::Body,
+        extract::Request,
+        http::{header, HeaderMap, Method, StatusCode},
+        Extension,
+    };
+    use googletest::prelude::*;
+    use tower::ServiceExt;
+
+    use crate::{
+        api::{handlers::context::AuthMode, routes::routes},
+        storage::{cors::CorsRule, BucketStorageExt, CreateBucketAttr, Storage},
+    };
+
+    async fn storage_with_bucket() -> Storage {
+        let storage = Storage::default();
+        let attr = CreateBucketAttr {
+            project: "test-project".into(),
+            cors: vec![CorsRule {
+                origins: vec!["https://example.com".into()],
+                methods: vec!["GET".into(), "PUT".into()],
+                response_headers: vec!["Content-Type".into()],
+                max_age_seconds: Some(3600),
+            }],
+            ..Default::default()
+        };
+        let _ = storage.create("test-bucket", attr).await;
+        storage
+    }
+
+    async fn send(storage: &Storage, method: Method, origin: &str) -> (StatusCode, HeaderMap) {
+        let request = Request::builder()
+            .method(method)
+            .uri("/test-bucket/a.txt")
+            .header(header::ORIGIN, origin)
+            .header(header::ACCESS_CONTROL_REQUEST_METHOD, "PUT")
+            .header(header::ACCESS_CONTROL_REQUEST_HEADERS, "content-type")
+            .body(Body::empty())
+            .unwrap();
+        let res = routes(storage)
+            .layer(Extension(AuthMode::default()))
+            .with_state(storage.clone())
+            .oneshot(request)
+            .await
+            .unwrap();
+        (res.status(), res.headers().clone())
+    }
+
+    #[googletest::test]
+    #[tokio::test]
+    async fn allow_preflight_matching_rule() {
+        // Arrange
+        let storage = storage_with_bucket().await;
+
+        // Act
+        let (status, headers) = send(&storage, Method::OPTIONS, "https://example.com").await;
+        let (_, actual) = send(&storage, Method::GET, "https://example.com").await;
+
+        // Assert
+        expect_that!(status, eq(StatusCode::OK));
+        expect_that!(
+            headers[header::ACCESS_CONTROL_ALLOW_ORIGIN],
+            eq("https://example.com")
+        );
+        expect_that!(
+            headers[header::ACCESS_CONTROL_ALLOW_METHODS],
+            eq("GET, PUT")
+        );
+        expect_that!(
+            headers[header::ACCESS_CONTROL_ALLOW_HEADERS],
+            eq("content-type")
+        );
+        expect_that!(headers[header::ACCESS_CONTROL_MAX_AGE], eq("3600"));
+        assert_that!(
+            actual[header::ACCESS_CONTROL_EXPOSE_HEADERS],
+            eq("Content-Type")
+        );
+    }
+
+    #[googletest::test]
+    #[tokio::test]
+    async fn leave_out_cors_headers_for_other_origin() {
+        // Arrange
+        let storage = storage_with_bucket().await;
+
+        // Act
+        let (status, headers) = send(&storage, Method::OPTIONS, "https://other.example").await;
+        let (_, actual) = send(&storage, Method::GET, "https://other.example").await;
+
+        // Assert
+        expect_that!(status, eq(StatusCode::OK));
+        expect_that!(headers.get(header::ACCESS_CONTROL_ALLOW_ORIGIN), none());
+        assert_that!(actual.get(header::ACCESS_CONTROL_ALLOW_ORIGIN), none());
+    }
+}
@@ -0,0 +1,24 @@
+use axum::{
+    extract::{Query, State},
+    response::sse::{Event, KeepAlive, Sse},
+};
+use futures_util::{Stream, StreamExt};
+use tracing::instrument;
+
+use crate::{
+    api::models::event::{EventParams, EventResponse},
+    flows::event::subscribe,
+    storage::Storage,
+};
+
+#[instrument(skip(storage))]
+pub async fn stream_events(
+    State(storage): State<Storage>,
+    Query(params): Query<EventParams>,
+) -> Sse<impl Stream<Item = Result<Event, axum::Error>>> {
+    let events = subscribe(storage, params.into()).map(|event| {
+        let event = EventResponse::from(event);
+        Event::default().event(event.name()).json_data(event)
+    });
+    Sse::new(events).keep_alive(KeepAlive::default())
+}
@@ -1,3 +1,8 @@
+pub mod clock;
 pub mod context;
+pub mod cors;
+pub mod event;
 pub mod health;
+pub mod notification;
 pub mod storage;
+pub mod xml;
@@ -0,0 +1,31 @@
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    Json,
+};
+use tracing::instrument;
+
+use crate::{
+    api::models::notification::{PublishedNotificationList, PublishedNotificationParams},
+    flows::notification::{clear_published, list_published},
+    libs::errors::{AppResult, Errors},
+    storage::Storage,
+};
+
+#[instrument(skip(storage))]
+pub async fn list_published_notifications(
+    State(storage): State<Storage>,
+    Query(params): Query<PublishedNotificationParams>,
+) -> AppResult<Json<PublishedNotificationList>, Errors> {
+    list_published(storage, params.topic).await.map(|n| {
+        Json(PublishedNotificationList {
+            notifications: n.into_iter().map(|n| n.into()).collect(),
+        })
+    })
+}
+
+#[instrument(skip(storage))]
+pub async fn clear_published_notifications(State(storage): State<Storage>) -> StatusCode {
+    clear_published(storage).await;
+    StatusCode::NO_CONTENT
+}
@@ -0,0 +1,323 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use axum_garde::WithValidation;
+use tracing::instrument;
+
+use crate::{
+    api::{
+        handlers::context::Caller,
+        models::{
+            acl::{
+                AccessControlResponse, InsertAccessControl, ObjectAccessControlParams,
+                UpdateAccessControl,
+            },
+            ListResponse,
+        },
+    },
+    flows::acl::{create_new_acl, delete_acl, find_acl, list, update_existing_acl},
+    libs::errors::{AppResult, Errors},
+    storage::{acl::AclTarget, Storage},
+};
+
+type AclResult = AppResult<Json<AccessControlResponse>, Errors>;
+type AclListResult = AppResult<Json<ListResponse<AccessControlResponse>>, Errors>;
+
+/// Checks the permission to read or modify the access control list.
+async fn authorize(
+    caller: &Caller,
+    storage: &Storage,
+    bucket: &str,
+    target: &AclTarget,
+    modify: bool,
+) -> AppResult<(), Errors> {
+    let (object, permission) = match (target, modify) {
+        (AclTarget::Bucket, false) => (None, "storage.buckets.getIamPolicy"),
+        (AclTarget::Bucket, true) => (None, "storage.buckets.setIamPolicy"),
+        (AclTarget::DefaultObject, false) => (None, "storage.buckets.get"),
+        (AclTarget::DefaultObject, true) => (None, "storage.buckets.update"),
+        (AclTarget::Object { name, .. }, false) => {
+            (Some(name.as_str()), "storage.objects.getIamPolicy")
+        }
+        (AclTarget::Object { name, .. }, true) => {
+            (Some(name.as_str()), "storage.objects.setIamPolicy")
+        }
+    };
+    caller.authorize(storage, bucket, object, permission).await
+}
+
+fn object_target(object: String, params: ObjectAccessControlParams) -> AclTarget {
+    AclTarget::Object {
+        name: object,
+        generation: params.generation,
+    }
+}
+
+async fn list_entries(
+    caller: Caller,
+    storage: Storage,
+    bucket: String,
+    target: AclTarget,
+) -> AclListResult {
+    authorize(&caller, &storage, &bucket, &target, false).await?;
+    list(storage, bucket.clone(), target.clone())
+        .await
+        .map(|acl| AccessControlResponse::list(&bucket, &target, acl))
+        .map(Json)
+}
+
+async fn get_entry(
+    caller: Caller,
+    storage: Storage,
+    bucket: String,
+    target: AclTarget,
+    entity: String,
+) -> AclResult {
+    authorize(&caller, &storage, &bucket, &target, false).await?;
+    find_acl(storage, bucket.clone(), target.clone(), entity)
+        .await
+        .map(|acl| AccessControlResponse::new(&bucket, &target, acl))
+        .map(Json)
+}
+
+async fn insert_entry(
+    caller: Caller,
+    storage: Storage,
+    bucket: String,
+    target: AclTarget,
+    req: InsertAccessControl,
+) -> AclResult {
+    authorize(&caller, &storage, &bucket, &target, true).await?;
+    create_new_acl(storage, bucket.clone(), target.clone(), req)
+        .await
+        .map(|acl| AccessControlResponse::new(&bucket, &target, acl))
+        .map(Json)
+}
+
+async fn update_entry(
+    caller: Caller,
+    storage: Storage,
+    bucket: String,
+    target: AclTarget,
+    entity: String,
+    req: UpdateAccessControl,
+) -> AclResult {
+    authorize(&caller, &storage, &bucket, &target, true).await?;
+    update_existing_acl(storage, bucket.clone(), target.clone(), entity, req)
+        .await
+        .map(|acl| AccessControlResponse::new(&bucket, &target, acl))
+        .map(Json)
+}
+
+async fn delete_entry(
+    caller: Caller,
+    storage: Storage,
+    bucket: String,
+    target: AclTarget,
+    entity: String,
+) -> AppResult<StatusCode, Errors> {
+    authorize(&caller, &storage, &bucket, &target, true).await?;
+    delete_acl(storage, bucket, target, entity)
+        .await
+        .map(|_| StatusCode::NO_CONTENT)
+}
+
+#[instrument(skip(storage))]
+pub async fn list_bucket_acl(
+    State(storage): State<Storage>,
+    caller: Caller,
+    Path(bucket): Path<String>,
+) -> AclListResult {
+    list_entries(caller, storage, bucket, AclTarget::Bucket).await
+}
+
+#[instrument(skip(storage))]
+pub async fn get_bucket_acl(
+    State(storage): State<Storage>,
+    caller: Caller,
+    Path((bucket, entity)): Path<(String, String)>,
+) -> AclResult {
+    get_entry(caller, storage, bucket, AclTarget::Bucket, entity).await
+}
+
+#[instrument(skip(storage))]
+pub async fn insert_bucket_acl(
+    State(storage): State<Storage>,
+    caller: Caller,
+    Path(bucket): Path<String>,
+    WithValidation(req): WithValidation<Json<InsertAccessControl>>,
+) -> AclResult {
+    insert_entry(caller, storage, bucket, AclTarget::Bucket, req.into_inner()).await
+}
+
+#[instrument(skip(storage))]
+pub async fn update_bucket_acl(
+    State(storage): State<Storage>,
+    caller: Caller,
+    Path((bucket, entity)): Path<(String, String)>,
+    WithValidation(req): WithValidation<Json<UpdateAccessControl>>,
+) -> AclResult {
+    update_entry(
+        caller,
+        storage,
+        bucket,
+        AclTarget::Bucket,
+        entity,
+        req.into_inner(),
+    )
+    .await
+}
+
+#[instrument(skip(storage))]
+pub async fn delete_bucket_acl(
+    State(storage): State<Storage>,
+    caller: Caller,
+    Path((bucket, entity)): Path<(String, String)>,
+) -> AppResult<StatusCode, Errors> {
+    delete_entry(caller, storage, bucket, AclTarget::Bucket, entity).await
+}
+
+#[instrument(skip(storage))]
+pub async fn list_default_object_acl(
+    State(storage): State<Storage>,
+    caller: Caller,
+    Path(bucket): Path<String>,
+) -> AclListResult {
+    list_entries(caller, storage, bucket, AclTarget::DefaultObject).await
+}
+
+#[instrument(skip(storage))]
+pub async fn get_default_object_acl(
+    State(storage): State<Storage>,
+    caller: Caller,
+    Path((bucket, entity)): Path<(String, String)>,
+) -> AclResult {
+    get_entry(caller, storage, bucket, AclTarget::DefaultObject, entity).await
+}
+
+#[instrument(skip(storage))]
+pub async fn insert_default_object_acl(
+    State(storage): State<Storage>,
+    caller: Caller,
+    Path(bucket): Path<String>,
+    WithValidation(req): WithValidation<Json<InsertAccessControl>>,
+) -> AclResult {
+    insert_entry(
+        caller,
+        storage,
+        bucket,
+        AclTarget::DefaultObject,
+        req.into_inner(),
+    )
+    .await
+}
+
+#[instrument(skip(storage))]
+pub async fn update_default_object_acl(
+    State(storage): State<Storage>,
+    caller: Caller,
+    Path((bucket, entity)): Path<(String, String)>,
+    WithValidation(req): WithValidation<Json<UpdateAccessControl>>,
+) -> AclResult {
+    update_entry(
+        caller,
+        storage,
+        bucket,
+        AclTarget::DefaultObject,
+        entity,
+        req.into_inner(),
+    )
+    .await
+}
+
+#[instrument(skip(storage))]
+pub async fn delete_default_object_acl(
+    State(storage): State<Storage>,
+    caller: Caller,
+    Path((bucket, entity)): Path<(String, String)>,
+) -> AppResult<StatusCode, Errors> {
+    delete_entry(caller, storage, bucket, AclTarget::DefaultObject, entity).await
+}
+
+#[instrument(skip(storage))]
+pub async fn list_object_acl(
+    State(storage): State<Storage>,
+    caller: Caller,
+    Path((bucket, object)): Path<(String, String)>,
+    Query(params): Query<ObjectAccessControlParams>,
+) -> AclListResult {
+    list_entries(caller, storage, bucket, object_target(object, params)).await
+}
+
+#[instrument(skip(storage))]
+pub async fn get_object_acl(
+    State(storage): State<Storage>,
+    caller: Caller,
+    Path((bucket, object, entity)): Path<(String, String, String)>,
+    Query(params): Query<ObjectAccessControlParams>,
+) -> AclResult {
+    get_entry(
+        caller,
+        storage,
+        bucket,
+        object_target(object, params),
+        entity,
+    )
+    .await
+}
+
+#[instrument(skip(storage))]
+pub async fn insert_object_acl(
+    State(storage): State<Storage>,
+    caller: Caller,
+    Path((bucket, object)): Path<(String, String)>,
+    Query(params): Query<ObjectAccessControlParams>,
+    WithValidation(req): WithValidation<Json<InsertAccessControl>>,
+) -> AclResult {
+    insert_entry(
+        caller,
+        storage,
+        bucket,
+        object_target(object, params),
+        req.into_inner(),
+    )
+    .await
+}
+
+#[instrument(skip(storage))]
+pub async fn update_object_acl(
+    State(storage): State<Storage>,
+    caller: Caller,
+    Path((bucket, object, entity)): Path<(String, String, String)>,
+    Query(params): Query<ObjectAccessControlParams>,
+    WithValidation(req): WithValidation<Json<UpdateAccessControl>>,
+) -> AclResult {
+    update_entry(
+        caller,
+        storage,
+        bucket,
+        object_target(object, params),
+        entity,
+        req.into_inner(),
+    )
+    .await
+}
+
+#[instrument(skip(storage))]
+pub async fn delete_object_acl(
+    State(storage): State<Storage>,
+    caller: Caller,
+    Path((bucket, object, entity)): Path<(String, String, String)>,
+    Query(params): Query<ObjectAccessControlParams>,
+) -> AppResult<StatusCode, Errors> {
+    delete_entry(
+        caller,
+        storage,
+        bucket,
+        object_target(object, params),
+        entity,
+    )
+    .await
+}
@@ -1,5 +1,3 @@
-use std::convert::Infallible;
-
 use axum::{
     extract::{Path, Query, State},
     Json,
@@ -8,15 +6,21 @@ use axum_garde::WithValidation;
 use tracing::instrument;
 
 use crate::{
-    api::models::{
-        bucket::{
-            BucketResponse, DeleteBucketParams, GetBucketParams, InsertBucket, InsertBucketParams,
-            UpdateBucket, UpdateBucketParams,
+    api::{
+        handlers::context::Caller,
+        models::{
+            bucket::{
+                BucketResponse, DeleteBucketParams, GetBucketParams, InsertBucket,
+                InsertBucketParams, ListBucketsParams, LockRetentionPolicyParams, PatchBucket,
+                RestoreBucketParams, UpdateBucket, UpdateBucketParams,
+            },
+            ListResponse, Projection,
         },
-        ListResponse,
     },
     flows::bucket::{
-        create_new_bucket, delete_bucket as delete, find_bucket, list, update_existing_bucket,
+        create_new_bucket, delete_bucket as delete, find_bucket, find_soft_deleted_bucket, list,
+        list_soft_deleted, lock_retention_policy as lock, patch_existing_bucket,
+        restore_bucket as restore, update_existing_bucket,
     },
     libs::errors::{AppResult, Errors},
     storage::Storage,
@@ -25,8 +29,20 @@ use crate::{
 #[instrument(skip(storage))]
 pub async fn list_buckets(
     State(storage): State<Storage>,
-) -> AppResult<Json<ListResponse<BucketResponse>>, Infallible> {
-    list(storage).await.map(ListResponse::from).map(Json)
+    Query(params): Query<ListBucketsParams>,
+    caller: Caller,
+) -> AppResult<Json<ListResponse<BucketResponse>>, Errors> {
+    caller.authorize_project("storage.buckets.list")?;
+    let projection = params.projection.unwrap_or(Projection::NoAcl);
+    let buckets = if params.soft_deleted.unwrap_or_default() {
+        list_soft_deleted(storage, params.project).await
+    } else {
+        list(storage, params.project).await
+    };
+    buckets
+        .map(ListResponse::from)
+        .map(|res| res.map_items(|b| b.with_projection(projection)))
+        .map(Json)
 }
 
 #[instrument(skip(storage))]
@@ -34,22 +50,41 @@ pub async fn get_bucket(
     Path(bucket): Path<String>,
     State(storage): State<Storage>,
     Query(params): Query<GetBucketParams>,
-) -> AppResult<Json<Option<BucketResponse>>, Infallible> {
-    find_bucket(storage, bucket)
-        .await
-        .map(|result| result.map(BucketResponse::from))
+    caller: Caller,
+) -> AppResult<Json<Option<BucketResponse>>, Errors> {
+    caller
+        .authorize(&storage, &bucket, None, "storage.buckets.get")
+        .await?;
+    let projection = params.projection.unwrap_or(Projection::NoAcl);
+    let found = if params.soft_deleted.unwrap_or_default() {
+        let generation = params.generation.ok_or_else(|| Errors::BadRequest {
+            message: "generation is required to get a soft-deleted bucket".into(),
+        })?;
+        find_soft_deleted_bucket(storage, bucket, generation)
+            .await
+            .map(Some)
+    } else {
+        find_bucket(storage, bucket).await
+    };
+    found
+        .map(|result| result.map(|b| BucketResponse::from(b).with_projection(projection)))
         .map(Json)
 }
 
 #[instrument(skip(storage))]
 pub async fn insert_bucket(
     State(storage): State<Storage>,
-    Query(_params): Query<InsertBucketParams>,
+    Query(params): Query<InsertBucketParams>,
+    caller: Caller,
     WithValidation(req): WithValidation<Json<InsertBucket>>,
 ) -> AppResult<Json<BucketResponse>, Errors> {
-    create_new_bucket(storage, req.into_inner())
+    caller.authorize_project("storage.buckets.create")?;
+    let req = req.into_inner();
+    let projection = params.projection(&req);
+    create_new_bucket(storage, params, req)
         .await
         .map(BucketResponse::from)
+        .map(|res| res.with_projection(projection))
         .map(Json)
 }
 
@@ -57,12 +92,71 @@ pub async fn insert_bucket(
 pub async fn update_bucket(
     State(storage): State<Storage>,
     Path(bucket): Path<String>,
-    Query(_params): Query<UpdateBucketParams>,
+    Query(params): Query<UpdateBucketParams>,
+    caller: Caller,
     WithValidation(req): WithValidation<Json<UpdateBucket>>,
 ) -> AppResult<Json<BucketResponse>, Errors> {
-    update_existing_bucket(storage, bucket, req.into_inner())
+    caller
+        .authorize(&storage, &bucket, None, "storage.buckets.update")
+        .await?;
+    let projection = params.projection.unwrap_or(Projection::Full);
+    update_existing_bucket(storage, bucket, params, req.into_inner())
         .await
         .map(BucketResponse::from)
+        .map(|res| res.with_projection(projection))
+        .map(Json)
+}
+
+#[instrument(skip(storage))]
+pub async fn patch_bucket(
+    State(storage): State<Storage>,
+    Path(bucket): Path<String>,
+    Query(params): Query<UpdateBucketParams>,
+    caller: Caller,
+    WithValidation(req): WithValidation<Json<PatchBucket>>,
+) -> AppResult<Json<BucketResponse>, Errors> {
+    caller
+        .authorize(&storage, &bucket, None, "storage.buckets.update")
+        .await?;
+    let projection = params.projection.unwrap_or(Projection::Full);
+    patch_existing_bucket(storage, bucket, params, req.into_inner())
+        .await
+        .map(BucketResponse::from)
+        .map(|res| res.with_projection(projection))
+        .map(Json)
+}
+
+#[instrument(skip(storage))]
+pub async fn lock_retention_policy(
+    State(storage): State<Storage>,
+    Path(bucket): Path<String>,
+    Query(params): Query<LockRetentionPolicyParams>,
+    caller: Caller,
+) -> AppResult<Json<BucketResponse>, Errors> {
+    caller
+        .authorize(&storage, &bucket, None, "storage.buckets.update")
+        .await?;
+    lock(storage, bucket, params)
+        .await
+        .map(BucketResponse::from)
+        .map(|res| res.with_projection(Projection::NoAcl))
+        .map(Json)
+}
+
+/// The bucket is soft-deleted, so only a project level permission is checked.
+#[instrument(skip(storage))]
+pub async fn restore_bucket(
+    State(storage): State<Storage>,
+    Path(bucket): Path<String>,
+    Query(params): Query<RestoreBucketParams>,
+    caller: Caller,
+) -> AppResult<Json<BucketResponse>, Errors> {
+    caller.authorize_project("storage.buckets.restore")?;
+    let projection = params.projection.unwrap_or(Projection::NoAcl);
+    restore(storage, bucket, params.generation)
+        .await
+        .map(BucketResponse::from)
+        .map(|res| res.with_projection(projection))
         .map(Json)
 }
 
@@ -71,9 +165,91 @@ pub async fn delete_bucket(
     State(storage): State<Storage>,
     Path(bucket): Path<String>,
     Query(_params): Query<DeleteBucketParams>,
+    caller: Caller,
 ) -> AppResult<Json<BucketResponse>, Errors> {
+    caller
+        .authorize(&storage, &bucket, None, "storage.buckets.delete")
+        .await?;
     delete(storage, bucket)
         .await
         .map(BucketResponse::from)
+        .map(|res| res.with_projection(Projection::NoAcl))
         .map(Json)
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use axum::{
+        body::Body,
+        http::{header, Method, Request, StatusCode},
+        Extension,
+    };
+    use googletest::prelude::*;
+    use rstest::rstest;
+    use tower::ServiceExt;
+
+    use crate::{
+        api::{handlers::context::AuthMode, routes::routes},
+        storage::{BucketStorageExt, CreateBucketAttr, Storage},
+    };
+
+    #[rstest]
+    #[case::patch_null_labels(Method::PATCH, r#"{"labels":null}"#, true, &[])]
+    #[case::patch_without_labels(
+        Method::PATCH,
+        r#"{"defaultEventBasedHold":true}"#,
+        true,
+        &[("a", "1"), ("b", "2")]
+    )]
+    #[case::patch_merged_labels(
+        Method::PATCH,
+        r#"{"labels":{"a":null,"c":"3"}}"#,
+        true,
+        &[("b", "2"), ("c", "3")]
+    )]
+    #[case::put_without_versioning(Method::PUT, r#"{"labels":{"a":"1"}}"#, false, &[("a", "1")])]
+    #[googletest::test]
+    #[tokio::test]
+    async fn update_labels_and_versioning(
+        #[case] method: Method,
+        #[case] body: &'static str,
+        #[case] versioning: bool,
+        #[case] labels: &[(&str, &str)],
+    ) {
+        // Arrange
+        let storage = Storage::default();
+        let attr = CreateBucketAttr {
+            project: "test-project".into(),
+            versioning: true,
+            labels: HashMap::from([("a".into(), "1".into()), ("b".into(), "2".into())]),
+            ..Default::default()
+        };
+        let _ = storage.create("test_bucket", attr).await;
+        let request = Request::builder()
+            .method(method)
+            .uri("/storage/v1/b/test_bucket")
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body))
+            .unwrap();
+
+        // Act
+        let res = routes(&storage)
+            .layer(Extension(AuthMode { enforce: false }))
+            .with_state(storage.clone())
+            .oneshot(request)
+            .await
+            .unwrap();
+        let bucket = storage.get("test_bucket").await.unwrap();
+
+        // Assert
+        expect_that!(res.status(), eq(StatusCode::OK));
+        expect_that!(bucket.versioning, eq(versioning));
+        let expected: HashMap<String, String> = labels
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        expect_that!(bucket.labels, eq(&expected));
+    }
+}
@@ -0,0 +1,46 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use axum_garde::WithValidation;
+use tracing::instrument;
+
+use crate::{
+    api::{
+        handlers::context::Caller,
+        models::channel::{ChannelResponse, StopChannel, WatchAllObjectsParams, WatchChannel},
+    },
+    flows::channel::{stop, watch_all},
+    libs::errors::{AppResult, Errors},
+    storage::Storage,
+};
+
+#[instrument(skip(storage))]
+pub async fn watch_all_objects(
+    State(storage): State<Storage>,
+    caller: Caller,
+    Path(bucket): Path<String>,
+    Query(params): Query<WatchAllObjectsParams>,
+    WithValidation(req): WithValidation<Json<WatchChannel>>,
+) -> AppResult<Json<ChannelResponse>, Errors> {
+    caller
+        .authorize(&storage, &bucket, None, "storage.objects.list")
+        .await?;
+    watch_all(storage, bucket, params, req.into_inner())
+        .await
+        .map(|c| Json(c.into()))
+}
+
+#[instrument(skip(storage))]
+pub async fn stop_channel(
+    State(storage): State<Storage>,
+    caller: Caller,
+    WithValidation(req): WithValidation<Json<StopChannel>>,
+) -> AppResult<StatusCode, Errors> {
+    // The channel is identified by the opaque resource ID, so knowing it is enough to stop the channel.
+    caller.authorize_project("storage.objects.list")?;
+    stop(storage, req.into_inner())
+        .await
+        .map(|_| StatusCode::NO_CONTENT)
+}
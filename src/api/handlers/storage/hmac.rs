@@ -0,0 +1,86 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use axum_garde::WithValidation;
+use tracing::instrument;
+
+use crate::{
+    api::{
+        handlers::context::Caller,
+        models::{
+            hmac::{
+                CreateHmacKeyParams, HmacKeyMetadataResponse, HmacKeyResponse, ListHmacKeysParams,
+                UpdateHmacKey,
+            },
+            ListResponse,
+        },
+    },
+    flows::hmac::{
+        create, delete_hmac_key as delete, find_hmac_key, list, update_existing_hmac_key,
+    },
+    libs::errors::{AppResult, Errors},
+    storage::Storage,
+};
+
+#[instrument(skip(storage))]
+pub async fn create_hmac_key(
+    State(storage): State<Storage>,
+    caller: Caller,
+    Path(project): Path<String>,
+    Query(params): Query<CreateHmacKeyParams>,
+) -> AppResult<Json<HmacKeyResponse>, Errors> {
+    caller.authorize_project("storage.hmacKeys.create")?;
+    create(storage, project, params)
+        .await
+        .map(|k| Json(k.into()))
+}
+
+#[instrument(skip(storage))]
+pub async fn list_hmac_keys(
+    State(storage): State<Storage>,
+    caller: Caller,
+    Path(project): Path<String>,
+    Query(params): Query<ListHmacKeysParams>,
+) -> AppResult<Json<ListResponse<HmacKeyMetadataResponse>>, Errors> {
+    caller.authorize_project("storage.hmacKeys.list")?;
+    Ok(Json(list(storage, project, params).await.into()))
+}
+
+#[instrument(skip(storage))]
+pub async fn get_hmac_key(
+    State(storage): State<Storage>,
+    caller: Caller,
+    Path((project, access_id)): Path<(String, String)>,
+) -> AppResult<Json<HmacKeyMetadataResponse>, Errors> {
+    caller.authorize_project("storage.hmacKeys.get")?;
+    find_hmac_key(storage, project, access_id)
+        .await
+        .map(|k| Json(k.into()))
+}
+
+#[instrument(skip(storage))]
+pub async fn update_hmac_key(
+    State(storage): State<Storage>,
+    caller: Caller,
+    Path((project, access_id)): Path<(String, String)>,
+    WithValidation(req): WithValidation<Json<UpdateHmacKey>>,
+) -> AppResult<Json<HmacKeyMetadataResponse>, Errors> {
+    caller.authorize_project("storage.hmacKeys.update")?;
+    update_existing_hmac_key(storage, project, access_id, req.into_inner())
+        .await
+        .map(|k| Json(k.into()))
+}
+
+#[instrument(skip(storage))]
+pub async fn delete_hmac_key(
+    State(storage): State<Storage>,
+    caller: Caller,
+    Path((project, access_id)): Path<(String, String)>,
+) -> AppResult<StatusCode, Errors> {
+    caller.authorize_project("storage.hmacKeys.delete")?;
+    delete(storage, project, access_id)
+        .await
+        .map(|_| StatusCode::NO_CONTENT)
+}
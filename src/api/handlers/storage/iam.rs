@@ -0,0 +1,69 @@
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use axum_garde::WithValidation;
+use tracing::instrument;
+
+use crate::{
+    api::{
+        handlers::context::Caller,
+        models::{
+            iam::{
+                PolicyResponse, SetIamPolicy, TestIamPermissionsParams, TestIamPermissionsResponse,
+            },
+            Kind,
+        },
+    },
+    flows::iam::{find_policy, replace_policy, test_permissions},
+    libs::errors::{AppResult, Errors},
+    storage::Storage,
+};
+
+#[instrument(skip(storage))]
+pub async fn get_iam_policy(
+    State(storage): State<Storage>,
+    Path(bucket): Path<String>,
+    caller: Caller,
+) -> AppResult<Json<PolicyResponse>, Errors> {
+    caller
+        .authorize(&storage, &bucket, None, "storage.buckets.getIamPolicy")
+        .await?;
+    find_policy(storage, bucket.clone())
+        .await
+        .map(|policy| PolicyResponse::new(&bucket, policy))
+        .map(Json)
+}
+
+#[instrument(skip(storage))]
+pub async fn set_iam_policy(
+    State(storage): State<Storage>,
+    Path(bucket): Path<String>,
+    caller: Caller,
+    WithValidation(req): WithValidation<Json<SetIamPolicy>>,
+) -> AppResult<Json<PolicyResponse>, Errors> {
+    caller
+        .authorize(&storage, &bucket, None, "storage.buckets.setIamPolicy")
+        .await?;
+    replace_policy(storage, bucket.clone(), req.into_inner())
+        .await
+        .map(|policy| PolicyResponse::new(&bucket, policy))
+        .map(Json)
+}
+
+#[instrument(skip(storage))]
+pub async fn test_iam_permissions(
+    State(storage): State<Storage>,
+    Path(bucket): Path<String>,
+    caller: Caller,
+    Query(query): Query<Vec<(String, String)>>,
+) -> AppResult<Json<TestIamPermissionsResponse>, Errors> {
+    let params = TestIamPermissionsParams::from(query);
+    test_permissions(storage, bucket, caller.principal, params.permissions)
+        .await
+        .map(|permissions| TestIamPermissionsResponse {
+            kind: Kind::TestIamPermissionsResponse,
+            permissions,
+        })
+        .map(Json)
+}
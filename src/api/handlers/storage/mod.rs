@@ -1 +1,7 @@
+pub mod acl;
 pub mod bucket;
+pub mod channel;
+pub mod hmac;
+pub mod iam;
+pub mod notification;
+pub mod object;
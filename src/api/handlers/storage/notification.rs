@@ -0,0 +1,77 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use axum_garde::WithValidation;
+use tracing::instrument;
+
+use crate::{
+    api::{
+        handlers::context::Caller,
+        models::{
+            notification::{InsertNotification, NotificationResponse},
+            ListResponse,
+        },
+    },
+    flows::notification::{
+        create_new_notification_config, delete_notification_config, find_notification_config, list,
+    },
+    libs::errors::{AppResult, Errors},
+    storage::Storage,
+};
+
+#[instrument(skip(storage))]
+pub async fn list_notifications(
+    State(storage): State<Storage>,
+    caller: Caller,
+    Path(bucket): Path<String>,
+) -> AppResult<Json<ListResponse<NotificationResponse>>, Errors> {
+    caller
+        .authorize(&storage, &bucket, None, "storage.buckets.get")
+        .await?;
+    list(storage, bucket).await.map(|c| Json(c.into()))
+}
+
+#[instrument(skip(storage))]
+pub async fn get_notification(
+    State(storage): State<Storage>,
+    caller: Caller,
+    Path((bucket, notification)): Path<(String, String)>,
+) -> AppResult<Json<NotificationResponse>, Errors> {
+    caller
+        .authorize(&storage, &bucket, None, "storage.buckets.get")
+        .await?;
+    find_notification_config(storage, bucket, notification)
+        .await
+        .map(|c| Json(c.into()))
+}
+
+#[instrument(skip(storage))]
+pub async fn insert_notification(
+    State(storage): State<Storage>,
+    caller: Caller,
+    Path(bucket): Path<String>,
+    WithValidation(req): WithValidation<Json<InsertNotification>>,
+) -> AppResult<Json<NotificationResponse>, Errors> {
+    caller
+        .authorize(&storage, &bucket, None, "storage.buckets.update")
+        .await?;
+    create_new_notification_config(storage, bucket, req.into_inner())
+        .await
+        .map(|c| Json(c.into()))
+}
+
+#[instrument(skip(storage))]
+pub async fn delete_notification(
+    State(storage): State<Storage>,
+    caller: Caller,
+    Path((bucket, notification)): Path<(String, String)>,
+) -> AppResult<StatusCode, Errors> {
+    caller
+        .authorize(&storage, &bucket, None, "storage.buckets.update")
+        .await?;
+    delete_notification_config(storage, bucket, notification)
+        .await
+        .map(|_| StatusCode::NO_CONTENT)
+}
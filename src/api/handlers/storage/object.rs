@@ -0,0 +1,490 @@
+use axum::{
+    body::Bytes,
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use axum_garde::WithValidation;
+use tracing::instrument;
+
+use crate::{
+    api::{
+        handlers::context::Caller,
+        models::{
+            object::{
+                copy_source_encryption_key, encryption_key, Alt, DeleteObjectParams,
+                GetObjectParams, InsertObjectParams, ListObjectsParams, ObjectMediaResponse,
+                ObjectResponse, ObjectRewrite, ObjectUpload, PatchObject, RestoreObjectParams,
+                RewriteObjectParams, RewriteResponse, UpdateObject, UpdateObjectParams,
+            },
+            ListResponse, Projection,
+        },
+    },
+    flows::object::{
+        create_new_object, delete_object as delete, find_object, find_soft_deleted_object, list,
+        patch_existing_object, restore_object as restore, rewrite_object as rewrite,
+        update_existing_object,
+    },
+    libs::errors::{AppResult, Errors},
+    storage::{Storage, StorageObjectAttr},
+};
+
+#[instrument(skip(storage))]
+pub async fn list_objects(
+    State(storage): State<Storage>,
+    Path(bucket): Path<String>,
+    Query(params): Query<ListObjectsParams>,
+    caller: Caller,
+) -> AppResult<Json<ListResponse<ObjectResponse>>, Errors> {
+    caller
+        .authorize(&storage, &bucket, None, "storage.objects.list")
+        .await?;
+    let projection = params.projection.unwrap_or(Projection::NoAcl);
+    list(storage, bucket, params.into())
+        .await
+        .map(ListResponse::from)
+        .map(|res| res.map_items(|o| o.with_projection(projection)))
+        .map(Json)
+}
+
+#[instrument(skip(storage, headers))]
+pub async fn get_object(
+    State(storage): State<Storage>,
+    Path((bucket, object)): Path<(String, String)>,
+    Query(params): Query<GetObjectParams>,
+    caller: Caller,
+    headers: HeaderMap,
+) -> AppResult<Response, Errors> {
+    caller
+        .authorize(&storage, &bucket, Some(&object), "storage.objects.get")
+        .await?;
+    let projection = params.projection.unwrap_or(Projection::NoAcl);
+    if params.soft_deleted.unwrap_or_default() {
+        // Only the metadata of soft-deleted objects is available.
+        let generation = match (&params.alt, params.generation) {
+            (Some(Alt::Media), _) => Err(Errors::BadRequest {
+                message: "Soft-deleted objects cannot be downloaded".into(),
+            }),
+            (_, None) => Err(Errors::BadRequest {
+                message: "generation is required to get a soft-deleted object".into(),
+            }),
+            (_, Some(generation)) => Ok(generation),
+        }?;
+        return find_soft_deleted_object(storage, bucket, object, generation)
+            .await
+            .map(|o| Json(ObjectResponse::from(o).with_projection(projection)).into_response());
+    }
+    let object = find_object(
+        storage,
+        bucket,
+        object,
+        params.generation,
+        params.preconditions(),
+    )
+    .await?;
+    match params.alt {
+        Some(Alt::Media) => {
+            let object = object.decrypt(encryption_key(&headers)?.as_ref())?;
+            Ok(ObjectMediaResponse::new(object, &headers).into_response())
+        }
+        Some(Alt::Json) | None => {
+            Ok(Json(ObjectResponse::from(object.attr).with_projection(projection)).into_response())
+        }
+    }
+}
+
+#[instrument(skip(storage, headers))]
+pub async fn download_object(
+    State(storage): State<Storage>,
+    Path((bucket, object)): Path<(String, String)>,
+    Query(params): Query<GetObjectParams>,
+    caller: Caller,
+    headers: HeaderMap,
+) -> AppResult<ObjectMediaResponse, Errors> {
+    caller
+        .authorize(&storage, &bucket, Some(&object), "storage.objects.get")
+        .await?;
+    find_object(
+        storage,
+        bucket,
+        object,
+        params.generation,
+        params.preconditions(),
+    )
+    .await?
+    .decrypt(encryption_key(&headers)?.as_ref())
+    .map(|o| ObjectMediaResponse::new(o, &headers))
+}
+
+#[instrument(skip(storage, headers, body))]
+pub async fn insert_object(
+    State(storage): State<Storage>,
+    Path(bucket): Path<String>,
+    Query(params): Query<InsertObjectParams>,
+    caller: Caller,
+    headers: HeaderMap,
+    body: Bytes,
+) -> AppResult<Json<ObjectResponse>, Errors> {
+    caller
+        .authorize(&storage, &bucket, None, "storage.objects.create")
+        .await?;
+    let upload = ObjectUpload::decode(&params, &headers, body)?;
+    let projection = params.projection(&upload);
+    create_new_object(storage, bucket, upload, params.preconditions())
+        .await
+        .map(ObjectResponse::from)
+        .map(|res| res.with_projection(projection))
+        .map(Json)
+}
+
+/// Copies an object, which is done as `rewrite` is.
+/// https://cloud.google.com/storage/docs/json_api/v1/objects/copy
+#[instrument(skip(storage, headers, body))]
+pub async fn copy_object(
+    State(storage): State<Storage>,
+    Path((source_bucket, source_object, destination_bucket, destination_object)): Path<(
+        String,
+        String,
+        String,
+        String,
+    )>,
+    Query(params): Query<RewriteObjectParams>,
+    caller: Caller,
+    headers: HeaderMap,
+    body: Bytes,
+) -> AppResult<Json<ObjectResponse>, Errors> {
+    let projection = params.projection.unwrap_or(Projection::NoAcl);
+    authorize_and_rewrite(
+        storage,
+        (source_bucket, source_object),
+        (destination_bucket, destination_object),
+        params,
+        caller,
+        &headers,
+        &body,
+    )
+    .await
+    .map(ObjectResponse::from)
+    .map(|res| res.with_projection(projection))
+    .map(Json)
+}
+
+/// Rewrites an object in a single call. The key of the source is given by `x-goog-copy-source-encryption-*`
+/// and the key of the destination by `x-goog-encryption-*`, so that the key can be rotated.
+/// https://cloud.google.com/storage/docs/json_api/v1/objects/rewrite
+#[instrument(skip(storage, headers, body))]
+pub async fn rewrite_object(
+    State(storage): State<Storage>,
+    Path((source_bucket, source_object, destination_bucket, destination_object)): Path<(
+        String,
+        String,
+        String,
+        String,
+    )>,
+    Query(params): Query<RewriteObjectParams>,
+    caller: Caller,
+    headers: HeaderMap,
+    body: Bytes,
+) -> AppResult<Json<RewriteResponse>, Errors> {
+    let projection = params.projection.unwrap_or(Projection::NoAcl);
+    authorize_and_rewrite(
+        storage,
+        (source_bucket, source_object),
+        (destination_bucket, destination_object),
+        params,
+        caller,
+        &headers,
+        &body,
+    )
+    .await
+    .map(ObjectResponse::from)
+    .map(|res| RewriteResponse::from(res.with_projection(projection)))
+    .map(Json)
+}
+
+async fn authorize_and_rewrite(
+    storage: Storage,
+    (source_bucket, source_object): (String, String),
+    (destination_bucket, destination_object): (String, String),
+    params: RewriteObjectParams,
+    caller: Caller,
+    headers: &HeaderMap,
+    body: &[u8],
+) -> AppResult<StorageObjectAttr, Errors> {
+    caller
+        .authorize(
+            &storage,
+            &source_bucket,
+            Some(&source_object),
+            "storage.objects.get",
+        )
+        .await?;
+    caller
+        .authorize(
+            &storage,
+            &destination_bucket,
+            None,
+            "storage.objects.create",
+        )
+        .await?;
+    let destination = ObjectRewrite::decode(destination_object, &params, headers, body)?;
+    rewrite(
+        storage,
+        (source_bucket, source_object),
+        copy_source_encryption_key(headers)?,
+        destination_bucket,
+        destination,
+        params,
+    )
+    .await
+}
+
+#[instrument(skip(storage))]
+pub async fn update_object(
+    State(storage): State<Storage>,
+    Path((bucket, object)): Path<(String, String)>,
+    Query(params): Query<UpdateObjectParams>,
+    caller: Caller,
+    WithValidation(req): WithValidation<Json<UpdateObject>>,
+) -> AppResult<Json<ObjectResponse>, Errors> {
+    caller
+        .authorize(&storage, &bucket, Some(&object), "storage.objects.update")
+        .await?;
+    let projection = params.projection.unwrap_or(Projection::Full);
+    update_existing_object(storage, bucket, object, params, req.into_inner())
+        .await
+        .map(ObjectResponse::from)
+        .map(|res| res.with_projection(projection))
+        .map(Json)
+}
+
+#[instrument(skip(storage))]
+pub async fn patch_object(
+    State(storage): State<Storage>,
+    Path((bucket, object)): Path<(String, String)>,
+    Query(params): Query<UpdateObjectParams>,
+    caller: Caller,
+    WithValidation(req): WithValidation<Json<PatchObject>>,
+) -> AppResult<Json<ObjectResponse>, Errors> {
+    caller
+        .authorize(&storage, &bucket, Some(&object), "storage.objects.update")
+        .await?;
+    let projection = params.projection.unwrap_or(Projection::Full);
+    patch_existing_object(storage, bucket, object, params, req.into_inner())
+        .await
+        .map(ObjectResponse::from)
+        .map(|res| res.with_projection(projection))
+        .map(Json)
+}
+
+#[instrument(skip(storage))]
+pub async fn restore_object(
+    State(storage): State<Storage>,
+    Path((bucket, object)): Path<(String, String)>,
+    Query(params): Query<RestoreObjectParams>,
+    caller: Caller,
+) -> AppResult<Json<ObjectResponse>, Errors> {
+    caller
+        .authorize(&storage, &bucket, Some(&object), "storage.objects.restore")
+        .await?;
+    let projection = params.projection.unwrap_or(Projection::NoAcl);
+    restore(storage, bucket, object, params)
+        .await
+        .map(ObjectResponse::from)
+        .map(|res| res.with_projection(projection))
+        .map(Json)
+}
+
+#[instrument(skip(storage))]
+pub async fn delete_object(
+    State(storage): State<Storage>,
+    Path((bucket, object)): Path<(String, String)>,
+    Query(params): Query<DeleteObjectParams>,
+    caller: Caller,
+) -> AppResult<StatusCode, Errors> {
+    caller
+        .authorize(&storage, &bucket, Some(&object), "storage.objects.delete")
+        .await?;
+    delete(
+        storage,
+        bucket,
+        object,
+        params.generation,
+        params.preconditions(),
+    )
+    .await
+    .map(|_| StatusCode::NO_CONTENT)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use axum::{
+        body::{to_bytes, Body},
+        http::{header, Method, Request, StatusCode},
+        Extension,
+    };
+    use bytes::Bytes;
+    use flate2::{write::GzEncoder, Compression};
+    use googletest::prelude::*;
+    use rstest::rstest;
+    use tower::ServiceExt;
+
+    use crate::{
+        api::{handlers::context::AuthMode, routes::routes},
+        storage::{
+            cors::CorsRule, BucketStorageExt, CreateBucketAttr, CreateObjectAttr, ObjectStorageExt,
+            Preconditions, Storage,
+        },
+    };
+
+    #[rstest]
+    #[case::copy_generation_match(
+        "copyTo",
+        "ifSourceGenerationMatch=0",
+        StatusCode::PRECONDITION_FAILED
+    )]
+    #[case::rewrite_generation_match(
+        "rewriteTo",
+        "ifSourceGenerationMatch=0",
+        StatusCode::PRECONDITION_FAILED
+    )]
+    #[case::rewrite_metageneration_not_match(
+        "rewriteTo",
+        "ifSourceMetagenerationNotMatch=1",
+        StatusCode::PRECONDITION_FAILED
+    )]
+    #[case::rewrite_metageneration_match(
+        "rewriteTo",
+        "ifSourceMetagenerationMatch=1",
+        StatusCode::OK
+    )]
+    #[googletest::test]
+    #[tokio::test]
+    async fn apply_source_preconditions(
+        #[case] method: &str,
+        #[case] query: &str,
+        #[case] expected: StatusCode,
+    ) {
+        // Arrange
+        let storage = Storage::default();
+        let attr = CreateBucketAttr {
+            project: "test-project".into(),
+            ..Default::default()
+        };
+        let _ = storage.create("test_bucket", attr).await;
+        let _ = storage
+            .create_object(
+                "test_bucket",
+                CreateObjectAttr {
+                    name: "a".into(),
+                    ..Default::default()
+                },
+                Bytes::from_static(b"content"),
+                Preconditions::default(),
+            )
+            .await;
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri(format!(
+                "/storage/v1/b/test_bucket/o/a/{method}/b/test_bucket/o/b?{query}"
+            ))
+            .body(Body::empty())
+            .unwrap();
+
+        // Act
+        let res = routes(&storage)
+            .layer(Extension(AuthMode { enforce: false }))
+            .with_state(storage.clone())
+            .oneshot(request)
+            .await
+            .unwrap();
+
+        // Assert
+        expect_that!(res.status(), eq(expected));
+    }
+
+    #[rstest]
+    #[case::accepting_gzip(Some("gzip, deflate"), None, true, Some("gzip"))]
+    #[case::not_accepting_gzip(None, None, false, None)]
+    #[case::no_transform(None, Some("no-transform"), true, Some("gzip"))]
+    #[googletest::test]
+    #[tokio::test]
+    async fn transcode_gzipped_object_on_download(
+        #[case] accept_encoding: Option<&str>,
+        #[case] cache_control: Option<&str>,
+        #[case] gzipped: bool,
+        #[case] content_encoding: Option<&str>,
+    ) {
+        // Arrange
+        let storage = Storage::default();
+        let attr = CreateBucketAttr {
+            project: "test-project".into(),
+            cors: vec![CorsRule {
+                origins: vec!["https://example.com".into()],
+                methods: vec!["GET".into()],
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let _ = storage.create("test_bucket", attr).await;
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(b"hello, world").unwrap();
+        let stored = Bytes::from(encoder.finish().unwrap());
+        let _ = storage
+            .create_object(
+                "test_bucket",
+                CreateObjectAttr {
+                    name: "a".into(),
+                    content_encoding: Some("gzip".into()),
+                    cache_control: cache_control.map(Into::into),
+                    ..Default::default()
+                },
+                stored.clone(),
+                Preconditions::default(),
+            )
+            .await;
+        let mut request = Request::get("/download/storage/v1/b/test_bucket/o/a?alt=media")
+            .header(header::ORIGIN, "https://example.com");
+        if let Some(accept_encoding) = accept_encoding {
+            request = request.header(header::ACCEPT_ENCODING, accept_encoding);
+        }
+
+        // Act
+        let res = routes(&storage)
+            .layer(Extension(AuthMode { enforce: false }))
+            .with_state(storage.clone())
+            .oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let headers = res.headers().clone();
+        let body = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+
+        // Assert
+        expect_that!(
+            headers
+                .get(header::CONTENT_ENCODING)
+                .map(|v| v.to_str().unwrap()),
+            eq(content_encoding)
+        );
+        let expected = if gzipped {
+            stored
+        } else {
+            Bytes::from_static(b"hello, world")
+        };
+        expect_that!(body, eq(&expected));
+        let vary = headers
+            .get_all(header::VARY)
+            .iter()
+            .map(|v| v.to_str().unwrap())
+            .collect::<Vec<_>>();
+        let expected_vary = if cache_control.is_some() {
+            vec!["Origin"]
+        } else {
+            vec!["Accept-Encoding", "Origin"]
+        };
+        assert_that!(vary, eq(&expected_vary));
+    }
+}
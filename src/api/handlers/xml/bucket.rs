@@ -0,0 +1,84 @@
+use axum::{
+    body::Bytes,
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+};
+use tracing::instrument;
+
+use crate::{
+    api::{
+        handlers::context::Caller,
+        models::xml::{
+            project_id, CreateBucketConfiguration, ListAllMyBucketsResult, ListBucketParams,
+            ListBucketResult, ListBucketV2Result, Xml,
+        },
+    },
+    flows::{bucket, object},
+    libs::errors::{AppResult, Errors},
+    storage::Storage,
+};
+
+use super::XmlResult;
+
+#[instrument(skip(storage, headers))]
+pub async fn list_buckets(
+    State(storage): State<Storage>,
+    caller: Caller,
+    headers: HeaderMap,
+) -> XmlResult<Xml<ListAllMyBucketsResult>> {
+    caller.authorize_project("storage.buckets.list")?;
+    let project = project(&caller, &headers)?;
+    let buckets = bucket::list(storage, project.clone()).await?;
+    Ok(Xml(ListAllMyBucketsResult::new(&project, buckets)))
+}
+
+#[instrument(skip(storage, headers, body))]
+pub async fn create_bucket(
+    State(storage): State<Storage>,
+    Path(bucket): Path<String>,
+    caller: Caller,
+    headers: HeaderMap,
+    body: Bytes,
+) -> XmlResult<StatusCode> {
+    caller.authorize_project("storage.buckets.create")?;
+    let attr = CreateBucketConfiguration::parse(&body)?
+        .into_attr(project(&caller, &headers)?, &headers)?;
+    bucket::create_bucket(storage, bucket, attr).await?;
+    Ok(StatusCode::OK)
+}
+
+#[instrument(skip(storage))]
+pub async fn delete_bucket(
+    State(storage): State<Storage>,
+    Path(bucket): Path<String>,
+    caller: Caller,
+) -> XmlResult<StatusCode> {
+    caller
+        .authorize(&storage, &bucket, None, "storage.buckets.delete")
+        .await?;
+    bucket::delete_bucket(storage, bucket).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[instrument(skip(storage))]
+pub async fn list_objects(
+    State(storage): State<Storage>,
+    Path(bucket): Path<String>,
+    Query(params): Query<ListBucketParams>,
+    caller: Caller,
+) -> XmlResult<Response> {
+    caller
+        .authorize(&storage, &bucket, None, "storage.objects.list")
+        .await?;
+    let list = object::list(storage, bucket.clone(), (&params).into()).await?;
+    if params.list_type == Some(2) {
+        return Ok(Xml(ListBucketV2Result::new(&bucket, params, list)?).into_response());
+    }
+    Ok(Xml(ListBucketResult::new(&bucket, params, list)).into_response())
+}
+
+/// Takes the project from `x-goog-project-id`, or else from the HMAC key the request is signed with.
+fn project(caller: &Caller, headers: &HeaderMap) -> AppResult<String, Errors> {
+    project_id(headers).or_else(|e| caller.project.clone().ok_or(e))
+}
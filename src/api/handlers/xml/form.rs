@@ -0,0 +1,161 @@
+use axum::{
+    body::Bytes,
+    extract::{Path, State},
+    http::{header, HeaderMap, HeaderName, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+};
+use reqwest::Url;
+use tracing::instrument;
+
+use crate::{
+    api::{
+        handlers::context::Caller,
+        models::xml::{etag, object_headers, object_upload, PostResponse, Xml},
+    },
+    flows::object::create_new_object,
+    libs::{
+        errors::Errors,
+        multipart::parse_form_data,
+        policy::PolicyDocument,
+        signature::{Credential, SigningScheme},
+    },
+    storage::{Preconditions, Storage},
+};
+
+use super::{signature::verify, XmlResult};
+
+/// Handles `POST /{bucket}` of an HTML form, whose fields are given as `multipart/form-data` followed by the file.
+/// A form with a `policy` is uploaded as the signer once the signature and the conditions of the policy are verified.
+/// https://cloud.google.com/storage/docs/xml-api/post-object-forms
+#[instrument(skip(storage, headers, body))]
+pub async fn post_form(
+    State(storage): State<Storage>,
+    Path(bucket): Path<String>,
+    caller: Caller,
+    headers: HeaderMap,
+    body: Bytes,
+) -> XmlResult<Response> {
+    let content_type = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .filter(|v| v.starts_with("multipart/form-data"))
+        .ok_or_else(|| Errors::BadRequest {
+            message: "POST of a bucket has to be an HTML form in multipart/form-data".into(),
+        })?;
+
+    // Fields after the file are ignored.
+    let mut fields = Vec::new();
+    let mut file = None;
+    for part in parse_form_data(content_type, &body)? {
+        let Some((name, filename)) = part.form_field() else {
+            continue;
+        };
+        if name.eq_ignore_ascii_case("file") {
+            file = Some((part, filename));
+            break;
+        }
+        fields.push((name, String::from_utf8_lossy(&part.body).into_owned()));
+    }
+    let (file, filename) = file.ok_or_else(|| Errors::BadRequest {
+        message: "The file field is required".into(),
+    })?;
+    let field = |name: &str| {
+        fields
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    };
+    let key = field("key")
+        .filter(|k| !k.is_empty())
+        .ok_or_else(|| Errors::BadRequest {
+            message: "The key field is required".into(),
+        })?
+        .replace("${filename}", filename.as_deref().unwrap_or_default());
+
+    let caller = match field("policy") {
+        Some(policy) => {
+            let scheme = ["x-goog", "x-amz"]
+                .into_iter()
+                .find_map(|prefix| {
+                    SigningScheme::from_algorithm(field(&format!("{prefix}-algorithm"))?)
+                        .filter(|s| s.header_prefix == prefix)
+                })
+                .ok_or_else(|| Errors::BadRequest {
+                    message: "x-goog-algorithm is required along with the policy".into(),
+                })?;
+            let prefix = scheme.header_prefix;
+            let credential = field(&format!("{prefix}-credential"))
+                .and_then(Credential::parse)
+                .ok_or_else(|| Errors::BadRequest {
+                    message: format!("{prefix}-credential is required along with the policy"),
+                })?;
+            let signature =
+                field(&format!("{prefix}-signature")).ok_or_else(|| Errors::BadRequest {
+                    message: format!("{prefix}-signature is required along with the policy"),
+                })?;
+            // The policy is signed as it's given, i.e. in base64.
+            let signer = verify(&storage, &scheme, &credential, policy, signature).await?;
+            let mut checked = fields.clone();
+            checked.push(("bucket".to_string(), bucket.clone()));
+            PolicyDocument::decode(policy)?.check(
+                &checked,
+                file.body.len() as u64,
+                storage.clock().now(),
+            )?;
+            caller.signed_by(signer)
+        }
+        None => caller,
+    };
+    caller
+        .authorize(&storage, &bucket, None, "storage.objects.create")
+        .await?;
+
+    // Fields such as `Content-Type` and `x-goog-meta-*` are taken as headers of an upload.
+    let mut upload_headers = HeaderMap::new();
+    if let Some(value) = file
+        .headers
+        .get("content-type")
+        .and_then(|v| HeaderValue::from_str(v).ok())
+    {
+        upload_headers.insert(header::CONTENT_TYPE, value);
+    }
+    for (name, value) in &fields {
+        let name = if name.eq_ignore_ascii_case("acl") {
+            "x-goog-acl"
+        } else {
+            name
+        };
+        if let (Ok(name), Ok(value)) = (
+            HeaderName::from_bytes(name.to_lowercase().as_bytes()),
+            HeaderValue::from_str(value),
+        ) {
+            upload_headers.insert(name, value);
+        }
+    }
+    let upload = object_upload(key, &upload_headers, file.body)?;
+    let object = create_new_object(storage, bucket, upload, Preconditions::default()).await?;
+
+    if let Some(mut url) = field("success_action_redirect").and_then(|u| Url::parse(u).ok()) {
+        url.query_pairs_mut()
+            .append_pair("bucket", &object.bucket_name)
+            .append_pair("key", &object.name)
+            .append_pair("etag", &etag(&object));
+        return Ok((StatusCode::SEE_OTHER, [(header::LOCATION, url.to_string())]).into_response());
+    }
+    match field("success_action_status") {
+        Some("200") => Ok((StatusCode::OK, object_headers(&object)).into_response()),
+        Some("201") => {
+            let host = headers
+                .get(header::HOST)
+                .and_then(|v| v.to_str().ok())
+                .unwrap_or("storage.googleapis.com");
+            Ok((
+                StatusCode::CREATED,
+                object_headers(&object),
+                Xml(PostResponse::new(host, &object)),
+            )
+                .into_response())
+        }
+        _ => Ok((StatusCode::NO_CONTENT, object_headers(&object)).into_response()),
+    }
+}
@@ -0,0 +1,72 @@
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+
+use crate::{
+    api::models::xml::{ErrorResponse, Xml},
+    libs::errors::Errors,
+};
+
+pub mod bucket;
+pub mod form;
+pub mod multipart;
+pub mod object;
+pub mod signature;
+
+/// Wraps errors of handlers in the XML API, whose body is an XML document with a code like `NoSuchKey`.
+/// https://cloud.google.com/storage/docs/xml-api/reference-status
+#[derive(Debug)]
+pub struct XmlError(pub Errors);
+
+impl From<Errors> for XmlError {
+    fn from(value: Errors) -> Self {
+        XmlError(value)
+    }
+}
+
+impl IntoResponse for XmlError {
+    fn into_response(self) -> Response {
+        let (status, code, message) = match self.0 {
+            Errors::AlreadyExists { message } => {
+                (StatusCode::CONFLICT, "BucketAlreadyOwnedByYou", message)
+            }
+            Errors::FailedToWriteStorage { id, message } => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "InternalError",
+                format!("Failed to write {id}: {message}"),
+            ),
+            Errors::BucketNotFound { message } => (StatusCode::NOT_FOUND, "NoSuchBucket", message),
+            Errors::BucketNotEmpty { message } => (StatusCode::CONFLICT, "BucketNotEmpty", message),
+            Errors::ObjectNotFound { message } => (StatusCode::NOT_FOUND, "NoSuchKey", message),
+            Errors::UploadNotFound { message } => (StatusCode::NOT_FOUND, "NoSuchUpload", message),
+            Errors::AccessControlNotFound { message }
+            | Errors::NotificationConfigNotFound { message }
+            | Errors::ChannelNotFound { message }
+            | Errors::HmacKeyNotFound { message } => (StatusCode::NOT_FOUND, "NotFound", message),
+            Errors::Forbidden { message } => (StatusCode::FORBIDDEN, "AccessDenied", message),
+            Errors::InvalidAccessKeyId { message } => {
+                (StatusCode::FORBIDDEN, "InvalidAccessKeyId", message)
+            }
+            Errors::SignatureDoesNotMatch { message } => {
+                (StatusCode::FORBIDDEN, "SignatureDoesNotMatch", message)
+            }
+            Errors::PreconditionFailed { message } => (
+                StatusCode::PRECONDITION_FAILED,
+                "PreconditionFailed",
+                message,
+            ),
+            Errors::BadRequest { message } => (StatusCode::BAD_REQUEST, "InvalidArgument", message),
+        };
+        (
+            status,
+            Xml(ErrorResponse {
+                code: code.to_string(),
+                message,
+            }),
+        )
+            .into_response()
+    }
+}
+
+pub type XmlResult<T> = Result<T, XmlError>;
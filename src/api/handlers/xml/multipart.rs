@@ -0,0 +1,264 @@
+use axum::{
+    body::Bytes,
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+};
+
+use crate::{
+    api::{
+        handlers::context::Caller,
+        models::{
+            object::encryption_key,
+            xml::{
+                object_headers, object_upload, preconditions, CompleteMultipartUpload,
+                CompleteMultipartUploadResult, InitiateMultipartUploadResult, ListPartsResult,
+                ObjectParams, Xml,
+            },
+        },
+    },
+    flows::multipart,
+    storage::Storage,
+};
+
+use super::XmlResult;
+
+/// Handles `POST /{bucket}/{object}?uploads`, taking the metadata of the object from the headers.
+pub async fn initiate(
+    storage: Storage,
+    bucket: String,
+    object: String,
+    caller: Caller,
+    headers: &HeaderMap,
+) -> XmlResult<Response> {
+    caller
+        .authorize(&storage, &bucket, None, "storage.objects.create")
+        .await?;
+    let upload = object_upload(object, headers, Bytes::new())?;
+    let upload = multipart::initiate(storage, bucket, upload).await?;
+    Ok(Xml(InitiateMultipartUploadResult::from(upload)).into_response())
+}
+
+/// Handles `PUT /{bucket}/{object}?partNumber={partNumber}&uploadId={uploadId}`.
+#[allow(clippy::too_many_arguments)]
+pub async fn upload_part(
+    storage: Storage,
+    bucket: String,
+    object: String,
+    upload_id: String,
+    part_number: u32,
+    caller: Caller,
+    headers: &HeaderMap,
+    body: Bytes,
+) -> XmlResult<Response> {
+    caller
+        .authorize(&storage, &bucket, None, "storage.objects.create")
+        .await?;
+    let part = multipart::upload_part(
+        storage,
+        bucket,
+        object,
+        upload_id,
+        part_number,
+        body,
+        encryption_key(headers)?,
+    )
+    .await?;
+    let mut headers = HeaderMap::new();
+    if let Ok(etag) = HeaderValue::from_str(&format!("\"{}\"", part.etag)) {
+        headers.insert(header::ETAG, etag);
+    }
+    Ok(headers.into_response())
+}
+
+/// Handles `GET /{bucket}/{object}?uploadId={uploadId}`.
+pub async fn list_parts(
+    storage: Storage,
+    bucket: String,
+    object: String,
+    params: &ObjectParams,
+    upload_id: String,
+    caller: Caller,
+) -> XmlResult<Response> {
+    caller
+        .authorize(&storage, &bucket, None, "storage.objects.create")
+        .await?;
+    let upload = multipart::list_parts(storage, bucket, object, upload_id).await?;
+    Ok(Xml(ListPartsResult::new(upload, params)).into_response())
+}
+
+/// Handles `POST /{bucket}/{object}?uploadId={uploadId}`, which creates the object from the listed parts.
+pub async fn complete(
+    storage: Storage,
+    bucket: String,
+    object: String,
+    upload_id: String,
+    caller: Caller,
+    headers: &HeaderMap,
+    body: Bytes,
+) -> XmlResult<Response> {
+    caller
+        .authorize(&storage, &bucket, None, "storage.objects.create")
+        .await?;
+    let parts = CompleteMultipartUpload::parse(&body)?;
+    let (object, etag) = multipart::complete(
+        storage,
+        bucket,
+        object,
+        upload_id,
+        parts,
+        preconditions(headers)?,
+        encryption_key(headers)?,
+    )
+    .await?;
+    let host = headers
+        .get(header::HOST)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("storage.googleapis.com");
+    let mut response_headers = object_headers(&object);
+    response_headers.remove(header::ETAG);
+    Ok((
+        response_headers,
+        Xml(CompleteMultipartUploadResult::new(host, &object, etag)),
+    )
+        .into_response())
+}
+
+/// Handles `DELETE /{bucket}/{object}?uploadId={uploadId}`, which discards the uploaded parts.
+pub async fn abort(
+    storage: Storage,
+    bucket: String,
+    object: String,
+    upload_id: String,
+    caller: Caller,
+) -> XmlResult<Response> {
+    caller
+        .authorize(&storage, &bucket, None, "storage.objects.delete")
+        .await?;
+    multipart::abort(storage, bucket, object, upload_id).await?;
+    Ok(StatusCode::NO_CONTENT.into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::{to_bytes, Body},
+        extract::Request,
+        http::{header, Method, StatusCode},
+        response::Response,
+        Extension,
+    };
+    use bytes::Bytes;
+    use googletest::prelude::*;
+    use tower::ServiceExt;
+
+    use crate::{
+        api::{handlers::context::AuthMode, routes::routes},
+        storage::{BucketStorageExt, CreateBucketAttr, ObjectStorageExt, Preconditions, Storage},
+    };
+
+    async fn storage_with_bucket() -> Storage {
+        let storage = Storage::default();
+        let attr = CreateBucketAttr {
+            project: "test-project".into(),
+            ..Default::default()
+        };
+        let _ = storage.create("test-bucket", attr).await;
+        storage
+    }
+
+    async fn send(storage: &Storage, method: Method, uri: &str, body: &str) -> Response {
+        routes(storage)
+            .layer(Extension(AuthMode::default()))
+            .with_state(storage.clone())
+            .oneshot(
+                Request::builder()
+                    .method(method)
+                    .uri(uri)
+                    .body(Body::from(body.to_string()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap()
+    }
+
+    async fn text(res: Response) -> String {
+        let body = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+        String::from_utf8_lossy(&body).into_owned()
+    }
+
+    fn element<'a>(xml: &'a str, name: &str) -> &'a str {
+        let start = xml.find(&format!("<{name}>")).unwrap() + name.len() + 2;
+        let end = xml.find(&format!("</{name}>")).unwrap();
+        &xml[start..end]
+    }
+
+    #[googletest::test]
+    #[tokio::test]
+    async fn create_object_from_uploaded_parts() {
+        // Arrange
+        let storage = storage_with_bucket().await;
+        let initiated = send(&storage, Method::POST, "/test-bucket/a.txt?uploads", "").await;
+        let upload_id = element(&text(initiated).await, "UploadId").to_string();
+
+        // Act
+        let part = send(
+            &storage,
+            Method::PUT,
+            &format!("/test-bucket/a.txt?partNumber=1&uploadId={upload_id}"),
+            "hello",
+        )
+        .await;
+        let etag = part.headers()[header::ETAG].to_str().unwrap().to_string();
+        let completed = send(
+            &storage,
+            Method::POST,
+            &format!("/test-bucket/a.txt?uploadId={upload_id}"),
+            &format!(
+                "<CompleteMultipartUpload><Part><PartNumber>1</PartNumber><ETag>{etag}</ETag></Part></CompleteMultipartUpload>"
+            ),
+        )
+        .await;
+
+        // Assert
+        expect_that!(part.status(), eq(StatusCode::OK));
+        expect_that!(completed.status(), eq(StatusCode::OK));
+        expect_that!(element(&text(completed).await, "Key"), eq("a.txt"));
+        let object = storage
+            .get_object("test-bucket", "a.txt", None, Preconditions::default())
+            .await;
+        assert_that!(object.map(|o| o.content), ok(eq(&Bytes::from("hello"))));
+    }
+
+    #[googletest::test]
+    #[tokio::test]
+    async fn reject_unknown_upload_and_part() {
+        // Arrange
+        let storage = storage_with_bucket().await;
+        let initiated = send(&storage, Method::POST, "/test-bucket/a.txt?uploads", "").await;
+        let upload_id = element(&text(initiated).await, "UploadId").to_string();
+
+        // Act
+        let unknown_upload = send(
+            &storage,
+            Method::PUT,
+            "/test-bucket/a.txt?partNumber=1&uploadId=unknown",
+            "hello",
+        )
+        .await;
+        let unknown_part = send(
+            &storage,
+            Method::POST,
+            &format!("/test-bucket/a.txt?uploadId={upload_id}"),
+            "<CompleteMultipartUpload><Part><PartNumber>1</PartNumber><ETag>\"unknown\"</ETag></Part></CompleteMultipartUpload>",
+        )
+        .await;
+
+        // Assert
+        expect_that!(unknown_upload.status(), eq(StatusCode::NOT_FOUND));
+        expect_that!(
+            text(unknown_upload).await,
+            contains_substring("<Code>NoSuchUpload</Code>")
+        );
+        assert_that!(unknown_part.status(), eq(StatusCode::BAD_REQUEST));
+    }
+}
@@ -0,0 +1,140 @@
+use axum::{
+    body::Bytes,
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+};
+use tracing::instrument;
+
+use crate::{
+    api::{
+        handlers::context::Caller,
+        models::{
+            object::{encryption_key, ObjectMediaResponse},
+            xml::{
+                object_headers, object_upload, preconditions, Dialect, ObjectParams, ObjectResponse,
+            },
+        },
+    },
+    flows::object::{create_new_object, delete_object as delete, find_object},
+    libs::errors::Errors,
+    storage::Storage,
+};
+
+use super::{multipart, XmlResult};
+
+#[instrument(skip(storage, headers))]
+pub async fn get_object(
+    State(storage): State<Storage>,
+    Path((bucket, object)): Path<(String, String)>,
+    Query(params): Query<ObjectParams>,
+    caller: Caller,
+    headers: HeaderMap,
+) -> XmlResult<Response> {
+    if let Some(upload_id) = params.upload_id.clone() {
+        return multipart::list_parts(storage, bucket, object, &params, upload_id, caller).await;
+    }
+    caller
+        .authorize(&storage, &bucket, Some(&object), "storage.objects.get")
+        .await?;
+    let object = find_object(
+        storage,
+        bucket,
+        object,
+        params.generation,
+        preconditions(&headers)?,
+    )
+    .await?
+    .decrypt(encryption_key(&headers)?.as_ref())?;
+    Ok(ObjectResponse(
+        ObjectMediaResponse::new(object, &headers),
+        Dialect::of(&headers),
+    )
+    .into_response())
+}
+
+#[instrument(skip(storage, headers, body))]
+pub async fn put_object(
+    State(storage): State<Storage>,
+    Path((bucket, object)): Path<(String, String)>,
+    Query(params): Query<ObjectParams>,
+    caller: Caller,
+    headers: HeaderMap,
+    body: Bytes,
+) -> XmlResult<Response> {
+    match (params.upload_id, params.part_number) {
+        (Some(upload_id), Some(part_number)) => {
+            return multipart::upload_part(
+                storage,
+                bucket,
+                object,
+                upload_id,
+                part_number,
+                caller,
+                &headers,
+                body,
+            )
+            .await
+        }
+        (None, None) => {}
+        _ => {
+            return Err(Errors::BadRequest {
+                message: "Both partNumber and uploadId are required to upload a part".into(),
+            }
+            .into())
+        }
+    }
+    caller
+        .authorize(&storage, &bucket, None, "storage.objects.create")
+        .await?;
+    let upload = object_upload(object, &headers, body)?;
+    let object = create_new_object(storage, bucket, upload, preconditions(&headers)?).await?;
+    Ok(object_headers(&object).into_response())
+}
+
+/// Initiates or completes a multipart upload, which are the only operations the XML API serves by `POST` on an object.
+#[instrument(skip(storage, headers, body))]
+pub async fn post_object(
+    State(storage): State<Storage>,
+    Path((bucket, object)): Path<(String, String)>,
+    Query(params): Query<ObjectParams>,
+    caller: Caller,
+    headers: HeaderMap,
+    body: Bytes,
+) -> XmlResult<Response> {
+    match (params.uploads, params.upload_id) {
+        (Some(_), None) => multipart::initiate(storage, bucket, object, caller, &headers).await,
+        (None, Some(upload_id)) => {
+            multipart::complete(storage, bucket, object, upload_id, caller, &headers, body).await
+        }
+        _ => Err(Errors::BadRequest {
+            message: "Either uploads or uploadId is required".into(),
+        }
+        .into()),
+    }
+}
+
+#[instrument(skip(storage, headers))]
+pub async fn delete_object(
+    State(storage): State<Storage>,
+    Path((bucket, object)): Path<(String, String)>,
+    Query(params): Query<ObjectParams>,
+    caller: Caller,
+    headers: HeaderMap,
+) -> XmlResult<Response> {
+    if let Some(upload_id) = params.upload_id {
+        return multipart::abort(storage, bucket, object, upload_id, caller).await;
+    }
+    caller
+        .authorize(&storage, &bucket, Some(&object), "storage.objects.delete")
+        .await?;
+    delete(
+        storage,
+        bucket,
+        object,
+        params.generation,
+        preconditions(&headers)?,
+    )
+    .await?;
+    Ok(StatusCode::NO_CONTENT.into_response())
+}
@@ -0,0 +1,685 @@
+use axum::{
+    body::{to_bytes, Body},
+    extract::{OriginalUri, Request, State},
+    http::{header, request::Parts, HeaderMap, HeaderValue},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use bytes::{Bytes, BytesMut};
+use chrono::SecondsFormat;
+
+use crate::{
+    api::handlers::context::Signer,
+    libs::{
+        errors::{AppResult, Errors},
+        signature::{
+            expiration, is_within_clock_skew, parse_query, parse_timestamp, sha256_hex,
+            string_to_sign, verify_hmac_signature, verify_rsa_signature, CanonicalRequest,
+            Credential, KeyKind, SigningScheme,
+        },
+    },
+    storage::{hmac::HmacKeyStorageExt, service_account::ServiceAccountKeyStorageExt, Storage},
+};
+
+use super::XmlError;
+
+/// The payload hash of a request whose body isn't signed.
+const UNSIGNED_PAYLOAD: &str = "UNSIGNED-PAYLOAD";
+
+/// Verifies requests signed in the `Authorization` header or by the query parameters of a signed URL,
+/// and lets the others through as they are.
+/// Requests are signed by an HMAC key with `AWS4-HMAC-SHA256` of S3 tools or `GOOG4-HMAC-SHA256`,
+/// or by the private key of a service account with `GOOG4-RSA-SHA256`.
+/// https://cloud.google.com/storage/docs/authentication/signatures
+pub async fn verify_signature(
+    State(storage): State<Storage>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let signature = RequestSignature::from_header(request.headers())
+        .or_else(|| RequestSignature::from_query(request.uri().query()?));
+    let Some(signature) = signature else {
+        return next.run(request).await;
+    };
+    match authenticate(&storage, signature, request).await {
+        Ok(request) => next.run(request).await,
+        Err(e) => XmlError(e).into_response(),
+    }
+}
+
+/// The signature given either by the `Authorization` header such as
+/// `AWS4-HMAC-SHA256 Credential=..., SignedHeaders=..., Signature=...`, or by the query parameters of a signed URL.
+#[derive(Debug)]
+struct RequestSignature {
+    scheme: SigningScheme,
+    credential: Credential,
+    signed_headers: Vec<String>,
+    signature: String,
+    /// Only given by signed URLs, which carry the timestamp in the query instead of a header.
+    signed_url: Option<SignedUrl>,
+}
+
+/// `X-Goog-Date` and `X-Goog-Expires` of a signed URL.
+#[derive(Debug)]
+struct SignedUrl {
+    timestamp: String,
+    expires: String,
+}
+
+impl RequestSignature {
+    /// Returns `None` unless the header is given in one of the supported schemes.
+    fn from_header(headers: &HeaderMap) -> Option<AppResult<Self, Errors>> {
+        let value = headers.get(header::AUTHORIZATION)?.to_str().ok()?;
+        let (algorithm, fields) = value.split_once(' ')?;
+        let scheme = SigningScheme::from_algorithm(algorithm)?;
+        let field = |name: &str| {
+            fields
+                .split(',')
+                .filter_map(|f| f.trim().split_once('='))
+                .find(|(k, _)| *k == name)
+                .map(|(_, v)| v)
+        };
+        let parsed = (|| {
+            Some(RequestSignature {
+                scheme,
+                credential: Credential::parse(field("Credential")?)?,
+                signed_headers: field("SignedHeaders")?
+                    .split(';')
+                    .map(str::to_string)
+                    .collect(),
+                signature: field("Signature")?.to_string(),
+                signed_url: None,
+            })
+        })();
+        Some(parsed.ok_or_else(|| Errors::BadRequest {
+            message: format!("Malformed Authorization header: {value}"),
+        }))
+    }
+
+    /// Returns `None` unless the query has the algorithm parameter such as `X-Goog-Algorithm`.
+    /// https://cloud.google.com/storage/docs/authentication/signatures#required-query-parameters
+    fn from_query(query: &str) -> Option<AppResult<Self, Errors>> {
+        let query = parse_query(query);
+        let param = |name: String| {
+            query
+                .iter()
+                .find(|(k, _)| k.eq_ignore_ascii_case(&name))
+                .map(|(_, v)| v.as_str())
+        };
+        let scheme = ["X-Goog", "X-Amz"].into_iter().find_map(|prefix| {
+            SigningScheme::from_algorithm(param(format!("{prefix}-Algorithm"))?)
+                .filter(|s| s.query_prefix == prefix)
+        })?;
+        let prefix = scheme.query_prefix;
+        let parsed = (|| {
+            Some(RequestSignature {
+                scheme,
+                credential: Credential::parse(param(format!("{prefix}-Credential"))?)?,
+                signed_headers: param(format!("{prefix}-SignedHeaders"))?
+                    .split(';')
+                    .map(str::to_string)
+                    .collect(),
+                signature: param(format!("{prefix}-Signature"))?.to_string(),
+                signed_url: Some(SignedUrl {
+                    timestamp: param(format!("{prefix}-Date"))?.to_string(),
+                    expires: param(format!("{prefix}-Expires"))?.to_string(),
+                }),
+            })
+        })();
+        Some(parsed.ok_or_else(|| Errors::BadRequest {
+            message: format!("Malformed signed URL: {prefix}-Credential, {prefix}-Date, {prefix}-Expires, {prefix}-SignedHeaders and {prefix}-Signature are required"),
+        }))
+    }
+}
+
+async fn authenticate(
+    storage: &Storage,
+    signature: AppResult<RequestSignature, Errors>,
+    request: Request,
+) -> AppResult<Request, Errors> {
+    let signature = signature?;
+    let (mut parts, body) = request.into_parts();
+    let body = to_bytes(body, usize::MAX)
+        .await
+        .map_err(|e| Errors::BadRequest {
+            message: format!("Failed to read the body: {e}"),
+        })?;
+
+    let prefix = signature.scheme.header_prefix;
+    let header = |name: String| {
+        parts
+            .headers
+            .get(&name)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string)
+    };
+    let timestamp = match &signature.signed_url {
+        Some(SignedUrl { timestamp, expires }) => {
+            let expiration = expiration(timestamp, expires).ok_or_else(|| Errors::BadRequest {
+                message: format!(
+                    "Invalid date or expiration of the signed URL: {timestamp}, {expires}"
+                ),
+            })?;
+            let now = storage.clock().now();
+            if parse_timestamp(timestamp).is_some_and(|signed_at| signed_at > now) {
+                return Err(Errors::SignatureDoesNotMatch {
+                    message: format!("Request is not valid yet: {timestamp}"),
+                });
+            }
+            if now > expiration {
+                return Err(Errors::SignatureDoesNotMatch {
+                    message: format!(
+                        "Request signature expired at: {}",
+                        expiration.to_rfc3339_opts(SecondsFormat::Secs, true)
+                    ),
+                });
+            }
+            timestamp.clone()
+        }
+        None => {
+            let timestamp = header(format!("{prefix}-date")).ok_or_else(|| Errors::BadRequest {
+                message: format!("{prefix}-date header is required"),
+            })?;
+            if !is_within_clock_skew(&timestamp, storage.clock().now()) {
+                return Err(Errors::SignatureDoesNotMatch {
+                    message: format!(
+                        "The difference between the request time and the current time is too large: {timestamp}"
+                    ),
+                });
+            }
+            timestamp
+        }
+    };
+    if timestamp.get(..8) != Some(signature.credential.date.as_str()) {
+        return Err(Errors::BadRequest {
+            message: format!(
+                "The date of the credential scope {} does not match the request time: {timestamp}",
+                signature.credential.date
+            ),
+        });
+    }
+    // The payload is taken as unsigned only when the hash header is neither given nor signed.
+    let payload_header = format!("{prefix}-content-sha256");
+    let payload_hash = match header(payload_header.clone()) {
+        Some(payload_hash) => payload_hash,
+        None if signature.signed_headers.contains(&payload_header) => {
+            return Err(Errors::BadRequest {
+                message: format!("{payload_header} header is signed but missing"),
+            });
+        }
+        None => UNSIGNED_PAYLOAD.to_string(),
+    };
+    let is_hash = payload_hash.len() == 64 && payload_hash.bytes().all(|b| b.is_ascii_hexdigit());
+    if is_hash && payload_hash != sha256_hex(&body) {
+        return Err(Errors::BadRequest {
+            message: "The provided content SHA-256 does not match what was computed".into(),
+        });
+    }
+    if !signature.signed_headers.iter().any(|h| h == "host") {
+        return Err(Errors::BadRequest {
+            message: "The host header has to be signed".into(),
+        });
+    }
+
+    // The path is signed as the client sent it, even if it's rewritten from a virtual-hosted-style request.
+    let uri = parts
+        .extensions
+        .get::<OriginalUri>()
+        .map_or_else(|| parts.uri.clone(), |u| u.0.clone());
+    // A signed URL covers every query parameter but the signature itself.
+    let signature_param = format!("{}-Signature", signature.scheme.query_prefix);
+    let query = parse_query(uri.query().unwrap_or_default())
+        .into_iter()
+        .filter(|(k, _)| !k.eq_ignore_ascii_case(&signature_param))
+        .collect::<Vec<_>>();
+    let canonical_request = CanonicalRequest {
+        method: parts.method.as_str(),
+        path: uri.path(),
+        query: &query,
+        headers: &parts.headers,
+        signed_headers: &signature.signed_headers,
+        payload_hash: &payload_hash,
+    }
+    .format();
+    let string_to_sign = string_to_sign(
+        &signature.scheme,
+        &timestamp,
+        &signature.credential,
+        &canonical_request,
+    );
+    let signer = verify(
+        storage,
+        &signature.scheme,
+        &signature.credential,
+        &string_to_sign,
+        &signature.signature,
+    )
+    .await?;
+
+    let body = if payload_hash.starts_with("STREAMING-") {
+        decode_chunked(&mut parts, &body)?
+    } else {
+        body
+    };
+    parts.extensions.insert(signer);
+    Ok(Request::from_parts(parts, Body::from(body)))
+}
+
+/// Finds the key the string is signed with, and returns whom it's signed by unless the signature is wrong.
+/// Policy documents of form uploads are verified the same way as requests.
+pub(super) async fn verify(
+    storage: &Storage,
+    scheme: &SigningScheme,
+    credential: &Credential,
+    string_to_sign: &str,
+    signature: &str,
+) -> AppResult<Signer, Errors> {
+    let signer = match scheme.key_kind {
+        KeyKind::Hmac => {
+            let key = storage.active_hmac_key(&credential.access_id).await?;
+            verify_hmac_signature(scheme, &key.secret, credential, string_to_sign, signature).then(
+                || Signer {
+                    principal: key.principal(),
+                    project: key.project.id,
+                },
+            )
+        }
+        KeyKind::ServiceAccount => storage
+            .service_account_keys(&credential.access_id)
+            .await
+            .into_iter()
+            .find(|k| verify_rsa_signature(&k.public_key, string_to_sign, signature))
+            .map(|k| Signer {
+                principal: k.principal(),
+                project: k.project.id,
+            }),
+    };
+    signer.ok_or_else(|| Errors::SignatureDoesNotMatch {
+        message: "The request signature we calculated does not match the signature you provided. Check your Google secret key and signing method.".into(),
+    })
+}
+
+/// Decodes the body sent by S3 tools in `aws-chunked` encoding, whose chunk signatures and trailers are ignored.
+/// https://docs.aws.amazon.com/AmazonS3/latest/API/sigv4-streaming.html
+fn decode_chunked(parts: &mut Parts, body: &[u8]) -> AppResult<Bytes, Errors> {
+    let malformed = || Errors::BadRequest {
+        message: "Malformed aws-chunked body".into(),
+    };
+    let mut decoded = BytesMut::new();
+    let mut rest = body;
+    loop {
+        let line_end = rest
+            .windows(2)
+            .position(|w| w == b"\r\n")
+            .ok_or_else(malformed)?;
+        let line = std::str::from_utf8(&rest[..line_end]).map_err(|_| malformed())?;
+        let size = line.split(';').next().unwrap_or_default();
+        let size = usize::from_str_radix(size.trim(), 16).map_err(|_| malformed())?;
+        rest = &rest[line_end + 2..];
+        if size == 0 {
+            break;
+        }
+        let chunk = rest.get(..size).ok_or_else(malformed)?;
+        decoded.extend_from_slice(chunk);
+        rest = rest.get(size + 2..).ok_or_else(malformed)?;
+    }
+
+    // `aws-chunked` is a transfer detail, which isn't stored as the encoding of the object.
+    let encoding = parts
+        .headers
+        .get(header::CONTENT_ENCODING)
+        .and_then(|v| v.to_str().ok())
+        .map(|v| {
+            v.split(',')
+                .map(str::trim)
+                .filter(|e| !e.is_empty() && *e != "aws-chunked")
+                .collect::<Vec<_>>()
+                .join(",")
+        });
+    match encoding.filter(|e| !e.is_empty()) {
+        Some(encoding) => {
+            if let Ok(value) = HeaderValue::from_str(&encoding) {
+                parts.headers.insert(header::CONTENT_ENCODING, value);
+            }
+        }
+        None => {
+            parts.headers.remove(header::CONTENT_ENCODING);
+        }
+    }
+    parts.headers.remove(header::CONTENT_LENGTH);
+    Ok(decoded.freeze())
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::{to_bytes, Body},
+        extract::Request,
+        http::{header, HeaderMap, HeaderValue, StatusCode},
+        Extension,
+    };
+    use chrono::{DateTime, Duration, Utc};
+    use googletest::prelude::*;
+    use hmac::{Hmac, Mac};
+    use rsa::{
+        pkcs1v15::SigningKey,
+        signature::{SignatureEncoding, Signer},
+        RsaPrivateKey, RsaPublicKey,
+    };
+    use sha2::Sha256;
+    use tower::ServiceExt;
+
+    use crate::{
+        api::{handlers::context::AuthMode, routes::routes},
+        libs::signature::{
+            sha256_hex, string_to_sign, CanonicalRequest, Credential, SigningScheme,
+            GOOG4_HMAC_SHA256, GOOG4_RSA_SHA256,
+        },
+        storage::{
+            hmac::HmacKeyStorageExt, service_account::ServiceAccountKeyStorageExt,
+            BucketStorageExt, CreateBucketAttr, Storage,
+        },
+    };
+
+    const ACCESS_ID: &str = "GOOG1TESTACCESSID";
+    const SECRET: &str = "test-secret";
+    const SERVICE_ACCOUNT: &str = "sa@test-project.iam.gserviceaccount.com";
+
+    async fn storage_with_key() -> Storage {
+        let storage = Storage::default();
+        let attr = CreateBucketAttr {
+            project: "test-project".into(),
+            ..Default::default()
+        };
+        let _ = storage.create("test-bucket", attr).await;
+        let _ = storage
+            .import_hmac_key("test-project", SERVICE_ACCOUNT, ACCESS_ID, SECRET)
+            .await;
+        storage
+    }
+
+    fn hmac_sha256(key: &[u8], data: &str) -> Vec<u8> {
+        let mut mac = Hmac::<Sha256>::new_from_slice(key).unwrap();
+        mac.update(data.as_bytes());
+        mac.finalize().into_bytes().to_vec()
+    }
+
+    /// Signs the string with the key derived from `secret` for the date in `YYYYMMDD` format.
+    fn hmac_signature(secret: &str, date: &str, string_to_sign: &str) -> String {
+        let key = [date, "auto", "storage", "goog4_request"]
+            .into_iter()
+            .fold(format!("GOOG4{secret}").into_bytes(), |key, data| {
+                hmac_sha256(&key, data)
+            });
+        hex::encode(hmac_sha256(&key, string_to_sign))
+    }
+
+    /// Signs `GET /test-bucket` with `GOOG4-HMAC-SHA256` in the `Authorization` header.
+    /// `payload_hash` is signed as `x-goog-content-sha256` when given.
+    fn signed_request(
+        access_id: &str,
+        secret: &str,
+        signed_at: DateTime<Utc>,
+        payload_hash: Option<&str>,
+    ) -> Request {
+        let timestamp = signed_at.format("%Y%m%dT%H%M%SZ").to_string();
+        let credential = format!("{access_id}/{}/auto/storage/goog4_request", &timestamp[..8]);
+        let mut headers = HeaderMap::new();
+        headers.insert(header::HOST, HeaderValue::from_static("localhost"));
+        headers.insert("x-goog-date", HeaderValue::from_str(&timestamp).unwrap());
+        let mut signed_headers = ["host", "x-goog-date"].map(String::from).to_vec();
+        if let Some(payload_hash) = payload_hash {
+            headers.insert(
+                "x-goog-content-sha256",
+                HeaderValue::from_str(payload_hash).unwrap(),
+            );
+            signed_headers.push("x-goog-content-sha256".into());
+        }
+        let canonical_request = CanonicalRequest {
+            method: "GET",
+            path: "/test-bucket",
+            query: &[],
+            headers: &headers,
+            signed_headers: &signed_headers,
+            payload_hash: payload_hash.unwrap_or("UNSIGNED-PAYLOAD"),
+        }
+        .format();
+        let string_to_sign = string_to_sign(
+            &GOOG4_HMAC_SHA256,
+            &timestamp,
+            &Credential::parse(&credential).unwrap(),
+            &canonical_request,
+        );
+        let signature = hmac_signature(secret, &timestamp[..8], &string_to_sign);
+
+        let mut request = Request::get("/test-bucket").body(Body::empty()).unwrap();
+        *request.headers_mut() = headers;
+        request.headers_mut().insert(
+            header::AUTHORIZATION,
+            HeaderValue::from_str(&format!(
+                "GOOG4-HMAC-SHA256 Credential={credential}, SignedHeaders={}, Signature={signature}",
+                signed_headers.join(";")
+            ))
+            .unwrap(),
+        );
+        request
+    }
+
+    /// Builds a signed URL of `GET /test-bucket` valid for `expires` seconds, whose credential scope is dated `date`.
+    fn signed_url(
+        scheme: &SigningScheme,
+        access_id: &str,
+        date: &str,
+        signed_at: DateTime<Utc>,
+        expires: u32,
+        sign: impl Fn(&str) -> String,
+    ) -> Request {
+        let timestamp = signed_at.format("%Y%m%dT%H%M%SZ").to_string();
+        let credential = format!("{access_id}/{date}/auto/storage/goog4_request");
+        let query = [
+            ("X-Goog-Algorithm", scheme.algorithm.to_string()),
+            ("X-Goog-Credential", credential.clone()),
+            ("X-Goog-Date", timestamp.clone()),
+            ("X-Goog-Expires", expires.to_string()),
+            ("X-Goog-SignedHeaders", "host".to_string()),
+        ]
+        .map(|(k, v)| (k.to_string(), v));
+        let mut headers = HeaderMap::new();
+        headers.insert(header::HOST, HeaderValue::from_static("localhost"));
+        let canonical_request = CanonicalRequest {
+            method: "GET",
+            path: "/test-bucket",
+            query: &query,
+            headers: &headers,
+            signed_headers: &["host".to_string()],
+            payload_hash: "UNSIGNED-PAYLOAD",
+        }
+        .format();
+        let string_to_sign = string_to_sign(
+            scheme,
+            &timestamp,
+            &Credential::parse(&credential).unwrap(),
+            &canonical_request,
+        );
+        let query = query
+            .iter()
+            .map(|(k, v)| format!("{k}={}", v.replace('/', "%2F").replace('@', "%40")))
+            .collect::<Vec<_>>()
+            .join("&");
+        let signature = sign(&string_to_sign);
+        Request::get(format!("/test-bucket?{query}&X-Goog-Signature={signature}"))
+            .header(header::HOST, "localhost")
+            .body(Body::empty())
+            .unwrap()
+    }
+
+    async fn send(storage: &Storage, request: Request) -> (StatusCode, String) {
+        let res = routes(storage)
+            .layer(Extension(AuthMode::default()))
+            .with_state(storage.clone())
+            .oneshot(request)
+            .await
+            .unwrap();
+        let status = res.status();
+        let body = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+        (status, String::from_utf8_lossy(&body).into_owned())
+    }
+
+    #[googletest::test]
+    #[tokio::test]
+    async fn accept_request_signed_by_hmac_key() {
+        // Arrange
+        let storage = storage_with_key().await;
+        let now = storage.clock().now();
+
+        // Act
+        let (status, body) = send(&storage, signed_request(ACCESS_ID, SECRET, now, None)).await;
+
+        // Assert
+        expect_that!(status, eq(StatusCode::OK));
+        assert_that!(body, contains_substring("<Name>test-bucket</Name>"));
+    }
+
+    #[googletest::test]
+    #[tokio::test]
+    async fn reject_request_failing_verification() {
+        // Arrange
+        let storage = storage_with_key().await;
+        let now = storage.clock().now();
+        let malformed = Request::get("/test-bucket")
+            .header(
+                header::AUTHORIZATION,
+                "GOOG4-HMAC-SHA256 Credential=invalid",
+            )
+            .body(Body::empty())
+            .unwrap();
+
+        // Act
+        let malformed = send(&storage, malformed).await;
+        let unknown_key = send(&storage, signed_request("GOOG1UNKNOWN", SECRET, now, None)).await;
+        let wrong_secret = send(&storage, signed_request(ACCESS_ID, "wrong", now, None)).await;
+        let skewed = send(
+            &storage,
+            signed_request(ACCESS_ID, SECRET, now - Duration::minutes(16), None),
+        )
+        .await;
+
+        // Assert
+        expect_that!(malformed.0, eq(StatusCode::BAD_REQUEST));
+        expect_that!(unknown_key.0, eq(StatusCode::FORBIDDEN));
+        expect_that!(
+            unknown_key.1,
+            contains_substring("<Code>InvalidAccessKeyId</Code>")
+        );
+        expect_that!(wrong_secret.0, eq(StatusCode::FORBIDDEN));
+        expect_that!(
+            wrong_secret.1,
+            contains_substring("<Code>SignatureDoesNotMatch</Code>")
+        );
+        expect_that!(skewed.0, eq(StatusCode::FORBIDDEN));
+        assert_that!(
+            skewed.1,
+            contains_substring("<Code>SignatureDoesNotMatch</Code>")
+        );
+    }
+
+    #[googletest::test]
+    #[tokio::test]
+    async fn reject_request_with_unverifiable_payload() {
+        // Arrange
+        let storage = storage_with_key().await;
+        let now = storage.clock().now();
+        let other_payload_hash = sha256_hex(b"other content");
+        let mismatched = signed_request(ACCESS_ID, SECRET, now, Some(&other_payload_hash));
+        let mut missing = signed_request(ACCESS_ID, SECRET, now, Some(&sha256_hex(b"")));
+        missing.headers_mut().remove("x-goog-content-sha256");
+
+        // Act
+        let mismatched = send(&storage, mismatched).await;
+        let missing = send(&storage, missing).await;
+
+        // Assert
+        expect_that!(mismatched.0, eq(StatusCode::BAD_REQUEST));
+        expect_that!(
+            mismatched.1,
+            contains_substring("does not match what was computed")
+        );
+        expect_that!(missing.0, eq(StatusCode::BAD_REQUEST));
+        assert_that!(missing.1, contains_substring("signed but missing"));
+    }
+
+    #[googletest::test]
+    #[tokio::test]
+    async fn accept_signed_url() {
+        // Arrange
+        let storage = storage_with_key().await;
+        let private_key = RsaPrivateKey::new(&mut rand::thread_rng(), 1024).unwrap();
+        let _ = storage
+            .import_service_account_key(
+                "test-project",
+                SERVICE_ACCOUNT,
+                RsaPublicKey::from(&private_key),
+            )
+            .await;
+        let now = storage.clock().now();
+        let date = now.format("%Y%m%d").to_string();
+        let signing_key = SigningKey::<Sha256>::new(private_key);
+
+        // Act
+        let hmac = send(
+            &storage,
+            signed_url(&GOOG4_HMAC_SHA256, ACCESS_ID, &date, now, 60, |s| {
+                hmac_signature(SECRET, &date, s)
+            }),
+        )
+        .await;
+        let rsa = send(
+            &storage,
+            signed_url(&GOOG4_RSA_SHA256, SERVICE_ACCOUNT, &date, now, 60, |s| {
+                hex::encode(signing_key.sign(s.as_bytes()).to_bytes())
+            }),
+        )
+        .await;
+
+        // Assert
+        expect_that!(hmac.0, eq(StatusCode::OK));
+        expect_that!(hmac.1, contains_substring("<Name>test-bucket</Name>"));
+        expect_that!(rsa.0, eq(StatusCode::OK));
+        assert_that!(rsa.1, contains_substring("<Name>test-bucket</Name>"));
+    }
+
+    #[googletest::test]
+    #[tokio::test]
+    async fn reject_signed_url_out_of_validity() {
+        // Arrange
+        let storage = storage_with_key().await;
+        let now = storage.clock().now();
+        let url = |date: DateTime<Utc>, signed_at: DateTime<Utc>| {
+            let date = date.format("%Y%m%d").to_string();
+            signed_url(&GOOG4_HMAC_SHA256, ACCESS_ID, &date, signed_at, 60, |s| {
+                hmac_signature(SECRET, &date, s)
+            })
+        };
+        let expiring = url(now, now);
+        let future = now + Duration::hours(1);
+
+        // Act
+        let not_yet_valid = send(&storage, url(future, future)).await;
+        let mismatched_date = send(&storage, url(now - Duration::days(1), now)).await;
+        storage.clock().advance(Duration::seconds(61));
+        let expired = send(&storage, expiring).await;
+
+        // Assert
+        expect_that!(not_yet_valid.0, eq(StatusCode::FORBIDDEN));
+        expect_that!(
+            not_yet_valid.1,
+            contains_substring("Request is not valid yet")
+        );
+        expect_that!(mismatched_date.0, eq(StatusCode::BAD_REQUEST));
+        expect_that!(expired.0, eq(StatusCode::FORBIDDEN));
+        assert_that!(
+            expired.1,
+            contains_substring("Request signature expired at")
+        );
+    }
+}
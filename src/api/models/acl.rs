@@ -0,0 +1,179 @@
+use serde::{Deserialize, Serialize};
+
+use crate::storage::acl::{AccessControl, AclRole, AclSpec, AclTarget, PredefinedAcl};
+
+use super::{Kind, ListKind, ListResponse};
+
+/// Represents `BucketAccessControls` and `ObjectAccessControls` resources.
+/// https://cloud.google.com/storage/docs/json_api/v1/bucketAccessControls#resource
+/// https://cloud.google.com/storage/docs/json_api/v1/objectAccessControls#resource
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccessControlResponse {
+    pub kind: Kind,
+    pub id: String,
+    pub bucket: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub object: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub generation: Option<String>,
+    pub entity: String,
+    pub role: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub domain: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub project_team: Option<ProjectTeam>,
+}
+
+impl AccessControlResponse {
+    /// Builds an entry of the given access control list.
+    /// `generation` of an object ACL is known only when the request specifies it.
+    pub fn new(bucket: &str, target: &AclTarget, acl: AccessControl) -> Self {
+        let (kind, id, object, generation) = match target {
+            AclTarget::Bucket => (
+                Kind::BucketAccessControl,
+                format!("{bucket}/{}", acl.entity),
+                None,
+                None,
+            ),
+            AclTarget::DefaultObject => (
+                Kind::ObjectAccessControl,
+                format!("{bucket}/{}", acl.entity),
+                None,
+                None,
+            ),
+            AclTarget::Object { name, generation } => (
+                Kind::ObjectAccessControl,
+                match generation {
+                    Some(generation) => format!("{bucket}/{name}/{generation}/{}", acl.entity),
+                    None => format!("{bucket}/{name}/{}", acl.entity),
+                },
+                Some(name.clone()),
+                generation.map(|g| g.to_string()),
+            ),
+        };
+        let (email, domain) = match acl.entity.split_once('-') {
+            Some(("user" | "group", email)) if email.contains('@') => {
+                (Some(email.to_string()), None)
+            }
+            Some(("domain", domain)) => (None, Some(domain.to_string())),
+            _ => (None, None),
+        };
+        AccessControlResponse {
+            kind,
+            id,
+            bucket: bucket.to_string(),
+            object,
+            generation,
+            project_team: ProjectTeam::parse(&acl.entity),
+            email,
+            domain,
+            entity: acl.entity,
+            role: acl.role.to_string(),
+        }
+    }
+
+    /// Builds the response of `list` operation.
+    pub fn list(
+        bucket: &str,
+        target: &AclTarget,
+        acl: Vec<AccessControl>,
+    ) -> ListResponse<AccessControlResponse> {
+        ListResponse {
+            kind: match target {
+                AclTarget::Bucket => ListKind::BucketAccessControls,
+                _ => ListKind::ObjectAccessControls,
+            },
+            items: acl
+                .into_iter()
+                .map(|a| AccessControlResponse::new(bucket, target, a))
+                .collect(),
+            prefixes: vec![],
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectTeam {
+    pub project_number: String,
+    pub team: String,
+}
+
+impl ProjectTeam {
+    /// Extracts the team from a `project-{team}-{projectNumber}` entity.
+    fn parse(entity: &str) -> Option<Self> {
+        let (team, project_number) = entity.strip_prefix("project-")?.split_once('-')?;
+        Some(ProjectTeam {
+            project_number: project_number.to_string(),
+            team: team.to_string(),
+        })
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Owner {
+    pub entity: String,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum Role {
+    Owner,
+    Writer,
+    Reader,
+}
+
+impl From<Role> for AclRole {
+    fn from(role: Role) -> Self {
+        match role {
+            Role::Owner => AclRole::Owner,
+            Role::Writer => AclRole::Writer,
+            Role::Reader => AclRole::Reader,
+        }
+    }
+}
+
+/// Represents an access control entry given on `insert`, or as a part of bucket and object resources.
+#[derive(Debug, Clone, Deserialize, garde::Validate)]
+pub struct InsertAccessControl {
+    #[garde(pattern(
+        "^(user-.+|group-.+|domain-.+|project-(owners|editors|viewers)-.+|allUsers|allAuthenticatedUsers)$"
+    ))]
+    pub entity: String,
+    #[garde(skip)]
+    pub role: Role,
+}
+
+impl From<InsertAccessControl> for AccessControl {
+    fn from(value: InsertAccessControl) -> Self {
+        AccessControl::new(value.entity, value.role.into())
+    }
+}
+
+/// A predefined ACL given by the parameter takes precedence over the entries in the request body.
+pub fn acl_spec<P: Into<PredefinedAcl>>(
+    predefined: Option<P>,
+    entries: Option<Vec<InsertAccessControl>>,
+) -> Option<AclSpec> {
+    predefined
+        .map(|p| AclSpec::Predefined(p.into()))
+        .or(entries.map(|e| AclSpec::Entries(e.into_iter().map(AccessControl::from).collect())))
+}
+
+/// Represents the request body for `update` and `patch`, where the entity is given by the path.
+#[derive(Debug, Deserialize, garde::Validate)]
+pub struct UpdateAccessControl {
+    #[garde(skip)]
+    pub role: Role,
+}
+
+/// Represents a request parameter of `objectAccessControls`.
+/// https://cloud.google.com/storage/docs/json_api/v1/objectAccessControls/get#parameters
+#[derive(Debug, Deserialize)]
+pub struct ObjectAccessControlParams {
+    pub generation: Option<u64>,
+}
@@ -1,10 +1,20 @@
-use chrono::{DateTime, Local};
+use std::collections::HashMap;
+
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 use strum::EnumString;
 
-use crate::storage::{CreateBucketAttr, StorageBucketAttr, UpdateBucketAttr};
+use crate::storage::{
+    acl::{self, AclTarget},
+    cors, iam, lifecycle, retention, soft_delete, CreateBucketAttr, MetadataUpdate,
+    StorageBucketAttr, UpdateBucketAttr,
+};
 
-use super::Kind;
+use super::{
+    acl::{acl_spec, AccessControlResponse, InsertAccessControl, Owner},
+    deserialize_int64, deserialize_nullable, serialize_optional_timestamp, serialize_timestamp,
+    Kind, Projection,
+};
 
 #[derive(Debug, Default, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -14,20 +24,82 @@ pub struct BucketResponse {
     pub default_event_based_hold: bool,
     pub name: String,
     pub versioning: BucketVersioning,
-    pub time_created: DateTime<Local>,
-    pub updated: DateTime<Local>,
+    #[serde(serialize_with = "serialize_timestamp")]
+    pub time_created: DateTime<Utc>,
+    #[serde(serialize_with = "serialize_timestamp")]
+    pub updated: DateTime<Utc>,
     pub location: String,
     pub storage_class: String,
     pub project_number: String,
+    pub generation: String,
     pub metageneration: String,
     pub etag: String,
     pub location_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub acl: Option<Vec<AccessControlResponse>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_object_acl: Option<Vec<AccessControlResponse>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub owner: Option<Owner>,
+    pub iam_configuration: IamConfiguration,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retention_policy: Option<RetentionPolicyResponse>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub object_retention: Option<BucketObjectRetention>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lifecycle: Option<Lifecycle>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cors: Option<Vec<Cors>>,
+    pub soft_delete_policy: SoftDeletePolicyResponse,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub labels: Option<HashMap<String, String>>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_optional_timestamp"
+    )]
+    pub soft_delete_time: Option<DateTime<Utc>>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_optional_timestamp"
+    )]
+    pub hard_delete_time: Option<DateTime<Utc>>,
+}
+
+impl BucketResponse {
+    pub fn with_projection(self, projection: Projection) -> Self {
+        match projection {
+            Projection::Full => self,
+            Projection::NoAcl => BucketResponse {
+                acl: None,
+                default_object_acl: None,
+                owner: None,
+                ..self
+            },
+        }
+    }
 }
 
 impl From<StorageBucketAttr> for BucketResponse {
     fn from(value: StorageBucketAttr) -> Self {
         BucketResponse {
             kind: Kind::Bucket,
+            owner: Some(Owner {
+                entity: acl::project_owner(&value.project),
+            }),
+            acl: Some(
+                value
+                    .acl
+                    .into_iter()
+                    .map(|a| AccessControlResponse::new(&value.name, &AclTarget::Bucket, a))
+                    .collect(),
+            ),
+            default_object_acl: Some(
+                value
+                    .default_object_acl
+                    .into_iter()
+                    .map(|a| AccessControlResponse::new(&value.name, &AclTarget::DefaultObject, a))
+                    .collect(),
+            ),
             id: value.name.clone(),
             name: value.name,
             time_created: value.time_created,
@@ -38,20 +110,342 @@ impl From<StorageBucketAttr> for BucketResponse {
             },
             location: value.location,
             storage_class: "STANDARD".to_string(),
-            project_number: "1".to_string(),
-            metageneration: "1".to_string(),
+            project_number: value.project.number.to_string(),
+            generation: value.generation.to_string(),
+            metageneration: value.metageneration.to_string(),
             etag: "tag".to_string(),
             location_type: "region".to_string(),
+            iam_configuration: value.iam_configuration.into(),
+            retention_policy: value.retention_policy.map(RetentionPolicyResponse::from),
+            object_retention: value.object_retention.then(|| BucketObjectRetention {
+                mode: "Enabled".to_string(),
+            }),
+            lifecycle: (!value.lifecycle.is_empty()).then(|| Lifecycle {
+                rule: value
+                    .lifecycle
+                    .into_iter()
+                    .map(LifecycleRule::from)
+                    .collect(),
+            }),
+            cors: (!value.cors.is_empty())
+                .then(|| value.cors.into_iter().map(Cors::from).collect()),
+            soft_delete_policy: value.soft_delete_policy.into(),
+            labels: (!value.labels.is_empty()).then_some(value.labels),
+            soft_delete_time: value.soft_delete_time,
+            hard_delete_time: value.hard_delete_time,
         }
     }
 }
 
 #[derive(Debug, Default, Deserialize, Serialize)]
 pub struct BucketVersioning {
+    #[serde(default)]
     pub enabled: bool,
 }
 
+/// Represents `retentionPolicy` of a bucket.
+/// https://cloud.google.com/storage/docs/json_api/v1/buckets#retentionPolicy
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RetentionPolicyResponse {
+    pub retention_period: String,
+    #[serde(serialize_with = "serialize_timestamp")]
+    pub effective_time: DateTime<Utc>,
+    pub is_locked: bool,
+}
+
+impl From<retention::RetentionPolicy> for RetentionPolicyResponse {
+    fn from(value: retention::RetentionPolicy) -> Self {
+        RetentionPolicyResponse {
+            retention_period: value.retention_period.to_string(),
+            effective_time: value.effective_time,
+            is_locked: value.is_locked,
+        }
+    }
+}
+
+/// Represents `softDeletePolicy` of a bucket.
+/// https://cloud.google.com/storage/docs/json_api/v1/buckets#softDeletePolicy
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SoftDeletePolicyResponse {
+    pub retention_duration_seconds: String,
+    #[serde(serialize_with = "serialize_timestamp")]
+    pub effective_time: DateTime<Utc>,
+}
+
+impl From<soft_delete::SoftDeletePolicy> for SoftDeletePolicyResponse {
+    fn from(value: soft_delete::SoftDeletePolicy) -> Self {
+        SoftDeletePolicyResponse {
+            retention_duration_seconds: value.retention_duration_seconds.to_string(),
+            effective_time: value.effective_time,
+        }
+    }
+}
+
+/// Represents `softDeletePolicy` given on `insert`, `update` and `patch`, where `0` disables soft delete.
+#[derive(Debug, Deserialize, garde::Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct SoftDeletePolicy {
+    #[garde(skip)]
+    #[serde(deserialize_with = "deserialize_int64")]
+    pub retention_duration_seconds: u64,
+}
+
+/// Represents `lifecycle` of a bucket.
+/// https://cloud.google.com/storage/docs/json_api/v1/buckets#lifecycle
+#[derive(Debug, Default, Deserialize, Serialize, garde::Validate)]
+pub struct Lifecycle {
+    #[garde(dive)]
+    #[serde(default)]
+    pub rule: Vec<LifecycleRule>,
+}
+
+impl From<Lifecycle> for Vec<lifecycle::LifecycleRule> {
+    fn from(value: Lifecycle) -> Self {
+        value.rule.into_iter().map(Into::into).collect()
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, garde::Validate)]
+pub struct LifecycleRule {
+    #[garde(skip)]
+    pub action: LifecycleAction,
+    #[garde(dive)]
+    pub condition: LifecycleCondition,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(tag = "type")]
+pub enum LifecycleAction {
+    Delete,
+    #[serde(rename_all = "camelCase")]
+    SetStorageClass {
+        storage_class: String,
+    },
+}
+
+#[derive(Debug, Deserialize, Serialize, garde::Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct LifecycleCondition {
+    #[garde(skip)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub age: Option<u32>,
+    #[garde(skip)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created_before: Option<NaiveDate>,
+    #[garde(skip)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub num_newer_versions: Option<u32>,
+    #[garde(skip)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_live: Option<bool>,
+    #[garde(skip)]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub matches_prefix: Vec<String>,
+    #[garde(skip)]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub matches_suffix: Vec<String>,
+    #[garde(skip)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub days_since_noncurrent_time: Option<u32>,
+    #[garde(inner(pattern(
+        "^(STANDARD|NEARLINE|COLDLINE|ARCHIVE|MULTI_REGIONAL|REGIONAL|DURABLE_REDUCED_AVAILABILITY)$"
+    )))]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub matches_storage_class: Vec<String>,
+}
+
+/// Represents an entry of `cors` of a bucket.
+/// https://cloud.google.com/storage/docs/json_api/v1/buckets#cors
+#[derive(Debug, Default, Deserialize, Serialize, garde::Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct Cors {
+    #[garde(skip)]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub origin: Vec<String>,
+    #[garde(skip)]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub method: Vec<String>,
+    #[garde(skip)]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub response_header: Vec<String>,
+    #[garde(skip)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_age_seconds: Option<u32>,
+}
+
+impl From<cors::CorsRule> for Cors {
+    fn from(value: cors::CorsRule) -> Self {
+        Cors {
+            origin: value.origins,
+            method: value.methods,
+            response_header: value.response_headers,
+            max_age_seconds: value.max_age_seconds,
+        }
+    }
+}
+
+impl From<Cors> for cors::CorsRule {
+    fn from(value: Cors) -> Self {
+        cors::CorsRule {
+            origins: value.origin,
+            methods: value.method,
+            response_headers: value.response_header,
+            max_age_seconds: value.max_age_seconds,
+        }
+    }
+}
+
+/// Omitted `cors` clears the configuration.
+fn cors_rules(cors: Option<Vec<Cors>>) -> Vec<cors::CorsRule> {
+    cors.unwrap_or_default()
+        .into_iter()
+        .map(Into::into)
+        .collect()
+}
+
+impl From<lifecycle::LifecycleRule> for LifecycleRule {
+    fn from(value: lifecycle::LifecycleRule) -> Self {
+        let lifecycle::LifecycleRule { action, condition } = value;
+        LifecycleRule {
+            action: match action {
+                lifecycle::LifecycleAction::Delete => LifecycleAction::Delete,
+                lifecycle::LifecycleAction::SetStorageClass(storage_class) => {
+                    LifecycleAction::SetStorageClass { storage_class }
+                }
+            },
+            condition: LifecycleCondition {
+                age: condition.age,
+                created_before: condition.created_before,
+                num_newer_versions: condition.num_newer_versions,
+                is_live: condition.is_live,
+                matches_prefix: condition.matches_prefix,
+                matches_suffix: condition.matches_suffix,
+                days_since_noncurrent_time: condition.days_since_noncurrent_time,
+                matches_storage_class: condition.matches_storage_class,
+            },
+        }
+    }
+}
+
+impl From<LifecycleRule> for lifecycle::LifecycleRule {
+    fn from(value: LifecycleRule) -> Self {
+        let LifecycleRule { action, condition } = value;
+        lifecycle::LifecycleRule {
+            action: match action {
+                LifecycleAction::Delete => lifecycle::LifecycleAction::Delete,
+                LifecycleAction::SetStorageClass { storage_class } => {
+                    lifecycle::LifecycleAction::SetStorageClass(storage_class)
+                }
+            },
+            condition: lifecycle::LifecycleCondition {
+                age: condition.age,
+                created_before: condition.created_before,
+                num_newer_versions: condition.num_newer_versions,
+                is_live: condition.is_live,
+                matches_prefix: condition.matches_prefix,
+                matches_suffix: condition.matches_suffix,
+                days_since_noncurrent_time: condition.days_since_noncurrent_time,
+                matches_storage_class: condition.matches_storage_class,
+            },
+        }
+    }
+}
+
+/// Represents `objectRetention` of a bucket, which is present only when enabled on `insert`.
+/// https://cloud.google.com/storage/docs/json_api/v1/buckets#objectRetention
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BucketObjectRetention {
+    pub mode: String,
+}
+
+/// Represents `retentionPolicy` given on `insert`, `update` and `patch`.
+/// GCS accepts retention periods of up to 100 years.
 #[derive(Debug, Deserialize, garde::Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct RetentionPolicy {
+    #[garde(range(min = 1, max = 3_155_760_000))]
+    #[serde(deserialize_with = "deserialize_int64")]
+    pub retention_period: u64,
+}
+
+/// Represents `iamConfiguration` of a bucket, where omitted fields in requests are left to each operation.
+/// https://cloud.google.com/storage/docs/json_api/v1/buckets#iamConfiguration
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IamConfiguration {
+    pub uniform_bucket_level_access: Option<UniformBucketLevelAccess>,
+    /// The legacy name of `uniformBucketLevelAccess`, which is only returned.
+    #[serde(skip_deserializing)]
+    pub bucket_policy_only: Option<UniformBucketLevelAccess>,
+    pub public_access_prevention: Option<PublicAccessPrevention>,
+}
+
+impl IamConfiguration {
+    fn ubla_enabled(&self) -> Option<bool> {
+        self.uniform_bucket_level_access
+            .as_ref()
+            .map(|ubla| ubla.enabled)
+    }
+}
+
+impl From<iam::IamConfiguration> for IamConfiguration {
+    fn from(value: iam::IamConfiguration) -> Self {
+        let ubla = UniformBucketLevelAccess {
+            enabled: value.uniform_bucket_level_access.is_some(),
+            locked_time: value.uniform_bucket_level_access.map(|u| u.locked_time),
+        };
+        IamConfiguration {
+            uniform_bucket_level_access: Some(ubla.clone()),
+            bucket_policy_only: Some(ubla),
+            public_access_prevention: Some(value.public_access_prevention.into()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UniformBucketLevelAccess {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_optional_timestamp",
+        skip_deserializing
+    )]
+    pub locked_time: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PublicAccessPrevention {
+    #[serde(alias = "unspecified")]
+    Inherited,
+    Enforced,
+}
+
+impl From<iam::PublicAccessPrevention> for PublicAccessPrevention {
+    fn from(value: iam::PublicAccessPrevention) -> Self {
+        match value {
+            iam::PublicAccessPrevention::Inherited => PublicAccessPrevention::Inherited,
+            iam::PublicAccessPrevention::Enforced => PublicAccessPrevention::Enforced,
+        }
+    }
+}
+
+impl From<PublicAccessPrevention> for iam::PublicAccessPrevention {
+    fn from(value: PublicAccessPrevention) -> Self {
+        match value {
+            PublicAccessPrevention::Inherited => iam::PublicAccessPrevention::Inherited,
+            PublicAccessPrevention::Enforced => iam::PublicAccessPrevention::Enforced,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, garde::Validate)]
+#[serde(rename_all = "camelCase")]
 pub struct InsertBucket {
     #[garde(pattern("^[a-zA-Z0-9][a-zA-Z0-9._-]*[a-zA-Z0-9]$"))]
     pub name: String,
@@ -63,65 +457,234 @@ pub struct InsertBucket {
     // TODO enum
     #[garde(skip)]
     pub location: Option<String>,
+    #[garde(dive)]
+    pub acl: Option<Vec<InsertAccessControl>>,
+    #[garde(dive)]
+    pub default_object_acl: Option<Vec<InsertAccessControl>>,
+    #[garde(skip)]
+    pub iam_configuration: Option<IamConfiguration>,
+    #[garde(dive)]
+    pub retention_policy: Option<RetentionPolicy>,
+    #[garde(dive)]
+    pub lifecycle: Option<Lifecycle>,
+    #[garde(dive)]
+    pub cors: Option<Vec<Cors>>,
+    #[garde(dive)]
+    pub soft_delete_policy: Option<SoftDeletePolicy>,
+    #[garde(skip)]
+    pub labels: Option<HashMap<String, String>>,
 }
 
-impl From<InsertBucket> for CreateBucketAttr {
-    fn from(event: InsertBucket) -> Self {
+impl From<(InsertBucketParams, InsertBucket)> for CreateBucketAttr {
+    fn from((params, event): (InsertBucketParams, InsertBucket)) -> Self {
         let InsertBucket {
             name: _,
             versioning,
             default_event_based_hold,
             location,
+            acl,
+            default_object_acl,
+            iam_configuration,
+            retention_policy,
+            lifecycle,
+            cors,
+            soft_delete_policy,
+            labels,
         } = event;
+        let iam_configuration = iam_configuration.unwrap_or_default();
         CreateBucketAttr {
-            versioning: versioning.map_or(false, |v| v.enabled),
+            project: params.project,
+            versioning: versioning.is_some_and(|v| v.enabled),
             location: location.unwrap_or_else(|| "US".to_string()),
             default_event_based_hold,
+            acl: acl_spec(params.predefined_acl, acl),
+            default_object_acl: acl_spec(params.predefined_default_object_acl, default_object_acl),
+            uniform_bucket_level_access: iam_configuration.ubla_enabled().unwrap_or_default(),
+            public_access_prevention: iam_configuration
+                .public_access_prevention
+                .map(Into::into)
+                .unwrap_or_default(),
+            retention_period: retention_policy.map(|r| r.retention_period),
+            object_retention: params.enable_object_retention.unwrap_or_default(),
+            lifecycle: lifecycle.map(Into::into).unwrap_or_default(),
+            cors: cors_rules(cors),
+            soft_delete_retention_duration: soft_delete_policy
+                .map_or(soft_delete::DEFAULT_RETENTION_DURATION, |s| {
+                    s.retention_duration_seconds
+                }),
+            labels: labels.unwrap_or_default(),
         }
     }
 }
 
+/// Represents the request body for `update` bucket.
+/// Every mutable field is replaced, so omitted fields are reset to their defaults.
+/// https://cloud.google.com/storage/docs/json_api/v1/buckets/update
 #[derive(Debug, Deserialize, garde::Validate)]
+#[serde(rename_all = "camelCase")]
 pub struct UpdateBucket {
     #[garde(skip)]
     pub versioning: Option<BucketVersioning>,
     #[garde(skip)]
-    #[serde(default)]
-    pub default_event_based_hold: bool,
+    pub default_event_based_hold: Option<bool>,
+    /// ACLs are kept unless given, since they're omitted from resources fetched with `noAcl`.
+    #[garde(dive)]
+    pub acl: Option<Vec<InsertAccessControl>>,
+    #[garde(dive)]
+    pub default_object_acl: Option<Vec<InsertAccessControl>>,
+    #[garde(skip)]
+    pub iam_configuration: Option<IamConfiguration>,
+    #[garde(dive)]
+    pub retention_policy: Option<RetentionPolicy>,
+    #[garde(dive)]
+    pub lifecycle: Option<Lifecycle>,
+    #[garde(dive)]
+    pub cors: Option<Vec<Cors>>,
+    #[garde(dive)]
+    pub soft_delete_policy: Option<SoftDeletePolicy>,
+    #[garde(skip)]
+    pub labels: Option<HashMap<String, String>>,
 }
 
-impl From<UpdateBucket> for UpdateBucketAttr {
-    fn from(event: UpdateBucket) -> Self {
+impl From<(UpdateBucketParams, UpdateBucket)> for UpdateBucketAttr {
+    fn from((params, event): (UpdateBucketParams, UpdateBucket)) -> Self {
         let UpdateBucket {
             versioning,
             default_event_based_hold,
+            acl,
+            default_object_acl,
+            iam_configuration,
+            retention_policy,
+            lifecycle,
+            cors,
+            soft_delete_policy,
+            labels,
         } = event;
+        let iam_configuration = iam_configuration.unwrap_or_default();
         UpdateBucketAttr {
-            versioning: versioning.map(|v| v.enabled),
+            versioning: Some(versioning.is_some_and(|v| v.enabled)),
+            default_event_based_hold: Some(default_event_based_hold.unwrap_or_default()),
+            acl: acl_spec(params.predefined_acl, acl),
+            default_object_acl: acl_spec(params.predefined_default_object_acl, default_object_acl),
+            uniform_bucket_level_access: Some(iam_configuration.ubla_enabled().unwrap_or_default()),
+            public_access_prevention: Some(
+                iam_configuration
+                    .public_access_prevention
+                    .map(Into::into)
+                    .unwrap_or_default(),
+            ),
+            retention_period: Some(retention_policy.map(|r| r.retention_period)),
+            lifecycle: Some(lifecycle.map(Into::into).unwrap_or_default()),
+            cors: Some(cors_rules(cors)),
+            soft_delete_retention_duration: Some(
+                soft_delete_policy.map_or(soft_delete::DEFAULT_RETENTION_DURATION, |s| {
+                    s.retention_duration_seconds
+                }),
+            ),
+            labels: Some(MetadataUpdate::Replace(labels.unwrap_or_default())),
+        }
+    }
+}
+
+/// Represents the request body for `patch` bucket.
+/// Omitted fields are left untouched, and fields explicitly set to `null` are cleared.
+/// Labels are merged, and labels set to `null` are removed.
+/// https://cloud.google.com/storage/docs/json_api/v1/buckets/patch
+#[derive(Debug, Deserialize, garde::Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct PatchBucket {
+    #[garde(skip)]
+    #[serde(default, deserialize_with = "deserialize_nullable")]
+    pub versioning: Option<Option<BucketVersioning>>,
+    #[garde(skip)]
+    #[serde(default, deserialize_with = "deserialize_nullable")]
+    pub default_event_based_hold: Option<Option<bool>>,
+    #[garde(dive)]
+    pub acl: Option<Vec<InsertAccessControl>>,
+    #[garde(dive)]
+    pub default_object_acl: Option<Vec<InsertAccessControl>>,
+    #[garde(skip)]
+    pub iam_configuration: Option<IamConfiguration>,
+    #[garde(dive)]
+    #[serde(default, deserialize_with = "deserialize_nullable")]
+    pub retention_policy: Option<Option<RetentionPolicy>>,
+    #[garde(dive)]
+    #[serde(default, deserialize_with = "deserialize_nullable")]
+    pub lifecycle: Option<Option<Lifecycle>>,
+    #[garde(dive)]
+    #[serde(default, deserialize_with = "deserialize_nullable")]
+    pub cors: Option<Option<Vec<Cors>>>,
+    #[garde(dive)]
+    #[serde(default, deserialize_with = "deserialize_nullable")]
+    pub soft_delete_policy: Option<Option<SoftDeletePolicy>>,
+    #[garde(skip)]
+    #[serde(default, deserialize_with = "deserialize_nullable")]
+    pub labels: Option<Option<HashMap<String, Option<String>>>>,
+}
+
+impl From<(UpdateBucketParams, PatchBucket)> for UpdateBucketAttr {
+    fn from((params, event): (UpdateBucketParams, PatchBucket)) -> Self {
+        let PatchBucket {
+            versioning,
             default_event_based_hold,
+            acl,
+            default_object_acl,
+            iam_configuration,
+            retention_policy,
+            lifecycle,
+            cors,
+            soft_delete_policy,
+            labels,
+        } = event;
+        let iam_configuration = iam_configuration.unwrap_or_default();
+        UpdateBucketAttr {
+            versioning: versioning.map(|v| v.is_some_and(|v| v.enabled)),
+            default_event_based_hold: default_event_based_hold.map(Option::unwrap_or_default),
+            acl: acl_spec(params.predefined_acl, acl),
+            default_object_acl: acl_spec(params.predefined_default_object_acl, default_object_acl),
+            uniform_bucket_level_access: iam_configuration.ubla_enabled(),
+            public_access_prevention: iam_configuration.public_access_prevention.map(Into::into),
+            retention_period: retention_policy.map(|r| r.map(|r| r.retention_period)),
+            lifecycle: lifecycle.map(|l| l.map(Into::into).unwrap_or_default()),
+            cors: cors.map(cors_rules),
+            soft_delete_retention_duration: soft_delete_policy
+                .map(|s| s.map(|s| s.retention_duration_seconds).unwrap_or_default()),
+            labels: labels.map(|labels| match labels {
+                Some(labels) => MetadataUpdate::Merge(labels),
+                None => MetadataUpdate::Replace(HashMap::new()),
+            }),
         }
     }
 }
 
+/// Represents a request parameter for `list` bucket.
+/// https://cloud.google.com/storage/docs/json_api/v1/buckets/list#parameters
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub enum Projection {
-    Full,
-    NoAcl,
+pub struct ListBucketsParams {
+    pub project: String,
+    pub projection: Option<Projection>,
+    /// Lists only soft-deleted buckets when `true`.
+    pub soft_deleted: Option<bool>,
 }
 
 /// Represents a request parameter for `get` bucket.
 /// https://cloud.google.com/storage/docs/json_api/v1/buckets/get#parameters
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
-#[allow(unused)]
 pub struct GetBucketParams {
+    #[allow(unused)]
     if_metageneration_match: Option<u64>,
+    #[allow(unused)]
     if_metageneration_not_match: Option<u64>,
-    projection: Option<Projection>,
+    pub projection: Option<Projection>,
+    /// Gets a soft-deleted bucket of the given `generation` when `true`.
+    pub soft_deleted: Option<bool>,
+    pub generation: Option<u64>,
 }
 
-#[derive(Debug, Deserialize, EnumString)]
+#[derive(Debug, Clone, Copy, Deserialize, EnumString)]
+#[serde(rename_all = "camelCase")]
 pub enum PredefinedAcl {
     #[strum(serialize = "authenticatedRead")]
     AuthenticatedRead,
@@ -135,7 +698,8 @@ pub enum PredefinedAcl {
     PublicReadWrite,
 }
 
-#[derive(Debug, Deserialize, EnumString)]
+#[derive(Debug, Clone, Copy, Deserialize, EnumString)]
+#[serde(rename_all = "camelCase")]
 pub enum PredefinedDefaultObjectAcl {
     #[strum(serialize = "authenticatedRead")]
     AuthenticatedRead,
@@ -151,30 +715,92 @@ pub enum PredefinedDefaultObjectAcl {
     PublicRead,
 }
 
+impl From<PredefinedAcl> for acl::PredefinedAcl {
+    fn from(value: PredefinedAcl) -> Self {
+        match value {
+            PredefinedAcl::AuthenticatedRead => acl::PredefinedAcl::AuthenticatedRead,
+            PredefinedAcl::Private => acl::PredefinedAcl::Private,
+            PredefinedAcl::ProjectPrivate => acl::PredefinedAcl::ProjectPrivate,
+            PredefinedAcl::PublicRead => acl::PredefinedAcl::PublicRead,
+            PredefinedAcl::PublicReadWrite => acl::PredefinedAcl::PublicReadWrite,
+        }
+    }
+}
+
+impl From<PredefinedDefaultObjectAcl> for acl::PredefinedAcl {
+    fn from(value: PredefinedDefaultObjectAcl) -> Self {
+        match value {
+            PredefinedDefaultObjectAcl::AuthenticatedRead => acl::PredefinedAcl::AuthenticatedRead,
+            PredefinedDefaultObjectAcl::BucketOwnerFullControl => {
+                acl::PredefinedAcl::BucketOwnerFullControl
+            }
+            PredefinedDefaultObjectAcl::BucketOwnerRead => acl::PredefinedAcl::BucketOwnerRead,
+            PredefinedDefaultObjectAcl::Private => acl::PredefinedAcl::Private,
+            PredefinedDefaultObjectAcl::ProjectPrivate => acl::PredefinedAcl::ProjectPrivate,
+            PredefinedDefaultObjectAcl::PublicRead => acl::PredefinedAcl::PublicRead,
+        }
+    }
+}
+
 /// Represents a request parameter for `insert` bucket.
 /// https://cloud.google.com/storage/docs/json_api/v1/buckets/insert#parameters
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
-#[allow(unused)]
 pub struct InsertBucketParams {
-    project: String,
-    enable_object_retention: Option<bool>,
-    predefined_acl: Option<PredefinedAcl>,
-    predefined_default_object_acl: Option<PredefinedDefaultObjectAcl>,
-    projection: Option<Projection>,
+    pub project: String,
+    pub enable_object_retention: Option<bool>,
+    pub predefined_acl: Option<PredefinedAcl>,
+    pub predefined_default_object_acl: Option<PredefinedDefaultObjectAcl>,
+    pub projection: Option<Projection>,
+}
+
+impl InsertBucketParams {
+    /// Defaults to `noAcl` unless an ACL is given by either the parameters or the request body.
+    pub fn projection(&self, event: &InsertBucket) -> Projection {
+        self.projection.unwrap_or(
+            if self.predefined_acl.is_some()
+                || self.predefined_default_object_acl.is_some()
+                || event.acl.is_some()
+                || event.default_object_acl.is_some()
+            {
+                Projection::Full
+            } else {
+                Projection::NoAcl
+            },
+        )
+    }
 }
 
-/// Represents a request parameter for `update` bucket.
+/// Represents a request parameter for `update` and `patch` bucket.
 /// https://cloud.google.com/storge/docs/json_api/v1/buckets/update#parameters
+/// https://cloud.google.com/storge/docs/json_api/v1/buckets/patch#parameters
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
-#[allow(unused)]
 pub struct UpdateBucketParams {
+    #[allow(unused)]
     if_metageneration_match: Option<u64>,
+    #[allow(unused)]
     if_metageneration_not_match: Option<u64>,
-    predefined_acl: Option<PredefinedAcl>,
-    predefined_default_object_acl: Option<PredefinedDefaultObjectAcl>,
-    projection: Option<Projection>,
+    pub predefined_acl: Option<PredefinedAcl>,
+    pub predefined_default_object_acl: Option<PredefinedDefaultObjectAcl>,
+    pub projection: Option<Projection>,
+}
+
+/// Represents a request parameter for `lockRetentionPolicy`.
+/// https://cloud.google.com/storage/docs/json_api/v1/buckets/lockRetentionPolicy#parameters
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LockRetentionPolicyParams {
+    pub if_metageneration_match: u64,
+}
+
+/// Represents a request parameter for `restore` bucket.
+/// https://cloud.google.com/storage/docs/json_api/v1/buckets/restore#parameters
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoreBucketParams {
+    pub generation: u64,
+    pub projection: Option<Projection>,
 }
 
 /// Represents a request parameter for `delete` bucket.
@@ -0,0 +1,87 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::storage::channel::{Channel, CreateChannelAttr};
+
+use super::{deserialize_optional_int64, Kind};
+
+/// Represents `Channel` resource returned by `watchAll`.
+/// https://cloud.google.com/storage/docs/json_api/v1/channels#resource
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChannelResponse {
+    pub kind: Kind,
+    pub id: String,
+    pub resource_id: String,
+    pub resource_uri: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+    /// Milliseconds since the epoch.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expiration: Option<String>,
+}
+
+impl From<Channel> for ChannelResponse {
+    fn from(value: Channel) -> Self {
+        ChannelResponse {
+            kind: Kind::Channel,
+            id: value.id,
+            resource_id: value.resource_id,
+            resource_uri: value.resource_uri,
+            token: value.token,
+            expiration: value.expiration.map(|e| e.timestamp_millis().to_string()),
+        }
+    }
+}
+
+/// Represents the request body for `watchAll`.
+/// https://cloud.google.com/storage/docs/json_api/v1/objects/watchAll#request-body
+#[derive(Debug, Deserialize, garde::Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct WatchChannel {
+    #[garde(length(min = 1))]
+    pub id: String,
+    #[garde(pattern("^web_?hook$"))]
+    pub r#type: String,
+    #[garde(pattern("^https?://"))]
+    pub address: String,
+    #[garde(skip)]
+    pub token: Option<String>,
+    /// Milliseconds since the epoch, after which no notification is sent.
+    #[garde(skip)]
+    #[serde(default, deserialize_with = "deserialize_optional_int64")]
+    pub expiration: Option<u64>,
+}
+
+/// Represents a request parameter for `watchAll`.
+/// https://cloud.google.com/storage/docs/json_api/v1/objects/watchAll#parameters
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WatchAllObjectsParams {
+    pub prefix: Option<String>,
+}
+
+impl From<(WatchAllObjectsParams, WatchChannel)> for CreateChannelAttr {
+    fn from((params, event): (WatchAllObjectsParams, WatchChannel)) -> Self {
+        CreateChannelAttr {
+            id: event.id,
+            address: event.address,
+            token: event.token,
+            expiration: event
+                .expiration
+                .and_then(|e| DateTime::<Utc>::from_timestamp_millis(e as i64)),
+            prefix: params.prefix,
+        }
+    }
+}
+
+/// Represents the request body for `stop`.
+/// https://cloud.google.com/storage/docs/json_api/v1/channels/stop#request-body
+#[derive(Debug, Deserialize, garde::Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct StopChannel {
+    #[garde(length(min = 1))]
+    pub id: String,
+    #[garde(length(min = 1))]
+    pub resource_id: String,
+}
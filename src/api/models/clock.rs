@@ -0,0 +1,53 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::libs::clock::ClockMode;
+
+use super::serialize_timestamp;
+
+/// Represents the state of the emulator clock.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClockResponse {
+    #[serde(serialize_with = "serialize_timestamp")]
+    pub now: DateTime<Utc>,
+    pub mode: Mode,
+}
+
+impl ClockResponse {
+    pub fn new(now: DateTime<Utc>, mode: ClockMode) -> Self {
+        ClockResponse {
+            now,
+            mode: match mode {
+                ClockMode::System { .. } => Mode::System,
+                ClockMode::Fixed { .. } => Mode::Fixed,
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Mode {
+    System,
+    Fixed,
+}
+
+/// Represents the request body to set the emulator clock.
+/// The clock keeps its current mode unless `mode` is given.
+#[derive(Debug, Deserialize, garde::Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct SetClock {
+    #[garde(skip)]
+    pub time: DateTime<Utc>,
+    #[garde(skip)]
+    pub mode: Option<Mode>,
+}
+
+/// Represents the request body to move the emulator clock forward.
+#[derive(Debug, Deserialize, garde::Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct AdvanceClock {
+    #[garde(range(min = 0))]
+    pub seconds: i64,
+}
@@ -0,0 +1,62 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::storage::event::{EventFilter, StorageEvent};
+
+use super::serialize_timestamp;
+
+/// Represents a mutation of a bucket or an object, which is sent as the data of a server-sent event.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventResponse {
+    /// `bucket` or `object`.
+    pub resource: String,
+    /// `create`, `update` or `delete`.
+    pub action: String,
+    pub bucket: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub object: Option<String>,
+    pub generation: String,
+    pub metageneration: String,
+    #[serde(serialize_with = "serialize_timestamp")]
+    pub time: DateTime<Utc>,
+}
+
+impl EventResponse {
+    /// The name of the server-sent event, e.g. `object.create`.
+    pub fn name(&self) -> String {
+        format!("{}.{}", self.resource, self.action)
+    }
+}
+
+impl From<StorageEvent> for EventResponse {
+    fn from(value: StorageEvent) -> Self {
+        EventResponse {
+            resource: value.resource.as_str().to_string(),
+            action: value.action.as_str().to_string(),
+            bucket: value.bucket,
+            object: value.object,
+            generation: value.generation.to_string(),
+            metageneration: value.metageneration.to_string(),
+            time: value.time,
+        }
+    }
+}
+
+/// Represents a request parameter of the event stream.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventParams {
+    pub bucket: Option<String>,
+    /// Limits events to objects whose name starts with the prefix, leaving bucket events out.
+    pub prefix: Option<String>,
+}
+
+impl From<EventParams> for EventFilter {
+    fn from(value: EventParams) -> Self {
+        EventFilter {
+            bucket: value.bucket,
+            prefix: value.prefix,
+        }
+    }
+}
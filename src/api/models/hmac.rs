@@ -0,0 +1,135 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::storage::hmac::{self, HmacKey, UpdateHmacKeyAttr};
+
+use super::{serialize_timestamp, Kind, ListKind, ListResponse};
+
+/// Represents `HmacKey` resource returned only by `create`, which is the only time the secret is revealed.
+/// https://cloud.google.com/storage/docs/json_api/v1/projects/hmacKeys#resource
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HmacKeyResponse {
+    pub kind: Kind,
+    pub metadata: HmacKeyMetadataResponse,
+    pub secret: String,
+}
+
+impl From<HmacKey> for HmacKeyResponse {
+    fn from(value: HmacKey) -> Self {
+        HmacKeyResponse {
+            kind: Kind::HmacKey,
+            secret: value.secret.clone(),
+            metadata: value.into(),
+        }
+    }
+}
+
+/// Represents `HmacKeyMetadata` resource.
+/// https://cloud.google.com/storage/docs/json_api/v1/projects/hmacKeys#resource
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HmacKeyMetadataResponse {
+    pub kind: Kind,
+    pub id: String,
+    pub access_id: String,
+    pub project_id: String,
+    pub service_account_email: String,
+    pub state: HmacKeyState,
+    #[serde(serialize_with = "serialize_timestamp")]
+    pub time_created: DateTime<Utc>,
+    #[serde(serialize_with = "serialize_timestamp")]
+    pub updated: DateTime<Utc>,
+    pub etag: String,
+}
+
+impl From<HmacKey> for HmacKeyMetadataResponse {
+    fn from(value: HmacKey) -> Self {
+        HmacKeyMetadataResponse {
+            kind: Kind::HmacKeyMetadata,
+            id: format!("{}/{}", value.project.id, value.access_id),
+            etag: value.etag(),
+            access_id: value.access_id,
+            project_id: value.project.id,
+            service_account_email: value.service_account_email,
+            state: value.state.into(),
+            time_created: value.time_created,
+            updated: value.updated,
+        }
+    }
+}
+
+impl From<Vec<HmacKey>> for ListResponse<HmacKeyMetadataResponse> {
+    fn from(keys: Vec<HmacKey>) -> Self {
+        ListResponse {
+            kind: ListKind::HmacKeysMetadata,
+            items: keys.into_iter().map(|k| k.into()).collect(),
+            prefixes: vec![],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum HmacKeyState {
+    Active,
+    Inactive,
+    Deleted,
+}
+
+impl From<hmac::HmacKeyState> for HmacKeyState {
+    fn from(value: hmac::HmacKeyState) -> Self {
+        match value {
+            hmac::HmacKeyState::Active => HmacKeyState::Active,
+            hmac::HmacKeyState::Inactive => HmacKeyState::Inactive,
+            hmac::HmacKeyState::Deleted => HmacKeyState::Deleted,
+        }
+    }
+}
+
+impl From<HmacKeyState> for hmac::HmacKeyState {
+    fn from(value: HmacKeyState) -> Self {
+        match value {
+            HmacKeyState::Active => hmac::HmacKeyState::Active,
+            HmacKeyState::Inactive => hmac::HmacKeyState::Inactive,
+            HmacKeyState::Deleted => hmac::HmacKeyState::Deleted,
+        }
+    }
+}
+
+/// Represents a request parameter for `create`.
+/// https://cloud.google.com/storage/docs/json_api/v1/projects/hmacKeys/create#parameters
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateHmacKeyParams {
+    pub service_account_email: String,
+}
+
+/// Represents a request parameter for `list`.
+/// https://cloud.google.com/storage/docs/json_api/v1/projects/hmacKeys/list#parameters
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListHmacKeysParams {
+    pub service_account_email: Option<String>,
+    pub show_deleted_keys: Option<bool>,
+}
+
+/// Represents the request body for `update`, where only `state` can be changed.
+/// https://cloud.google.com/storage/docs/json_api/v1/projects/hmacKeys/update#request-body
+#[derive(Debug, Deserialize, garde::Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateHmacKey {
+    #[garde(skip)]
+    pub state: HmacKeyState,
+    #[garde(skip)]
+    pub etag: Option<String>,
+}
+
+impl From<UpdateHmacKey> for UpdateHmacKeyAttr {
+    fn from(value: UpdateHmacKey) -> Self {
+        UpdateHmacKeyAttr {
+            state: value.state.into(),
+            etag: value.etag,
+        }
+    }
+}
@@ -0,0 +1,107 @@
+use serde::{Deserialize, Serialize};
+
+use crate::storage::iam::{IamBinding, IamPolicy, SetIamPolicyAttr};
+
+use super::Kind;
+
+/// Represents the `Policy` resource of a bucket.
+/// https://cloud.google.com/storage/docs/json_api/v1/buckets/getIamPolicy#response
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PolicyResponse {
+    pub kind: Kind,
+    pub resource_id: String,
+    pub version: u32,
+    pub bindings: Vec<Binding>,
+    pub etag: String,
+}
+
+impl PolicyResponse {
+    pub fn new(bucket: &str, policy: IamPolicy) -> Self {
+        PolicyResponse {
+            kind: Kind::Policy,
+            resource_id: format!("projects/_/buckets/{bucket}"),
+            version: policy.version,
+            etag: policy.etag(),
+            bindings: policy.bindings.into_iter().map(Binding::from).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, garde::Validate)]
+pub struct Binding {
+    #[garde(pattern("^roles/.+$"))]
+    pub role: String,
+    #[garde(inner(pattern(
+        "^(allUsers|allAuthenticatedUsers|(user|serviceAccount|group|domain|principal|principalSet|projectOwner|projectEditor|projectViewer):.+)$"
+    )))]
+    pub members: Vec<String>,
+}
+
+impl From<IamBinding> for Binding {
+    fn from(value: IamBinding) -> Self {
+        Binding {
+            role: value.role,
+            members: value.members,
+        }
+    }
+}
+
+impl From<Binding> for IamBinding {
+    fn from(value: Binding) -> Self {
+        IamBinding {
+            role: value.role,
+            members: value.members,
+        }
+    }
+}
+
+/// Represents the request body for `setIamPolicy`.
+/// https://cloud.google.com/storage/docs/json_api/v1/buckets/setIamPolicy#request-body
+#[derive(Debug, Deserialize, garde::Validate)]
+pub struct SetIamPolicy {
+    #[garde(dive)]
+    #[serde(default)]
+    pub bindings: Vec<Binding>,
+    #[garde(skip)]
+    pub etag: Option<String>,
+    #[garde(range(min = 1, max = 3))]
+    pub version: Option<u32>,
+}
+
+impl From<SetIamPolicy> for SetIamPolicyAttr {
+    fn from(value: SetIamPolicy) -> Self {
+        SetIamPolicyAttr {
+            version: value.version,
+            bindings: value.bindings.into_iter().map(IamBinding::from).collect(),
+            etag: value.etag,
+        }
+    }
+}
+
+/// Represents the response of `testIamPermissions`.
+/// https://cloud.google.com/storage/docs/json_api/v1/buckets/testIamPermissions#response
+#[derive(Debug, Serialize)]
+pub struct TestIamPermissionsResponse {
+    pub kind: Kind,
+    pub permissions: Vec<String>,
+}
+
+/// Represents a request parameter for `testIamPermissions`, where `permissions` is repeated.
+/// https://cloud.google.com/storage/docs/json_api/v1/buckets/testIamPermissions#parameters
+#[derive(Debug)]
+pub struct TestIamPermissionsParams {
+    pub permissions: Vec<String>,
+}
+
+impl From<Vec<(String, String)>> for TestIamPermissionsParams {
+    fn from(query: Vec<(String, String)>) -> Self {
+        TestIamPermissionsParams {
+            permissions: query
+                .into_iter()
+                .filter(|(k, _)| k == "permissions")
+                .map(|(_, v)| v)
+                .collect(),
+        }
+    }
+}
@@ -1,9 +1,20 @@
 use bucket::BucketResponse;
-use serde::Serialize;
+use chrono::{DateTime, SecondsFormat, Utc};
+use object::ObjectResponse;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
-use crate::storage::StorageBucketAttr;
+use crate::storage::{ObjectList, StorageBucketAttr};
 
+pub mod acl;
 pub mod bucket;
+pub mod channel;
+pub mod clock;
+pub mod event;
+pub mod hmac;
+pub mod iam;
+pub mod notification;
+pub mod object;
+pub mod xml;
 
 #[derive(Debug, Serialize)]
 pub struct ListResponse<T: Serialize> {
@@ -22,13 +33,39 @@ impl From<Vec<StorageBucketAttr>> for ListResponse<BucketResponse> {
     }
 }
 
+impl From<ObjectList> for ListResponse<ObjectResponse> {
+    fn from(objects: ObjectList) -> Self {
+        ListResponse {
+            kind: ListKind::Objects,
+            items: objects.items.into_iter().map(|o| o.into()).collect(),
+            prefixes: objects.prefixes,
+        }
+    }
+}
+
+impl<T: Serialize> ListResponse<T> {
+    pub fn map_items(self, f: impl FnMut(T) -> T) -> Self {
+        ListResponse {
+            items: self.items.into_iter().map(f).collect(),
+            ..self
+        }
+    }
+}
+
 #[derive(Debug, Serialize)]
 pub enum ListKind {
     #[serde(rename = "storage#buckets")]
     Buckets,
-    #[allow(dead_code)]
     #[serde(rename = "storage#objects")]
     Objects,
+    #[serde(rename = "storage#bucketAccessControls")]
+    BucketAccessControls,
+    #[serde(rename = "storage#objectAccessControls")]
+    ObjectAccessControls,
+    #[serde(rename = "storage#notifications")]
+    Notifications,
+    #[serde(rename = "storage#hmacKeysMetadata")]
+    HmacKeysMetadata,
 }
 
 // TODO: need to remove `Default` trait here
@@ -37,7 +74,92 @@ pub enum Kind {
     #[default]
     #[serde(rename = "storage#bucket")]
     Bucket,
-    #[allow(dead_code)]
     #[serde(rename = "storage#object")]
     Object,
+    #[serde(rename = "storage#bucketAccessControl")]
+    BucketAccessControl,
+    #[serde(rename = "storage#objectAccessControl")]
+    ObjectAccessControl,
+    #[serde(rename = "storage#policy")]
+    Policy,
+    #[serde(rename = "storage#testIamPermissionsResponse")]
+    TestIamPermissionsResponse,
+    #[serde(rename = "storage#notification")]
+    Notification,
+    #[serde(rename = "api#channel")]
+    Channel,
+    #[serde(rename = "storage#hmacKey")]
+    HmacKey,
+    #[serde(rename = "storage#hmacKeyMetadata")]
+    HmacKeyMetadata,
+    #[serde(rename = "storage#rewriteResponse")]
+    RewriteResponse,
+}
+
+/// Controls whether ACL related properties appear in bucket and object resources.
+/// The default depends on the operation, so each request parameter keeps it optional.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Projection {
+    /// Includes `acl`, `defaultObjectAcl` and `owner`.
+    Full,
+    /// Omits `acl`, `defaultObjectAcl` and `owner`.
+    NoAcl,
+}
+
+/// Formats a timestamp as GCS does, i.e. RFC 3339 in UTC with milliseconds like `2024-01-01T00:00:00.000Z`.
+pub fn serialize_timestamp<S>(time: &DateTime<Utc>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.serialize_str(&time.to_rfc3339_opts(SecondsFormat::Millis, true))
+}
+
+/// Same as `serialize_timestamp`, which is combined with `skip_serializing_if = "Option::is_none"`.
+pub fn serialize_optional_timestamp<S>(
+    time: &Option<DateTime<Utc>>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    match time {
+        Some(time) => serialize_timestamp(time, serializer),
+        None => serializer.serialize_none(),
+    }
+}
+
+/// Accepts an `int64` field either as a string, which is how the JSON API formats it, or as a number.
+pub fn deserialize_int64<'de, D>(deserializer: D) -> Result<u64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Int64 {
+        String(String),
+        Number(u64),
+    }
+    match Int64::deserialize(deserializer)? {
+        Int64::String(s) => s.parse().map_err(serde::de::Error::custom),
+        Int64::Number(n) => Ok(n),
+    }
+}
+
+/// Same as `deserialize_int64`, which is combined with `#[serde(default)]` for an optional field.
+pub fn deserialize_optional_int64<'de, D>(deserializer: D) -> Result<Option<u64>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserialize_int64(deserializer).map(Some)
+}
+
+/// Distinguishes an explicit `null` from an absent field in `patch` requests.
+/// Combined with `#[serde(default)]`, an absent field becomes `None` and `null` becomes `Some(None)`.
+pub fn deserialize_nullable<'de, T, D>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
 }
@@ -0,0 +1,209 @@
+use std::collections::HashMap;
+
+use base64::{prelude::BASE64_STANDARD, Engine};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::storage::notification::{
+    self, CreateNotificationConfigAttr, Notification, NotificationConfig,
+};
+
+use super::{
+    object::ObjectResponse, serialize_timestamp, Kind, ListKind, ListResponse, Projection,
+};
+
+/// Represents `Notifications` resource, whose fields are snake_case unlike other resources.
+/// https://cloud.google.com/storage/docs/json_api/v1/notifications#resource
+#[derive(Debug, Serialize)]
+pub struct NotificationResponse {
+    pub kind: Kind,
+    pub id: String,
+    pub topic: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub event_types: Vec<EventType>,
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub custom_attributes: HashMap<String, String>,
+    pub payload_format: PayloadFormat,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub object_name_prefix: Option<String>,
+    pub etag: String,
+}
+
+impl From<NotificationConfig> for NotificationResponse {
+    fn from(value: NotificationConfig) -> Self {
+        NotificationResponse {
+            kind: Kind::Notification,
+            etag: value.id.clone(),
+            id: value.id,
+            topic: value.topic,
+            event_types: value.event_types.into_iter().map(|e| e.into()).collect(),
+            custom_attributes: value.custom_attributes,
+            payload_format: value.payload_format.into(),
+            object_name_prefix: value.object_name_prefix,
+        }
+    }
+}
+
+impl From<Vec<NotificationConfig>> for ListResponse<NotificationResponse> {
+    fn from(configs: Vec<NotificationConfig>) -> Self {
+        ListResponse {
+            kind: ListKind::Notifications,
+            items: configs.into_iter().map(|c| c.into()).collect(),
+            prefixes: vec![],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum EventType {
+    #[serde(rename = "OBJECT_FINALIZE")]
+    Finalize,
+    #[serde(rename = "OBJECT_METADATA_UPDATE")]
+    MetadataUpdate,
+    #[serde(rename = "OBJECT_DELETE")]
+    Delete,
+    #[serde(rename = "OBJECT_ARCHIVE")]
+    Archive,
+}
+
+impl From<notification::EventType> for EventType {
+    fn from(value: notification::EventType) -> Self {
+        match value {
+            notification::EventType::Finalize => EventType::Finalize,
+            notification::EventType::MetadataUpdate => EventType::MetadataUpdate,
+            notification::EventType::Delete => EventType::Delete,
+            notification::EventType::Archive => EventType::Archive,
+        }
+    }
+}
+
+impl From<EventType> for notification::EventType {
+    fn from(value: EventType) -> Self {
+        match value {
+            EventType::Finalize => notification::EventType::Finalize,
+            EventType::MetadataUpdate => notification::EventType::MetadataUpdate,
+            EventType::Delete => notification::EventType::Delete,
+            EventType::Archive => notification::EventType::Archive,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum PayloadFormat {
+    #[default]
+    JsonApiV1,
+    None,
+}
+
+impl From<notification::PayloadFormat> for PayloadFormat {
+    fn from(value: notification::PayloadFormat) -> Self {
+        match value {
+            notification::PayloadFormat::JsonApiV1 => PayloadFormat::JsonApiV1,
+            notification::PayloadFormat::None => PayloadFormat::None,
+        }
+    }
+}
+
+impl From<PayloadFormat> for notification::PayloadFormat {
+    fn from(value: PayloadFormat) -> Self {
+        match value {
+            PayloadFormat::JsonApiV1 => notification::PayloadFormat::JsonApiV1,
+            PayloadFormat::None => notification::PayloadFormat::None,
+        }
+    }
+}
+
+/// Represents the request body for `insert`.
+/// https://cloud.google.com/storage/docs/json_api/v1/notifications/insert#request-body
+#[derive(Debug, Deserialize, garde::Validate)]
+pub struct InsertNotification {
+    #[garde(length(min = 1))]
+    pub topic: String,
+    #[garde(skip)]
+    #[serde(default)]
+    pub event_types: Vec<EventType>,
+    #[garde(skip)]
+    #[serde(default)]
+    pub custom_attributes: HashMap<String, String>,
+    #[garde(skip)]
+    pub object_name_prefix: Option<String>,
+    #[garde(skip)]
+    #[serde(default)]
+    pub payload_format: PayloadFormat,
+}
+
+impl From<InsertNotification> for CreateNotificationConfigAttr {
+    fn from(value: InsertNotification) -> Self {
+        CreateNotificationConfigAttr {
+            topic: value.topic,
+            event_types: value.event_types.into_iter().map(|e| e.into()).collect(),
+            custom_attributes: value.custom_attributes,
+            object_name_prefix: value.object_name_prefix,
+            payload_format: value.payload_format.into(),
+        }
+    }
+}
+
+/// Represents a Pub/Sub message, whose data is the object resource encoded in base64.
+/// https://cloud.google.com/pubsub/docs/reference/rest/v1/PubsubMessage
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PubsubMessage {
+    pub data: String,
+    pub attributes: HashMap<String, String>,
+    pub message_id: String,
+    #[serde(serialize_with = "serialize_timestamp")]
+    pub publish_time: DateTime<Utc>,
+}
+
+impl From<Notification> for PubsubMessage {
+    fn from(value: Notification) -> Self {
+        let data = value
+            .payload
+            .map(|object| {
+                let object = ObjectResponse::from(object).with_projection(Projection::NoAcl);
+                // Serializing a response model never fails.
+                BASE64_STANDARD.encode(serde_json::to_vec(&object).unwrap())
+            })
+            .unwrap_or_default();
+        PubsubMessage {
+            data,
+            attributes: value.attributes,
+            message_id: value.message_id,
+            publish_time: value.publish_time,
+        }
+    }
+}
+
+/// Represents a message kept by the emulator along with the topic it's published to.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PublishedNotification {
+    pub topic: String,
+    pub message: PubsubMessage,
+}
+
+impl From<Notification> for PublishedNotification {
+    fn from(value: Notification) -> Self {
+        PublishedNotification {
+            topic: value.topic.clone(),
+            message: value.into(),
+        }
+    }
+}
+
+/// Represents the response of the admin endpoint listing published messages, from the oldest one.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PublishedNotificationList {
+    pub notifications: Vec<PublishedNotification>,
+}
+
+/// Represents a request parameter of the admin endpoint listing published messages.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PublishedNotificationParams {
+    /// Limits messages to the topic given either by the full resource name or `projects/{project}/topics/{topic}`.
+    pub topic: Option<String>,
+}
@@ -0,0 +1,917 @@
+use std::collections::HashMap;
+
+use axum::{
+    http::{header, HeaderMap, HeaderName, HeaderValue},
+    response::IntoResponse,
+};
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    libs::{
+        errors::{AppResult, Errors},
+        multipart,
+        transcoding::{gunzip, is_transcodable, is_transcoded},
+    },
+    storage::{
+        acl::{AclSpec, AclTarget},
+        encryption::{self, EncryptionKey},
+        retention, CreateObjectAttr, ListObjectsAttr, MetadataUpdate, OnMemoryStorageObject,
+        Preconditions, StorageObjectAttr, UpdateObjectAttr,
+    },
+};
+
+use super::{
+    acl::{acl_spec, AccessControlResponse, InsertAccessControl, Owner},
+    bucket::PredefinedDefaultObjectAcl,
+    deserialize_nullable, serialize_optional_timestamp, serialize_timestamp, Kind, Projection,
+};
+
+/// Object ACLs accept the same predefined values as default object ACLs of a bucket.
+pub type PredefinedObjectAcl = PredefinedDefaultObjectAcl;
+
+/// Represents the `Objects` resource.
+/// https://cloud.google.com/storage/docs/json_api/v1/objects#resource
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ObjectResponse {
+    pub kind: Kind,
+    pub id: String,
+    pub name: String,
+    pub bucket: String,
+    pub generation: String,
+    pub metageneration: String,
+    pub content_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_encoding: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_disposition: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_language: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cache_control: Option<String>,
+    pub storage_class: String,
+    pub size: String,
+    pub md5_hash: String,
+    pub crc32c: String,
+    pub etag: String,
+    #[serde(serialize_with = "serialize_timestamp")]
+    pub time_created: DateTime<Utc>,
+    #[serde(serialize_with = "serialize_timestamp")]
+    pub updated: DateTime<Utc>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_optional_timestamp"
+    )]
+    pub time_deleted: Option<DateTime<Utc>>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_optional_timestamp"
+    )]
+    pub soft_delete_time: Option<DateTime<Utc>>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_optional_timestamp"
+    )]
+    pub hard_delete_time: Option<DateTime<Utc>>,
+    pub event_based_hold: bool,
+    pub temporary_hold: bool,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_optional_timestamp"
+    )]
+    pub retention_expiration_time: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retention: Option<ObjectRetention>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub customer_encryption: Option<CustomerEncryption>,
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub metadata: HashMap<String, String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub acl: Option<Vec<AccessControlResponse>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub owner: Option<Owner>,
+}
+
+impl ObjectResponse {
+    pub fn with_projection(self, projection: Projection) -> Self {
+        match projection {
+            Projection::Full => self,
+            Projection::NoAcl => ObjectResponse {
+                acl: None,
+                owner: None,
+                ..self
+            },
+        }
+    }
+}
+
+impl From<StorageObjectAttr> for ObjectResponse {
+    fn from(value: StorageObjectAttr) -> Self {
+        let target = AclTarget::Object {
+            name: value.name.clone(),
+            generation: Some(value.generation),
+        };
+        let acl = value
+            .acl
+            .into_iter()
+            .map(|a| AccessControlResponse::new(&value.bucket_name, &target, a))
+            .collect();
+        ObjectResponse {
+            kind: Kind::Object,
+            id: format!("{}/{}/{}", value.bucket_name, value.name, value.generation),
+            name: value.name,
+            bucket: value.bucket_name,
+            generation: value.generation.to_string(),
+            metageneration: value.metageneration.to_string(),
+            content_type: value.content_type,
+            content_encoding: value.content_encoding,
+            content_disposition: value.content_disposition,
+            content_language: value.content_language,
+            cache_control: value.cache_control,
+            storage_class: value.storage_class,
+            size: value.size.to_string(),
+            md5_hash: value.md5_hash,
+            crc32c: value.crc32c,
+            etag: value.etag,
+            time_created: value.time_created,
+            updated: value.updated,
+            time_deleted: value.time_deleted,
+            soft_delete_time: value.soft_delete_time,
+            hard_delete_time: value.hard_delete_time,
+            event_based_hold: value.event_based_hold,
+            temporary_hold: value.temporary_hold,
+            retention_expiration_time: value.retention_expiration_time,
+            retention: value.retention.map(ObjectRetention::from),
+            customer_encryption: value.customer_encryption.map(CustomerEncryption::from),
+            metadata: value.metadata,
+            acl: Some(acl),
+            owner: Some(Owner {
+                entity: value.owner,
+            }),
+        }
+    }
+}
+
+/// Represents `customerEncryption` of an object, which tells the key the object is encrypted by.
+/// https://cloud.google.com/storage/docs/json_api/v1/objects#customerEncryption
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CustomerEncryption {
+    pub encryption_algorithm: String,
+    pub key_sha256: String,
+}
+
+impl From<encryption::CustomerEncryption> for CustomerEncryption {
+    fn from(value: encryption::CustomerEncryption) -> Self {
+        CustomerEncryption {
+            encryption_algorithm: value.encryption_algorithm,
+            key_sha256: value.key_sha256,
+        }
+    }
+}
+
+/// Represents `retention` of an object.
+/// https://cloud.google.com/storage/docs/json_api/v1/objects#retention
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ObjectRetention {
+    pub mode: RetentionMode,
+    #[serde(serialize_with = "serialize_timestamp")]
+    pub retain_until_time: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize)]
+pub enum RetentionMode {
+    Unlocked,
+    Locked,
+}
+
+impl From<retention::ObjectRetention> for ObjectRetention {
+    fn from(value: retention::ObjectRetention) -> Self {
+        ObjectRetention {
+            mode: match value.mode {
+                retention::RetentionMode::Unlocked => RetentionMode::Unlocked,
+                retention::RetentionMode::Locked => RetentionMode::Locked,
+            },
+            retain_until_time: value.retain_until_time,
+        }
+    }
+}
+
+impl From<ObjectRetention> for retention::ObjectRetention {
+    fn from(value: ObjectRetention) -> Self {
+        retention::ObjectRetention {
+            mode: match value.mode {
+                RetentionMode::Unlocked => retention::RetentionMode::Unlocked,
+                RetentionMode::Locked => retention::RetentionMode::Locked,
+            },
+            retain_until_time: value.retain_until_time,
+        }
+    }
+}
+
+/// Serves the content of an object, i.e. `alt=media`.
+/// https://cloud.google.com/storage/docs/json_api/v1/objects/get
+pub struct ObjectMediaResponse {
+    pub object: OnMemoryStorageObject,
+    /// Whether the content is decompressed from gzip, in which case `Content-Encoding` is left out.
+    pub transcoded: bool,
+    /// Whether the content depends on `Accept-Encoding`, which caches are told by `Vary`.
+    pub transcodable: bool,
+}
+
+impl ObjectMediaResponse {
+    /// Decompresses gzipped content unless the request accepts it as it's stored.
+    /// `Range` isn't honored on transcoded content, which is served in whole.
+    /// https://cloud.google.com/storage/docs/transcoding
+    pub fn new(mut object: OnMemoryStorageObject, request: &HeaderMap) -> Self {
+        let accept_encoding = request
+            .get(header::ACCEPT_ENCODING)
+            .and_then(|v| v.to_str().ok());
+        let content_encoding = object.attr.content_encoding.as_deref();
+        let cache_control = object.attr.cache_control.as_deref();
+        let transcodable = is_transcodable(content_encoding, cache_control);
+        let decompressed = is_transcoded(content_encoding, cache_control, accept_encoding)
+            .then(|| gunzip(&object.content))
+            .flatten();
+        let transcoded = decompressed.is_some();
+        if let Some(content) = decompressed {
+            object.content = content;
+        }
+        ObjectMediaResponse {
+            object,
+            transcoded,
+            transcodable,
+        }
+    }
+
+    pub fn into_parts(self) -> (HeaderMap, Bytes) {
+        let OnMemoryStorageObject { attr, content } = self.object;
+        let mut headers = media_headers(attr);
+        if self.transcoded {
+            headers.remove(header::CONTENT_ENCODING);
+            headers.insert(
+                header::WARNING,
+                HeaderValue::from_static("214 UploadServer gunzipped"),
+            );
+        }
+        if self.transcodable {
+            headers.append(header::VARY, HeaderValue::from_static("Accept-Encoding"));
+        }
+        (headers, content)
+    }
+}
+
+impl IntoResponse for ObjectMediaResponse {
+    fn into_response(self) -> axum::response::Response {
+        self.into_parts().into_response()
+    }
+}
+
+/// Describes the object by the headers served along with its content.
+pub fn media_headers(attr: StorageObjectAttr) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    let mut insert = |name: HeaderName, value: &str| {
+        if let Ok(value) = HeaderValue::from_str(value) {
+            headers.insert(name, value);
+        }
+    };
+    insert(header::CONTENT_TYPE, &attr.content_type);
+    insert(
+        header::LAST_MODIFIED,
+        &attr
+            .updated
+            .to_utc()
+            .format("%a, %d %b %Y %H:%M:%S GMT")
+            .to_string(),
+    );
+    insert(
+        HeaderName::from_static("x-goog-generation"),
+        &attr.generation.to_string(),
+    );
+    insert(
+        HeaderName::from_static("x-goog-metageneration"),
+        &attr.metageneration.to_string(),
+    );
+    insert(
+        HeaderName::from_static("x-goog-hash"),
+        &format!("crc32c={},md5={}", attr.crc32c, attr.md5_hash),
+    );
+    insert(
+        HeaderName::from_static("x-goog-stored-content-length"),
+        &attr.size.to_string(),
+    );
+    insert(
+        HeaderName::from_static("x-goog-stored-content-encoding"),
+        attr.content_encoding.as_deref().unwrap_or("identity"),
+    );
+    if let Some(encryption) = &attr.customer_encryption {
+        insert(
+            HeaderName::from_static("x-goog-encryption-algorithm"),
+            &encryption.encryption_algorithm,
+        );
+        insert(
+            HeaderName::from_static("x-goog-encryption-key-sha256"),
+            &encryption.key_sha256,
+        );
+    }
+    let optional_headers = [
+        (header::CONTENT_ENCODING, attr.content_encoding),
+        (header::CONTENT_DISPOSITION, attr.content_disposition),
+        (header::CONTENT_LANGUAGE, attr.content_language),
+        (header::CACHE_CONTROL, attr.cache_control),
+    ];
+    for (name, value) in optional_headers {
+        if let Some(value) = value {
+            insert(name, &value);
+        }
+    }
+    headers
+}
+
+/// Represents the object metadata given on `insert`.
+/// https://cloud.google.com/storage/docs/json_api/v1/objects/insert#request-body
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InsertObject {
+    pub name: Option<String>,
+    pub content_type: Option<String>,
+    pub content_encoding: Option<String>,
+    pub content_disposition: Option<String>,
+    pub content_language: Option<String>,
+    pub cache_control: Option<String>,
+    #[serde(default)]
+    pub metadata: HashMap<String, String>,
+    pub md5_hash: Option<String>,
+    pub crc32c: Option<String>,
+    pub acl: Option<Vec<InsertAccessControl>>,
+    pub event_based_hold: Option<bool>,
+    pub temporary_hold: Option<bool>,
+    pub retention: Option<ObjectRetention>,
+}
+
+/// An uploaded object, which consists of its metadata and media.
+#[derive(Debug)]
+pub struct ObjectUpload {
+    pub attr: CreateObjectAttr,
+    pub content: Bytes,
+}
+
+impl ObjectUpload {
+    /// Decodes the body of `uploadType=media` and `uploadType=multipart` requests.
+    /// https://cloud.google.com/storage/docs/json_api/v1/how-tos/upload
+    pub fn decode(
+        params: &InsertObjectParams,
+        headers: &HeaderMap,
+        body: Bytes,
+    ) -> AppResult<Self, Errors> {
+        let content_type = headers
+            .get(header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
+        let (metadata, media_type, content) = match params.upload_type {
+            UploadType::Media => (InsertObject::default(), content_type, body),
+            UploadType::Multipart => {
+                let content_type = content_type.unwrap_or_default();
+                let [metadata, media] = <[multipart::Part; 2]>::try_from(multipart::parse_related(
+                    &content_type,
+                    &body,
+                )?)
+                .map_err(|_| Errors::BadRequest {
+                    message: "Multipart upload must consist of metadata and media".into(),
+                })?;
+                let metadata =
+                    serde_json::from_slice::<InsertObject>(&metadata.body).map_err(|e| {
+                        Errors::BadRequest {
+                            message: format!("Invalid object metadata: {e}"),
+                        }
+                    })?;
+                (
+                    metadata,
+                    media.headers.get("content-type").cloned(),
+                    media.body,
+                )
+            }
+            UploadType::Resumable => {
+                return Err(Errors::BadRequest {
+                    message: "Resumable uploads are not supported".into(),
+                })
+            }
+        };
+
+        let name = params
+            .name
+            .clone()
+            .or(metadata.name)
+            .ok_or_else(|| Errors::BadRequest {
+                message: "Object name is required".into(),
+            })?;
+        Ok(ObjectUpload {
+            attr: CreateObjectAttr {
+                name,
+                content_type: metadata.content_type.or(media_type),
+                content_encoding: params
+                    .content_encoding
+                    .clone()
+                    .or(metadata.content_encoding),
+                content_disposition: metadata.content_disposition,
+                content_language: metadata.content_language,
+                cache_control: metadata.cache_control,
+                metadata: metadata.metadata,
+                md5_hash: metadata.md5_hash,
+                crc32c: metadata.crc32c,
+                acl: acl_spec(params.predefined_acl, metadata.acl),
+                event_based_hold: metadata.event_based_hold,
+                temporary_hold: metadata.temporary_hold.unwrap_or_default(),
+                retention: metadata.retention.map(Into::into),
+                encryption_key: encryption_key(headers)?,
+            },
+            content,
+        })
+    }
+}
+
+/// The destination of `copy` and `rewrite`, whose metadata is taken from the request body
+/// and falls back to the source object.
+#[derive(Debug)]
+pub struct ObjectRewrite {
+    pub name: String,
+    pub metadata: InsertObject,
+    pub acl: Option<AclSpec>,
+    pub encryption_key: Option<EncryptionKey>,
+}
+
+impl ObjectRewrite {
+    /// Decodes the request body, which may be empty, and the key of the destination.
+    pub fn decode(
+        name: String,
+        params: &RewriteObjectParams,
+        headers: &HeaderMap,
+        body: &[u8],
+    ) -> AppResult<Self, Errors> {
+        let mut metadata = match body.iter().all(u8::is_ascii_whitespace) {
+            true => InsertObject::default(),
+            false => {
+                serde_json::from_slice::<InsertObject>(body).map_err(|e| Errors::BadRequest {
+                    message: format!("Invalid object metadata: {e}"),
+                })?
+            }
+        };
+        let acl = acl_spec(params.destination_predefined_acl, metadata.acl.take());
+        Ok(ObjectRewrite {
+            name,
+            metadata,
+            acl,
+            encryption_key: encryption_key(headers)?,
+        })
+    }
+
+    pub fn attr(self, source: &StorageObjectAttr) -> CreateObjectAttr {
+        let ObjectRewrite {
+            name,
+            metadata,
+            acl,
+            encryption_key,
+        } = self;
+        CreateObjectAttr {
+            name,
+            content_type: metadata
+                .content_type
+                .or_else(|| Some(source.content_type.clone())),
+            content_encoding: metadata
+                .content_encoding
+                .or_else(|| source.content_encoding.clone()),
+            content_disposition: metadata
+                .content_disposition
+                .or_else(|| source.content_disposition.clone()),
+            content_language: metadata
+                .content_language
+                .or_else(|| source.content_language.clone()),
+            cache_control: metadata
+                .cache_control
+                .or_else(|| source.cache_control.clone()),
+            metadata: match metadata.metadata.is_empty() {
+                true => source.metadata.clone(),
+                false => metadata.metadata,
+            },
+            md5_hash: None,
+            crc32c: None,
+            acl,
+            event_based_hold: metadata.event_based_hold,
+            temporary_hold: metadata.temporary_hold.unwrap_or_default(),
+            retention: metadata.retention.map(Into::into),
+            encryption_key,
+        }
+    }
+}
+
+/// Represents the response of `rewrite`, which is always done in a single call.
+/// https://cloud.google.com/storage/docs/json_api/v1/objects/rewrite#response
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RewriteResponse {
+    pub kind: Kind,
+    pub total_bytes_rewritten: String,
+    pub object_size: String,
+    pub done: bool,
+    pub resource: ObjectResponse,
+}
+
+impl From<ObjectResponse> for RewriteResponse {
+    fn from(resource: ObjectResponse) -> Self {
+        RewriteResponse {
+            kind: Kind::RewriteResponse,
+            total_bytes_rewritten: resource.size.clone(),
+            object_size: resource.size.clone(),
+            done: true,
+            resource,
+        }
+    }
+}
+
+/// Takes the customer-supplied encryption key from `x-goog-encryption-*` headers.
+/// https://cloud.google.com/storage/docs/encryption/customer-supplied-keys#rest
+pub fn encryption_key(headers: &HeaderMap) -> AppResult<Option<EncryptionKey>, Errors> {
+    encryption_key_of(headers, "x-goog-encryption")
+}
+
+/// Takes the key of the source object of `copy` and `rewrite` from `x-goog-copy-source-encryption-*` headers.
+pub fn copy_source_encryption_key(headers: &HeaderMap) -> AppResult<Option<EncryptionKey>, Errors> {
+    encryption_key_of(headers, "x-goog-copy-source-encryption")
+}
+
+fn encryption_key_of(
+    headers: &HeaderMap,
+    prefix: &str,
+) -> AppResult<Option<EncryptionKey>, Errors> {
+    let header = |name: &str| {
+        headers
+            .get(format!("{prefix}-{name}"))
+            .and_then(|v| v.to_str().ok())
+    };
+    match (header("algorithm"), header("key"), header("key-sha256")) {
+        (None, None, None) => Ok(None),
+        (Some(algorithm), Some(key), Some(key_sha256)) => {
+            EncryptionKey::decode(algorithm, key, key_sha256).map(Some)
+        }
+        _ => Err(Errors::BadRequest {
+            message: format!(
+                "{prefix}-algorithm, {prefix}-key and {prefix}-key-sha256 have to be given together"
+            ),
+        }),
+    }
+}
+
+/// Represents the request body for `update` object.
+/// Every mutable field is replaced, so omitted fields are reset to their defaults.
+/// https://cloud.google.com/storage/docs/json_api/v1/objects/update
+#[derive(Debug, Deserialize, garde::Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateObject {
+    #[garde(skip)]
+    pub content_type: Option<String>,
+    #[garde(skip)]
+    pub content_encoding: Option<String>,
+    #[garde(skip)]
+    pub content_disposition: Option<String>,
+    #[garde(skip)]
+    pub content_language: Option<String>,
+    #[garde(skip)]
+    pub cache_control: Option<String>,
+    #[garde(skip)]
+    #[serde(default)]
+    pub metadata: HashMap<String, String>,
+    #[garde(dive)]
+    pub acl: Option<Vec<InsertAccessControl>>,
+    #[garde(skip)]
+    pub event_based_hold: Option<bool>,
+    #[garde(skip)]
+    pub temporary_hold: Option<bool>,
+    #[garde(skip)]
+    pub retention: Option<ObjectRetention>,
+}
+
+impl From<(UpdateObjectParams, UpdateObject)> for UpdateObjectAttr {
+    fn from((params, event): (UpdateObjectParams, UpdateObject)) -> Self {
+        let UpdateObject {
+            content_type,
+            content_encoding,
+            content_disposition,
+            content_language,
+            cache_control,
+            metadata,
+            acl,
+            event_based_hold,
+            temporary_hold,
+            retention,
+        } = event;
+        UpdateObjectAttr {
+            content_type: Some(content_type),
+            content_encoding: Some(content_encoding),
+            content_disposition: Some(content_disposition),
+            content_language: Some(content_language),
+            cache_control: Some(cache_control),
+            metadata: Some(MetadataUpdate::Replace(metadata)),
+            acl: acl_spec(params.predefined_acl, acl),
+            event_based_hold: Some(event_based_hold.unwrap_or_default()),
+            temporary_hold: Some(temporary_hold.unwrap_or_default()),
+            retention: Some(retention.map(Into::into)),
+            override_unlocked_retention: params.override_unlocked_retention.unwrap_or_default(),
+        }
+    }
+}
+
+/// Represents the request body for `patch` object.
+/// Omitted fields are left untouched, and fields explicitly set to `null` are cleared.
+/// Custom metadata entries are merged, and entries set to `null` are removed.
+/// https://cloud.google.com/storage/docs/json_api/v1/objects/patch
+#[derive(Debug, Deserialize, garde::Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct PatchObject {
+    #[garde(skip)]
+    #[serde(default, deserialize_with = "deserialize_nullable")]
+    pub content_type: Option<Option<String>>,
+    #[garde(skip)]
+    #[serde(default, deserialize_with = "deserialize_nullable")]
+    pub content_encoding: Option<Option<String>>,
+    #[garde(skip)]
+    #[serde(default, deserialize_with = "deserialize_nullable")]
+    pub content_disposition: Option<Option<String>>,
+    #[garde(skip)]
+    #[serde(default, deserialize_with = "deserialize_nullable")]
+    pub content_language: Option<Option<String>>,
+    #[garde(skip)]
+    #[serde(default, deserialize_with = "deserialize_nullable")]
+    pub cache_control: Option<Option<String>>,
+    #[garde(skip)]
+    #[serde(default, deserialize_with = "deserialize_nullable")]
+    pub metadata: Option<Option<HashMap<String, Option<String>>>>,
+    #[garde(dive)]
+    pub acl: Option<Vec<InsertAccessControl>>,
+    #[garde(skip)]
+    #[serde(default, deserialize_with = "deserialize_nullable")]
+    pub event_based_hold: Option<Option<bool>>,
+    #[garde(skip)]
+    #[serde(default, deserialize_with = "deserialize_nullable")]
+    pub temporary_hold: Option<Option<bool>>,
+    #[garde(skip)]
+    #[serde(default, deserialize_with = "deserialize_nullable")]
+    pub retention: Option<Option<ObjectRetention>>,
+}
+
+impl From<(UpdateObjectParams, PatchObject)> for UpdateObjectAttr {
+    fn from((params, event): (UpdateObjectParams, PatchObject)) -> Self {
+        let PatchObject {
+            content_type,
+            content_encoding,
+            content_disposition,
+            content_language,
+            cache_control,
+            metadata,
+            acl,
+            event_based_hold,
+            temporary_hold,
+            retention,
+        } = event;
+        UpdateObjectAttr {
+            content_type,
+            content_encoding,
+            content_disposition,
+            content_language,
+            cache_control,
+            metadata: metadata.map(|metadata| match metadata {
+                Some(metadata) => MetadataUpdate::Merge(metadata),
+                None => MetadataUpdate::Replace(HashMap::new()),
+            }),
+            acl: acl_spec(params.predefined_acl, acl),
+            event_based_hold: event_based_hold.map(Option::unwrap_or_default),
+            temporary_hold: temporary_hold.map(Option::unwrap_or_default),
+            retention: retention.map(|r| r.map(Into::into)),
+            override_unlocked_retention: params.override_unlocked_retention.unwrap_or_default(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum UploadType {
+    Media,
+    Multipart,
+    Resumable,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Alt {
+    Json,
+    Media,
+}
+
+/// Represents a request parameter for `insert` object.
+/// https://cloud.google.com/storage/docs/json_api/v1/objects/insert#parameters
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InsertObjectParams {
+    pub upload_type: UploadType,
+    pub name: Option<String>,
+    pub content_encoding: Option<String>,
+    pub if_generation_match: Option<u64>,
+    pub if_generation_not_match: Option<u64>,
+    pub if_metageneration_match: Option<u64>,
+    pub if_metageneration_not_match: Option<u64>,
+    pub predefined_acl: Option<PredefinedObjectAcl>,
+    pub projection: Option<Projection>,
+}
+
+impl InsertObjectParams {
+    pub fn preconditions(&self) -> Preconditions {
+        Preconditions {
+            if_generation_match: self.if_generation_match,
+            if_generation_not_match: self.if_generation_not_match,
+            if_metageneration_match: self.if_metageneration_match,
+            if_metageneration_not_match: self.if_metageneration_not_match,
+        }
+    }
+
+    /// Defaults to `noAcl` unless an ACL is given by either `predefinedAcl` or the metadata.
+    pub fn projection(&self, upload: &ObjectUpload) -> Projection {
+        self.projection.unwrap_or(if upload.attr.acl.is_some() {
+            Projection::Full
+        } else {
+            Projection::NoAcl
+        })
+    }
+}
+
+/// Represents a request parameter for `list` object.
+/// https://cloud.google.com/storage/docs/json_api/v1/objects/list#parameters
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListObjectsParams {
+    pub prefix: Option<String>,
+    pub delimiter: Option<String>,
+    pub versions: Option<bool>,
+    pub start_offset: Option<String>,
+    pub end_offset: Option<String>,
+    pub include_trailing_delimiter: Option<bool>,
+    pub projection: Option<Projection>,
+    pub soft_deleted: Option<bool>,
+}
+
+impl From<ListObjectsParams> for ListObjectsAttr {
+    fn from(params: ListObjectsParams) -> Self {
+        ListObjectsAttr {
+            prefix: params.prefix,
+            delimiter: params.delimiter,
+            versions: params.versions.unwrap_or_default(),
+            start_offset: params.start_offset,
+            end_offset: params.end_offset,
+            include_trailing_delimiter: params.include_trailing_delimiter.unwrap_or_default(),
+            soft_deleted: params.soft_deleted.unwrap_or_default(),
+        }
+    }
+}
+
+/// Represents a request parameter for `get` object.
+/// https://cloud.google.com/storage/docs/json_api/v1/objects/get#parameters
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetObjectParams {
+    pub alt: Option<Alt>,
+    pub generation: Option<u64>,
+    pub if_generation_match: Option<u64>,
+    pub if_generation_not_match: Option<u64>,
+    pub if_metageneration_match: Option<u64>,
+    pub if_metageneration_not_match: Option<u64>,
+    pub projection: Option<Projection>,
+    /// Gets a soft-deleted object of the given `generation` when `true`.
+    pub soft_deleted: Option<bool>,
+}
+
+impl GetObjectParams {
+    pub fn preconditions(&self) -> Preconditions {
+        Preconditions {
+            if_generation_match: self.if_generation_match,
+            if_generation_not_match: self.if_generation_not_match,
+            if_metageneration_match: self.if_metageneration_match,
+            if_metageneration_not_match: self.if_metageneration_not_match,
+        }
+    }
+}
+
+/// Represents a request parameter for `update` and `patch` object.
+/// https://cloud.google.com/storage/docs/json_api/v1/objects/update#parameters
+/// https://cloud.google.com/storage/docs/json_api/v1/objects/patch#parameters
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateObjectParams {
+    pub generation: Option<u64>,
+    pub if_generation_match: Option<u64>,
+    pub if_generation_not_match: Option<u64>,
+    pub if_metageneration_match: Option<u64>,
+    pub if_metageneration_not_match: Option<u64>,
+    pub predefined_acl: Option<PredefinedObjectAcl>,
+    pub projection: Option<Projection>,
+    pub override_unlocked_retention: Option<bool>,
+}
+
+impl UpdateObjectParams {
+    pub fn preconditions(&self) -> Preconditions {
+        Preconditions {
+            if_generation_match: self.if_generation_match,
+            if_generation_not_match: self.if_generation_not_match,
+            if_metageneration_match: self.if_metageneration_match,
+            if_metageneration_not_match: self.if_metageneration_not_match,
+        }
+    }
+}
+
+/// Represents a request parameter for `restore` object.
+/// https://cloud.google.com/storage/docs/json_api/v1/objects/restore#parameters
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoreObjectParams {
+    pub generation: u64,
+    pub copy_source_acl: Option<bool>,
+    pub if_generation_match: Option<u64>,
+    pub if_generation_not_match: Option<u64>,
+    pub if_metageneration_match: Option<u64>,
+    pub if_metageneration_not_match: Option<u64>,
+    pub projection: Option<Projection>,
+}
+
+impl RestoreObjectParams {
+    pub fn preconditions(&self) -> Preconditions {
+        Preconditions {
+            if_generation_match: self.if_generation_match,
+            if_generation_not_match: self.if_generation_not_match,
+            if_metageneration_match: self.if_metageneration_match,
+            if_metageneration_not_match: self.if_metageneration_not_match,
+        }
+    }
+}
+
+/// Represents a request parameter for `copy` and `rewrite` object.
+/// https://cloud.google.com/storage/docs/json_api/v1/objects/rewrite#parameters
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RewriteObjectParams {
+    pub source_generation: Option<u64>,
+    pub destination_predefined_acl: Option<PredefinedObjectAcl>,
+    pub if_generation_match: Option<u64>,
+    pub if_generation_not_match: Option<u64>,
+    pub if_metageneration_match: Option<u64>,
+    pub if_metageneration_not_match: Option<u64>,
+    pub if_source_generation_match: Option<u64>,
+    pub if_source_generation_not_match: Option<u64>,
+    pub if_source_metageneration_match: Option<u64>,
+    pub if_source_metageneration_not_match: Option<u64>,
+    pub projection: Option<Projection>,
+}
+
+impl RewriteObjectParams {
+    /// Preconditions on the destination object.
+    pub fn preconditions(&self) -> Preconditions {
+        Preconditions {
+            if_generation_match: self.if_generation_match,
+            if_generation_not_match: self.if_generation_not_match,
+            if_metageneration_match: self.if_metageneration_match,
+            if_metageneration_not_match: self.if_metageneration_not_match,
+        }
+    }
+
+    /// Preconditions on the source object.
+    pub fn source_preconditions(&self) -> Preconditions {
+        Preconditions {
+            if_generation_match: self.if_source_generation_match,
+            if_generation_not_match: self.if_source_generation_not_match,
+            if_metageneration_match: self.if_source_metageneration_match,
+            if_metageneration_not_match: self.if_source_metageneration_not_match,
+        }
+    }
+}
+
+/// Represents a request parameter for `delete` object.
+/// https://cloud.google.com/storage/docs/json_api/v1/objects/delete#parameters
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeleteObjectParams {
+    pub generation: Option<u64>,
+    pub if_generation_match: Option<u64>,
+    pub if_generation_not_match: Option<u64>,
+    pub if_metageneration_match: Option<u64>,
+    pub if_metageneration_not_match: Option<u64>,
+}
+
+impl DeleteObjectParams {
+    pub fn preconditions(&self) -> Preconditions {
+        Preconditions {
+            if_generation_match: self.if_generation_match,
+            if_generation_not_match: self.if_generation_not_match,
+            if_metageneration_match: self.if_metageneration_match,
+            if_metageneration_not_match: self.if_metageneration_not_match,
+        }
+    }
+}
@@ -0,0 +1,722 @@
+use std::collections::HashMap;
+
+use axum::{
+    http::{header, HeaderMap, HeaderName, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+};
+use base64::{prelude::BASE64_STANDARD, Engine};
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    libs::errors::{AppResult, Errors},
+    storage::{
+        acl::{AclSpec, PredefinedAcl},
+        multipart::{CompletedPart, MultipartUpload},
+        soft_delete, CreateBucketAttr, CreateObjectAttr, ListObjectsAttr, ObjectList,
+        Preconditions, StorageBucketAttr, StorageObjectAttr,
+    },
+};
+
+use super::{
+    object::{encryption_key, ObjectMediaResponse, ObjectUpload},
+    serialize_timestamp,
+};
+
+/// The namespace GCS puts on XML API documents, which is borrowed from Amazon S3.
+const XMLNS: &str = "http://doc.s3.amazonaws.com/2006-03-01";
+
+/// Serializes a document of the XML API with the declaration.
+pub struct Xml<T>(pub T);
+
+impl<T: Serialize> IntoResponse for Xml<T> {
+    fn into_response(self) -> Response {
+        match quick_xml::se::to_string(&self.0) {
+            Ok(body) => (
+                [(header::CONTENT_TYPE, "application/xml; charset=UTF-8")],
+                format!("<?xml version='1.0' encoding='UTF-8'?>{body}"),
+            )
+                .into_response(),
+            Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+        }
+    }
+}
+
+/// Represents an error of the XML API.
+/// https://cloud.google.com/storage/docs/xml-api/reference-status
+#[derive(Debug, Serialize)]
+#[serde(rename = "Error", rename_all = "PascalCase")]
+pub struct ErrorResponse {
+    pub code: String,
+    pub message: String,
+}
+
+/// Represents the response of listing buckets.
+/// https://cloud.google.com/storage/docs/xml-api/get-service
+#[derive(Debug, Serialize)]
+#[serde(rename = "ListAllMyBucketsResult", rename_all = "PascalCase")]
+pub struct ListAllMyBucketsResult {
+    #[serde(rename = "@xmlns")]
+    pub xmlns: &'static str,
+    pub owner: Owner,
+    pub buckets: Buckets,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Owner {
+    #[serde(rename = "ID")]
+    pub id: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Buckets {
+    #[serde(rename = "Bucket")]
+    pub items: Vec<BucketEntry>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct BucketEntry {
+    pub name: String,
+    #[serde(serialize_with = "serialize_timestamp")]
+    pub creation_date: DateTime<Utc>,
+}
+
+impl ListAllMyBucketsResult {
+    pub fn new(project: &str, buckets: Vec<StorageBucketAttr>) -> Self {
+        ListAllMyBucketsResult {
+            xmlns: XMLNS,
+            owner: Owner {
+                id: project.to_string(),
+            },
+            buckets: Buckets {
+                items: buckets
+                    .into_iter()
+                    .map(|b| BucketEntry {
+                        name: b.name,
+                        creation_date: b.time_created,
+                    })
+                    .collect(),
+            },
+        }
+    }
+}
+
+/// Represents the response of listing objects.
+/// https://cloud.google.com/storage/docs/xml-api/get-bucket-list
+#[derive(Debug, Serialize)]
+#[serde(rename = "ListBucketResult", rename_all = "PascalCase")]
+pub struct ListBucketResult {
+    #[serde(rename = "@xmlns")]
+    pub xmlns: &'static str,
+    pub name: String,
+    pub prefix: String,
+    pub marker: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_marker: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delimiter: Option<String>,
+    pub max_keys: usize,
+    pub is_truncated: bool,
+    pub contents: Vec<ObjectEntry>,
+    pub common_prefixes: Vec<CommonPrefix>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ObjectEntry {
+    pub key: String,
+    pub generation: u64,
+    pub meta_generation: u64,
+    #[serde(serialize_with = "serialize_timestamp")]
+    pub last_modified: DateTime<Utc>,
+    #[serde(rename = "ETag")]
+    pub etag: String,
+    pub size: u64,
+    pub storage_class: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct CommonPrefix {
+    pub prefix: String,
+}
+
+/// The number of keys returned at most when `max-keys` isn't given.
+const DEFAULT_MAX_KEYS: usize = 1000;
+
+/// A page of objects and prefixes.
+struct Page {
+    contents: Vec<ObjectEntry>,
+    common_prefixes: Vec<CommonPrefix>,
+    /// The last key of the page, which is set only when more keys follow.
+    next_marker: Option<String>,
+}
+
+impl Page {
+    /// Pages objects and prefixes together in the order of their keys, starting after the marker.
+    fn new(list: ObjectList, marker: &str, max_keys: usize) -> Self {
+        enum Entry {
+            Object(Box<StorageObjectAttr>),
+            Prefix(String),
+        }
+        let key = |e: &Entry| match e {
+            Entry::Object(o) => o.name.clone(),
+            Entry::Prefix(p) => p.clone(),
+        };
+        let mut entries = list
+            .items
+            .into_iter()
+            .map(|o| Entry::Object(Box::new(o)))
+            .chain(list.prefixes.into_iter().map(Entry::Prefix))
+            .filter(|e| key(e).as_str() > marker)
+            .collect::<Vec<_>>();
+        entries.sort_by_key(key);
+        let is_truncated = entries.len() > max_keys;
+        entries.truncate(max_keys);
+        let next_marker = is_truncated.then(|| entries.last().map(key)).flatten();
+
+        let mut contents = vec![];
+        let mut common_prefixes = vec![];
+        for entry in entries {
+            match entry {
+                Entry::Object(o) => contents.push(ObjectEntry {
+                    etag: etag(&o),
+                    key: o.name,
+                    generation: o.generation,
+                    meta_generation: o.metageneration,
+                    last_modified: o.updated,
+                    size: o.size,
+                    storage_class: o.storage_class,
+                }),
+                Entry::Prefix(prefix) => common_prefixes.push(CommonPrefix { prefix }),
+            }
+        }
+        Page {
+            contents,
+            common_prefixes,
+            next_marker,
+        }
+    }
+}
+
+impl ListBucketResult {
+    pub fn new(bucket: &str, params: ListBucketParams, list: ObjectList) -> Self {
+        let marker = params.marker.unwrap_or_default();
+        let max_keys = params.max_keys.unwrap_or(DEFAULT_MAX_KEYS);
+        let page = Page::new(list, &marker, max_keys);
+        ListBucketResult {
+            xmlns: XMLNS,
+            name: bucket.to_string(),
+            prefix: params.prefix.unwrap_or_default(),
+            marker,
+            is_truncated: page.next_marker.is_some(),
+            next_marker: page.next_marker,
+            delimiter: params.delimiter,
+            max_keys,
+            contents: page.contents,
+            common_prefixes: page.common_prefixes,
+        }
+    }
+}
+
+/// Represents the response of `ListObjectsV2` of S3, which is chosen by `list-type=2`.
+/// https://docs.aws.amazon.com/AmazonS3/latest/API/API_ListObjectsV2.html
+#[derive(Debug, Serialize)]
+#[serde(rename = "ListBucketResult", rename_all = "PascalCase")]
+pub struct ListBucketV2Result {
+    #[serde(rename = "@xmlns")]
+    pub xmlns: &'static str,
+    pub name: String,
+    pub prefix: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delimiter: Option<String>,
+    pub max_keys: usize,
+    pub key_count: usize,
+    pub is_truncated: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub continuation_token: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_continuation_token: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_after: Option<String>,
+    pub contents: Vec<ObjectEntry>,
+    pub common_prefixes: Vec<CommonPrefix>,
+}
+
+impl ListBucketV2Result {
+    /// The continuation token is the last key of the previous page encoded in base64.
+    pub fn new(
+        bucket: &str,
+        params: ListBucketParams,
+        list: ObjectList,
+    ) -> AppResult<Self, Errors> {
+        let marker = match &params.continuation_token {
+            Some(token) => BASE64_STANDARD
+                .decode(token)
+                .ok()
+                .and_then(|t| String::from_utf8(t).ok())
+                .ok_or_else(|| bad_request(format!("Invalid continuation-token: {token}")))?,
+            None => params.start_after.clone().unwrap_or_default(),
+        };
+        let max_keys = params.max_keys.unwrap_or(DEFAULT_MAX_KEYS);
+        let page = Page::new(list, &marker, max_keys);
+        Ok(ListBucketV2Result {
+            xmlns: XMLNS,
+            name: bucket.to_string(),
+            prefix: params.prefix.unwrap_or_default(),
+            delimiter: params.delimiter,
+            max_keys,
+            key_count: page.contents.len() + page.common_prefixes.len(),
+            is_truncated: page.next_marker.is_some(),
+            continuation_token: params.continuation_token,
+            next_continuation_token: page.next_marker.map(|m| BASE64_STANDARD.encode(m)),
+            start_after: params.start_after,
+            contents: page.contents,
+            common_prefixes: page.common_prefixes,
+        })
+    }
+}
+
+/// Represents a request parameter of listing objects.
+/// https://cloud.google.com/storage/docs/xml-api/get-bucket-list#query_string_parameters
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct ListBucketParams {
+    pub prefix: Option<String>,
+    pub delimiter: Option<String>,
+    pub marker: Option<String>,
+    pub max_keys: Option<usize>,
+    /// `2` for `ListObjectsV2` of S3, which pages by the following parameters instead of `marker`.
+    pub list_type: Option<u8>,
+    pub continuation_token: Option<String>,
+    pub start_after: Option<String>,
+}
+
+/// Represents a request parameter of an object, where multipart uploads are told apart by `uploads` and `uploadId`.
+/// https://cloud.google.com/storage/docs/xml-api/reference-multipart
+#[derive(Debug, Deserialize)]
+pub struct ObjectParams {
+    pub generation: Option<u64>,
+    /// Present without a value when initiating a multipart upload.
+    pub uploads: Option<String>,
+    #[serde(rename = "uploadId")]
+    pub upload_id: Option<String>,
+    #[serde(rename = "partNumber")]
+    pub part_number: Option<u32>,
+    #[serde(rename = "part-number-marker")]
+    pub part_number_marker: Option<u32>,
+    #[serde(rename = "max-parts")]
+    pub max_parts: Option<usize>,
+}
+
+/// Represents the optional request body of creating a bucket.
+/// https://cloud.google.com/storage/docs/xml-api/put-bucket-create#request_body_elements
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct CreateBucketConfiguration {
+    pub location_constraint: Option<String>,
+}
+
+impl CreateBucketConfiguration {
+    /// Parses the body, which may be empty.
+    pub fn parse(body: &[u8]) -> AppResult<Self, Errors> {
+        if body.iter().all(u8::is_ascii_whitespace) {
+            return Ok(Self::default());
+        }
+        let body = std::str::from_utf8(body).map_err(|e| bad_request(e.to_string()))?;
+        quick_xml::de::from_str(body).map_err(|e| bad_request(format!("Malformed XML: {e}")))
+    }
+
+    pub fn into_attr(
+        self,
+        project: String,
+        headers: &HeaderMap,
+    ) -> AppResult<CreateBucketAttr, Errors> {
+        Ok(CreateBucketAttr {
+            project,
+            location: self.location_constraint.unwrap_or_else(|| "US".to_string()),
+            acl: predefined_acl(headers)?,
+            soft_delete_retention_duration: soft_delete::DEFAULT_RETENTION_DURATION,
+            ..Default::default()
+        })
+    }
+}
+
+/// The project given by `x-goog-project-id`, which is required to create and list buckets.
+pub fn project_id(headers: &HeaderMap) -> AppResult<String, Errors> {
+    header_str(headers, "x-goog-project-id")
+        .map(str::to_string)
+        .ok_or_else(|| bad_request("x-goog-project-id header is required".into()))
+}
+
+/// Builds an object upload from the headers of `PUT Object`.
+/// https://cloud.google.com/storage/docs/xml-api/put-object-upload#request_headers
+pub fn object_upload(
+    name: String,
+    headers: &HeaderMap,
+    content: Bytes,
+) -> AppResult<ObjectUpload, Errors> {
+    let header = |name| header_str(headers, name).map(str::to_string);
+    let metadata = headers
+        .iter()
+        .filter_map(|(k, v)| {
+            let key = [Dialect::Goog, Dialect::Amz]
+                .into_iter()
+                .find_map(|d| k.as_str().strip_prefix(&format!("{}meta-", d.prefix())))?;
+            Some((key.to_string(), v.to_str().ok()?.to_string()))
+        })
+        .collect::<HashMap<_, _>>();
+    let hashes = header("x-goog-hash").unwrap_or_default();
+    let hash = |algorithm: &str| {
+        hashes
+            .split(',')
+            .filter_map(|h| h.trim().split_once('='))
+            .find(|(k, _)| *k == algorithm)
+            .map(|(_, v)| v.to_string())
+    };
+    Ok(ObjectUpload {
+        attr: CreateObjectAttr {
+            name,
+            content_type: header("content-type"),
+            content_encoding: header("content-encoding"),
+            content_disposition: header("content-disposition"),
+            content_language: header("content-language"),
+            cache_control: header("cache-control"),
+            metadata,
+            md5_hash: header("content-md5").or_else(|| hash("md5")),
+            crc32c: hash("crc32c"),
+            acl: predefined_acl(headers)?,
+            encryption_key: encryption_key(headers)?,
+            ..Default::default()
+        },
+        content,
+    })
+}
+
+/// Takes preconditions from `x-goog-if-generation-match` and `x-goog-if-metageneration-match`.
+pub fn preconditions(headers: &HeaderMap) -> AppResult<Preconditions, Errors> {
+    let number = |name| {
+        header_str(headers, name)
+            .map(|v| {
+                v.parse::<u64>()
+                    .map_err(|_| bad_request(format!("Invalid {name}: {v}")))
+            })
+            .transpose()
+    };
+    Ok(Preconditions {
+        if_generation_match: number("x-goog-if-generation-match")?,
+        if_metageneration_match: number("x-goog-if-metageneration-match")?,
+        ..Default::default()
+    })
+}
+
+/// Maps `x-goog-acl` or `x-amz-acl` to a predefined ACL.
+/// https://cloud.google.com/storage/docs/access-control/lists#predefined-acl
+fn predefined_acl(headers: &HeaderMap) -> AppResult<Option<AclSpec>, Errors> {
+    let Some(value) =
+        header_str(headers, "x-goog-acl").or_else(|| header_str(headers, "x-amz-acl"))
+    else {
+        return Ok(None);
+    };
+    let acl = match value {
+        "authenticated-read" => PredefinedAcl::AuthenticatedRead,
+        "bucket-owner-full-control" => PredefinedAcl::BucketOwnerFullControl,
+        "bucket-owner-read" => PredefinedAcl::BucketOwnerRead,
+        "private" => PredefinedAcl::Private,
+        "project-private" => PredefinedAcl::ProjectPrivate,
+        "public-read" => PredefinedAcl::PublicRead,
+        "public-read-write" => PredefinedAcl::PublicReadWrite,
+        _ => return Err(bad_request(format!("Invalid x-goog-acl: {value}"))),
+    };
+    Ok(Some(AclSpec::Predefined(acl)))
+}
+
+/// The family of headers a request speaks. GCS accepts `x-amz-*` headers along with `x-goog-*` ones for S3 tools.
+/// https://cloud.google.com/storage/docs/interoperability
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Dialect {
+    Goog,
+    Amz,
+}
+
+impl Dialect {
+    /// Requests signed by AWS Signature Version 4 or carrying any `x-amz-*` header are answered in the S3 dialect.
+    pub fn of(headers: &HeaderMap) -> Self {
+        let signed_by_aws = header_str(headers, header::AUTHORIZATION.as_str())
+            .is_some_and(|v| v.starts_with("AWS4-"));
+        if signed_by_aws || headers.keys().any(|k| k.as_str().starts_with("x-amz-")) {
+            Dialect::Amz
+        } else {
+            Dialect::Goog
+        }
+    }
+
+    fn prefix(&self) -> &'static str {
+        match self {
+            Dialect::Goog => "x-goog-",
+            Dialect::Amz => "x-amz-",
+        }
+    }
+}
+
+/// Serves the content of an object along with its metadata as `x-goog-meta-*` or `x-amz-meta-*` headers.
+/// https://cloud.google.com/storage/docs/xml-api/get-object-download#response_headers
+pub struct ObjectResponse(pub ObjectMediaResponse, pub Dialect);
+
+impl IntoResponse for ObjectResponse {
+    fn into_response(self) -> Response {
+        let (media, dialect) = (self.0, self.1);
+        let attr = &media.object.attr;
+        let mut headers = object_headers(attr);
+        let prefix = dialect.prefix();
+        for (key, value) in &attr.metadata {
+            if let (Ok(name), Ok(value)) = (
+                HeaderName::try_from(format!("{prefix}meta-{key}")),
+                HeaderValue::from_str(value),
+            ) {
+                headers.insert(name, value);
+            }
+        }
+        if let (Ok(name), Ok(value)) = (
+            HeaderName::try_from(format!("{prefix}storage-class")),
+            HeaderValue::from_str(&attr.storage_class),
+        ) {
+            headers.insert(name, value);
+        }
+        let (media_headers, content) = media.into_parts();
+        headers.extend(media_headers);
+        (headers, content).into_response()
+    }
+}
+
+/// Headers identifying a written object, which are returned by `PUT Object`.
+pub fn object_headers(attr: &StorageObjectAttr) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    let values = [
+        (header::ETAG, etag(attr)),
+        (
+            HeaderName::from_static("x-goog-generation"),
+            attr.generation.to_string(),
+        ),
+        (
+            HeaderName::from_static("x-goog-metageneration"),
+            attr.metageneration.to_string(),
+        ),
+        (
+            HeaderName::from_static("x-goog-hash"),
+            format!("crc32c={},md5={}", attr.crc32c, attr.md5_hash),
+        ),
+    ];
+    for (name, value) in values {
+        if let Ok(value) = HeaderValue::from_str(&value) {
+            headers.insert(name, value);
+        }
+    }
+    headers
+}
+
+/// The XML API uses the MD5 hash in hex as the entity tag of an object.
+pub fn etag(attr: &StorageObjectAttr) -> String {
+    let md5 = BASE64_STANDARD.decode(&attr.md5_hash).unwrap_or_default();
+    let hex = md5.iter().map(|b| format!("{b:02x}")).collect::<String>();
+    format!("\"{hex}\"")
+}
+
+fn header_str<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers
+        .get(name)
+        .and_then(|v| v.to_str().ok())
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+}
+
+fn bad_request(message: String) -> Errors {
+    Errors::BadRequest { message }
+}
+
+impl From<&ListBucketParams> for ListObjectsAttr {
+    fn from(params: &ListBucketParams) -> Self {
+        ListObjectsAttr {
+            prefix: params.prefix.clone(),
+            delimiter: params.delimiter.clone(),
+            ..Default::default()
+        }
+    }
+}
+
+/// Represents the response of initiating a multipart upload.
+/// https://cloud.google.com/storage/docs/xml-api/post-object-multipart#response_body_elements
+#[derive(Debug, Serialize)]
+#[serde(rename = "InitiateMultipartUploadResult", rename_all = "PascalCase")]
+pub struct InitiateMultipartUploadResult {
+    #[serde(rename = "@xmlns")]
+    pub xmlns: &'static str,
+    pub bucket: String,
+    pub key: String,
+    pub upload_id: String,
+}
+
+impl From<MultipartUpload> for InitiateMultipartUploadResult {
+    fn from(value: MultipartUpload) -> Self {
+        InitiateMultipartUploadResult {
+            xmlns: XMLNS,
+            bucket: value.bucket,
+            key: value.attr.name,
+            upload_id: value.upload_id,
+        }
+    }
+}
+
+/// The number of parts returned at most when `max-parts` isn't given.
+const DEFAULT_MAX_PARTS: usize = 1000;
+
+/// Represents the response of listing uploaded parts.
+/// https://cloud.google.com/storage/docs/xml-api/get-object-multipart#response_body_elements
+#[derive(Debug, Serialize)]
+#[serde(rename = "ListPartsResult", rename_all = "PascalCase")]
+pub struct ListPartsResult {
+    #[serde(rename = "@xmlns")]
+    pub xmlns: &'static str,
+    pub bucket: String,
+    pub key: String,
+    pub upload_id: String,
+    pub storage_class: &'static str,
+    pub part_number_marker: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_part_number_marker: Option<u32>,
+    pub max_parts: usize,
+    pub is_truncated: bool,
+    pub part: Vec<PartEntry>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct PartEntry {
+    pub part_number: u32,
+    #[serde(serialize_with = "serialize_timestamp")]
+    pub last_modified: DateTime<Utc>,
+    #[serde(rename = "ETag")]
+    pub etag: String,
+    pub size: u64,
+}
+
+impl ListPartsResult {
+    /// Pages parts in the order of their numbers, starting after the marker.
+    pub fn new(upload: MultipartUpload, params: &ObjectParams) -> Self {
+        let marker = params.part_number_marker.unwrap_or_default();
+        let max_parts = params.max_parts.unwrap_or(DEFAULT_MAX_PARTS);
+        let mut parts = upload
+            .parts
+            .into_values()
+            .filter(|p| p.part_number > marker)
+            .collect::<Vec<_>>();
+        let is_truncated = parts.len() > max_parts;
+        parts.truncate(max_parts);
+        ListPartsResult {
+            xmlns: XMLNS,
+            bucket: upload.bucket,
+            key: upload.attr.name,
+            upload_id: upload.upload_id,
+            storage_class: "STANDARD",
+            part_number_marker: marker,
+            next_part_number_marker: is_truncated
+                .then(|| parts.last().map(|p| p.part_number))
+                .flatten(),
+            max_parts,
+            is_truncated,
+            part: parts
+                .into_iter()
+                .map(|p| PartEntry {
+                    part_number: p.part_number,
+                    last_modified: p.last_modified,
+                    etag: format!("\"{}\"", p.etag),
+                    size: p.size,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Represents the request body of completing a multipart upload.
+/// https://cloud.google.com/storage/docs/xml-api/post-object-complete#request_body_elements
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct CompleteMultipartUpload {
+    #[serde(default)]
+    pub part: Vec<CompletedPartEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct CompletedPartEntry {
+    pub part_number: u32,
+    #[serde(rename = "ETag")]
+    pub etag: String,
+}
+
+impl CompleteMultipartUpload {
+    pub fn parse(body: &[u8]) -> AppResult<Vec<CompletedPart>, Errors> {
+        let body = std::str::from_utf8(body).map_err(|e| bad_request(e.to_string()))?;
+        let request: Self = quick_xml::de::from_str(body)
+            .map_err(|e| bad_request(format!("Malformed XML: {e}")))?;
+        Ok(request
+            .part
+            .into_iter()
+            .map(|p| CompletedPart {
+                part_number: p.part_number,
+                etag: p.etag,
+            })
+            .collect())
+    }
+}
+
+/// Represents the response of a form upload with `success_action_status` of `201`.
+/// https://cloud.google.com/storage/docs/xml-api/post-object-forms#response_body_elements
+#[derive(Debug, Serialize)]
+#[serde(rename = "PostResponse", rename_all = "PascalCase")]
+pub struct PostResponse {
+    pub location: String,
+    pub bucket: String,
+    pub key: String,
+    #[serde(rename = "ETag")]
+    pub etag: String,
+}
+
+impl PostResponse {
+    pub fn new(host: &str, object: &StorageObjectAttr) -> Self {
+        PostResponse {
+            location: format!("http://{host}/{}/{}", object.bucket_name, object.name),
+            bucket: object.bucket_name.clone(),
+            key: object.name.clone(),
+            etag: etag(object),
+        }
+    }
+}
+
+/// Represents the response of completing a multipart upload.
+/// https://cloud.google.com/storage/docs/xml-api/post-object-complete#response_body_elements
+#[derive(Debug, Serialize)]
+#[serde(rename = "CompleteMultipartUploadResult", rename_all = "PascalCase")]
+pub struct CompleteMultipartUploadResult {
+    #[serde(rename = "@xmlns")]
+    pub xmlns: &'static str,
+    pub location: String,
+    pub bucket: String,
+    pub key: String,
+    #[serde(rename = "ETag")]
+    pub etag: String,
+}
+
+impl CompleteMultipartUploadResult {
+    pub fn new(host: &str, object: &StorageObjectAttr, etag: String) -> Self {
+        CompleteMultipartUploadResult {
+            xmlns: XMLNS,
+            location: format!("http://{host}/{}/{}", object.bucket_name, object.name),
+            bucket: object.bucket_name.clone(),
+            key: object.name.clone(),
+            etag: format!("\"{etag}\""),
+        }
+    }
+}
@@ -1,16 +1,54 @@
-use axum::{routing::get, Router};
-use storage::bucket::bucket_routes;
+use axum::{
+    routing::{get, post},
+    Router,
+};
+use storage::{
+    acl::acl_routes,
+    bucket::bucket_routes,
+    channel::channel_routes,
+    hmac::hmac_routes,
+    iam::iam_routes,
+    notification::notification_routes,
+    object::{download_routes, object_routes, upload_routes},
+};
 
 use crate::storage::Storage;
+use xml::xml_routes;
 
-use super::handlers::health::health_check;
+use super::handlers::{
+    clock::{advance_clock, get_clock, set_clock},
+    event::stream_events,
+    health::health_check,
+    notification::{clear_published_notifications, list_published_notifications},
+};
 
 pub mod storage;
+pub mod xml;
 
-pub fn routes() -> Router<Storage> {
+pub fn routes(storage: &Storage) -> Router<Storage> {
     let hc_router = Router::new().route("/hc", get(health_check));
-    let storage_router = Router::new().merge(bucket_routes());
+    // Controls the emulator itself, which doesn't exist in GCS.
+    let emulator_router = Router::new()
+        .route("/clock", get(get_clock).put(set_clock))
+        .route("/clock/advance", post(advance_clock))
+        .route("/events", get(stream_events))
+        .route(
+            "/notifications",
+            get(list_published_notifications).delete(clear_published_notifications),
+        );
+    let storage_router = Router::new()
+        .merge(bucket_routes())
+        .merge(object_routes(storage))
+        .merge(acl_routes())
+        .merge(iam_routes())
+        .merge(notification_routes())
+        .merge(channel_routes())
+        .merge(hmac_routes());
     Router::new()
         .merge(hc_router)
+        .nest("/_emulator", emulator_router)
         .nest("/storage/v1", storage_router)
+        .nest("/upload/storage/v1", upload_routes())
+        .nest("/download/storage/v1", download_routes(storage))
+        .merge(xml_routes(storage))
 }
@@ -0,0 +1,54 @@
+use axum::{
+    routing::{delete, get, patch, post, put},
+    Router,
+};
+
+use crate::{
+    api::handlers::storage::acl::{
+        delete_bucket_acl, delete_default_object_acl, delete_object_acl, get_bucket_acl,
+        get_default_object_acl, get_object_acl, insert_bucket_acl, insert_default_object_acl,
+        insert_object_acl, list_bucket_acl, list_default_object_acl, list_object_acl,
+        update_bucket_acl, update_default_object_acl, update_object_acl,
+    },
+    storage::Storage,
+};
+
+pub fn acl_routes() -> Router<Storage> {
+    Router::new()
+        .route("/b/:bucket/acl", get(list_bucket_acl))
+        .route("/b/:bucket/acl", post(insert_bucket_acl))
+        .route("/b/:bucket/acl/:entity", get(get_bucket_acl))
+        .route("/b/:bucket/acl/:entity", put(update_bucket_acl))
+        .route("/b/:bucket/acl/:entity", patch(update_bucket_acl))
+        .route("/b/:bucket/acl/:entity", delete(delete_bucket_acl))
+        .route("/b/:bucket/defaultObjectAcl", get(list_default_object_acl))
+        .route(
+            "/b/:bucket/defaultObjectAcl",
+            post(insert_default_object_acl),
+        )
+        .route(
+            "/b/:bucket/defaultObjectAcl/:entity",
+            get(get_default_object_acl),
+        )
+        .route(
+            "/b/:bucket/defaultObjectAcl/:entity",
+            put(update_default_object_acl),
+        )
+        .route(
+            "/b/:bucket/defaultObjectAcl/:entity",
+            patch(update_default_object_acl),
+        )
+        .route(
+            "/b/:bucket/defaultObjectAcl/:entity",
+            delete(delete_default_object_acl),
+        )
+        .route("/b/:bucket/o/:object/acl", get(list_object_acl))
+        .route("/b/:bucket/o/:object/acl", post(insert_object_acl))
+        .route("/b/:bucket/o/:object/acl/:entity", get(get_object_acl))
+        .route("/b/:bucket/o/:object/acl/:entity", put(update_object_acl))
+        .route("/b/:bucket/o/:object/acl/:entity", patch(update_object_acl))
+        .route(
+            "/b/:bucket/o/:object/acl/:entity",
+            delete(delete_object_acl),
+        )
+}
@@ -5,7 +5,8 @@ use axum::{
 
 use crate::{
     api::handlers::storage::bucket::{
-        delete_bucket, get_bucket, insert_bucket, list_buckets, update_bucket,
+        delete_bucket, get_bucket, insert_bucket, list_buckets, lock_retention_policy,
+        patch_bucket, restore_bucket, update_bucket,
     },
     storage::Storage,
 };
@@ -17,6 +18,11 @@ pub fn bucket_routes() -> Router<Storage> {
         .route("/b", post(insert_bucket))
         // Google doesn't recommend this method but for just in case.
         .route("/b/:bucket", put(update_bucket))
-        .route("/b/:bucket", patch(update_bucket))
+        .route("/b/:bucket", patch(patch_bucket))
         .route("/b/:bucket", delete(delete_bucket))
+        .route(
+            "/b/:bucket/lockRetentionPolicy",
+            post(lock_retention_policy),
+        )
+        .route("/b/:bucket/restore", post(restore_bucket))
 }
@@ -0,0 +1,12 @@
+use axum::{routing::post, Router};
+
+use crate::{
+    api::handlers::storage::channel::{stop_channel, watch_all_objects},
+    storage::Storage,
+};
+
+pub fn channel_routes() -> Router<Storage> {
+    Router::new()
+        .route("/b/:bucket/o/watch", post(watch_all_objects))
+        .route("/channels/stop", post(stop_channel))
+}
@@ -0,0 +1,22 @@
+use axum::{routing::get, Router};
+
+use crate::{
+    api::handlers::storage::hmac::{
+        create_hmac_key, delete_hmac_key, get_hmac_key, list_hmac_keys, update_hmac_key,
+    },
+    storage::Storage,
+};
+
+pub fn hmac_routes() -> Router<Storage> {
+    Router::new()
+        .route(
+            "/projects/:project/hmacKeys",
+            get(list_hmac_keys).post(create_hmac_key),
+        )
+        .route(
+            "/projects/:project/hmacKeys/:access_id",
+            get(get_hmac_key)
+                .put(update_hmac_key)
+                .delete(delete_hmac_key),
+        )
+}
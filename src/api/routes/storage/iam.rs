@@ -0,0 +1,16 @@
+use axum::{
+    routing::{get, put},
+    Router,
+};
+
+use crate::{
+    api::handlers::storage::iam::{get_iam_policy, set_iam_policy, test_iam_permissions},
+    storage::Storage,
+};
+
+pub fn iam_routes() -> Router<Storage> {
+    Router::new()
+        .route("/b/:bucket/iam", get(get_iam_policy))
+        .route("/b/:bucket/iam", put(set_iam_policy))
+        .route("/b/:bucket/iam/testPermissions", get(test_iam_permissions))
+}
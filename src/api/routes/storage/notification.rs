@@ -0,0 +1,25 @@
+use axum::{
+    routing::{delete, get, post},
+    Router,
+};
+
+use crate::{
+    api::handlers::storage::notification::{
+        delete_notification, get_notification, insert_notification, list_notifications,
+    },
+    storage::Storage,
+};
+
+pub fn notification_routes() -> Router<Storage> {
+    Router::new()
+        .route("/b/:bucket/notificationConfigs", get(list_notifications))
+        .route("/b/:bucket/notificationConfigs", post(insert_notification))
+        .route(
+            "/b/:bucket/notificationConfigs/:notification",
+            get(get_notification),
+        )
+        .route(
+            "/b/:bucket/notificationConfigs/:notification",
+            delete(delete_notification),
+        )
+}
@@ -0,0 +1,53 @@
+use axum::{
+    extract::DefaultBodyLimit,
+    middleware,
+    routing::{get, post},
+    Router,
+};
+
+use crate::{
+    api::handlers::{
+        cors::apply_cors,
+        storage::object::{
+            copy_object, delete_object, download_object, get_object, insert_object, list_objects,
+            patch_object, restore_object, rewrite_object, update_object,
+        },
+    },
+    storage::Storage,
+};
+
+/// Only `get` is subject to the `cors` of the bucket, as it serves media downloads by `alt=media`.
+pub fn object_routes(storage: &Storage) -> Router<Storage> {
+    Router::new()
+        .route("/b/:bucket/o", get(list_objects))
+        // Methods chained after the layer are left out of it, while preflight requests still go through it.
+        .route(
+            "/b/:bucket/o/:object",
+            get(get_object)
+                .layer(middleware::from_fn_with_state(storage.clone(), apply_cors))
+                .put(update_object)
+                .patch(patch_object)
+                .delete(delete_object),
+        )
+        .route("/b/:bucket/o/:object/restore", post(restore_object))
+        .route(
+            "/b/:bucket/o/:object/copyTo/b/:destination_bucket/o/:destination_object",
+            post(copy_object),
+        )
+        .route(
+            "/b/:bucket/o/:object/rewriteTo/b/:destination_bucket/o/:destination_object",
+            post(rewrite_object),
+        )
+}
+
+pub fn upload_routes() -> Router<Storage> {
+    Router::new()
+        .route("/b/:bucket/o", post(insert_object))
+        .layer(DefaultBodyLimit::disable())
+}
+
+pub fn download_routes(storage: &Storage) -> Router<Storage> {
+    Router::new()
+        .route("/b/:bucket/o/:object", get(download_object))
+        .layer(middleware::from_fn_with_state(storage.clone(), apply_cors))
+}
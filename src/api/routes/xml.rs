@@ -0,0 +1,197 @@
+use axum::{
+    extract::{DefaultBodyLimit, OriginalUri},
+    http::{header, Request, Uri},
+    middleware,
+    routing::get,
+    Router,
+};
+
+use crate::{
+    api::handlers::{
+        cors::apply_cors,
+        xml::{
+            bucket::{create_bucket, delete_bucket, list_buckets, list_objects},
+            form::post_form,
+            object::{delete_object, get_object, post_object, put_object},
+            signature::verify_signature,
+        },
+    },
+    storage::Storage,
+};
+
+/// The host name under which a bucket is addressed as a subdomain, i.e. `{bucket}.storage.googleapis.com`.
+const VIRTUAL_HOST_SUFFIX: &str = ".storage.googleapis.com";
+
+/// Routes of the XML API, which address buckets and objects by the path like `/{bucket}/{object}`.
+/// Requests signed by an HMAC key are verified before they're handled, and cross-origin requests are answered by the `cors` of the bucket.
+/// https://cloud.google.com/storage/docs/xml-api/overview
+pub fn xml_routes(storage: &Storage) -> Router<Storage> {
+    Router::new()
+        .route("/", get(list_buckets))
+        .route(
+            "/:bucket",
+            get(list_objects)
+                .put(create_bucket)
+                .post(post_form)
+                .delete(delete_bucket),
+        )
+        .route("/:bucket/", get(list_objects).post(post_form))
+        .route(
+            "/:bucket/*object",
+            get(get_object)
+                .put(put_object)
+                .post(post_object)
+                .delete(delete_object),
+        )
+        .layer(middleware::from_fn_with_state(
+            storage.clone(),
+            verify_signature,
+        ))
+        // Preflight requests aren't signed, so they're answered before the signature is verified.
+        .layer(middleware::from_fn_with_state(storage.clone(), apply_cors))
+        .layer(DefaultBodyLimit::disable())
+}
+
+/// Rewrites a virtual-hosted-style request to the path style, e.g. `Host: my-bucket.storage.googleapis.com` and `/a.txt` to `/my-bucket/a.txt`.
+/// This has to run before routing, so it's applied to the whole router rather than as a route layer.
+pub fn rewrite_virtual_host<B>(mut req: Request<B>) -> Request<B> {
+    let bucket = req
+        .headers()
+        .get(header::HOST)
+        .and_then(|v| v.to_str().ok())
+        .map(|host| host.split(':').next().unwrap_or(host))
+        .and_then(|host| host.strip_suffix(VIRTUAL_HOST_SUFFIX))
+        .filter(|bucket| !bucket.is_empty())
+        .map(str::to_string);
+    if let Some(bucket) = bucket {
+        let path_and_query = req
+            .uri()
+            .path_and_query()
+            .map_or("/", |p| p.as_str())
+            .to_string();
+        if let Ok(uri) = format!("/{bucket}{path_and_query}").parse::<Uri>() {
+            let original = std::mem::replace(req.uri_mut(), uri);
+            req.extensions_mut().insert(OriginalUri(original));
+        }
+    }
+    req
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::{to_bytes, Body},
+        extract::Request,
+        http::{header, StatusCode},
+        Extension,
+    };
+    use bytes::Bytes;
+    use googletest::prelude::*;
+    use tower::{Layer, ServiceExt};
+
+    use crate::{
+        api::{
+            handlers::context::AuthMode,
+            routes::{routes, xml::rewrite_virtual_host},
+        },
+        storage::{
+            BucketStorageExt, CreateBucketAttr, CreateObjectAttr, ObjectStorageExt, Preconditions,
+            Storage,
+        },
+    };
+
+    async fn storage_with_object() -> Storage {
+        let storage = Storage::default();
+        let attr = CreateBucketAttr {
+            project: "test-project".into(),
+            ..Default::default()
+        };
+        let _ = storage.create("test-bucket", attr).await;
+        let _ = storage
+            .create_object(
+                "test-bucket",
+                CreateObjectAttr {
+                    name: "dir/a.txt".into(),
+                    ..Default::default()
+                },
+                Bytes::from("hello"),
+                Preconditions::default(),
+            )
+            .await;
+        storage
+    }
+
+    async fn send(storage: &Storage, request: Request) -> (StatusCode, String) {
+        let router = routes(storage)
+            .layer(Extension(AuthMode::default()))
+            .with_state(storage.clone());
+        let res = tower::util::MapRequestLayer::new(rewrite_virtual_host)
+            .layer(router)
+            .oneshot(request)
+            .await
+            .unwrap();
+        let status = res.status();
+        let body = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+        (status, String::from_utf8_lossy(&body).into_owned())
+    }
+
+    fn get(host: &str, uri: &str) -> Request {
+        Request::get(uri)
+            .header(header::HOST, host)
+            .body(Body::empty())
+            .unwrap()
+    }
+
+    #[googletest::test]
+    #[tokio::test]
+    async fn serve_object_by_path_and_virtual_host() {
+        // Arrange
+        let storage = storage_with_object().await;
+
+        // Act
+        let by_path = send(&storage, get("localhost", "/test-bucket/dir/a.txt")).await;
+        let by_host = send(
+            &storage,
+            get("test-bucket.storage.googleapis.com:443", "/dir/a.txt"),
+        )
+        .await;
+        let listed = send(
+            &storage,
+            get("test-bucket.storage.googleapis.com", "/?prefix=dir/"),
+        )
+        .await;
+
+        // Assert
+        expect_that!(by_path, eq(&(StatusCode::OK, "hello".to_string())));
+        expect_that!(by_host, eq(&(StatusCode::OK, "hello".to_string())));
+        expect_that!(listed.0, eq(StatusCode::OK));
+        assert_that!(listed.1, contains_substring("<Key>dir/a.txt</Key>"));
+    }
+
+    #[googletest::test]
+    #[tokio::test]
+    async fn answer_missing_object_and_bucket_by_xml_error() {
+        // Arrange
+        let storage = storage_with_object().await;
+
+        // Act
+        let missing_object = send(&storage, get("localhost", "/test-bucket/b.txt")).await;
+        let missing_bucket = send(
+            &storage,
+            get("other-bucket.storage.googleapis.com", "/dir/a.txt"),
+        )
+        .await;
+
+        // Assert
+        expect_that!(missing_object.0, eq(StatusCode::NOT_FOUND));
+        expect_that!(
+            missing_object.1,
+            contains_substring("<Code>NoSuchKey</Code>")
+        );
+        expect_that!(missing_bucket.0, eq(StatusCode::NOT_FOUND));
+        assert_that!(
+            missing_bucket.1,
+            contains_substring("<Code>NoSuchBucket</Code>")
+        );
+    }
+}
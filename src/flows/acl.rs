@@ -0,0 +1,63 @@
+use crate::{
+    api::models::acl::{InsertAccessControl, UpdateAccessControl},
+    libs::errors::{AppResult, Errors},
+    storage::{
+        acl::{AccessControl, AclStorageExt, AclTarget},
+        Storage,
+    },
+};
+
+pub async fn list(
+    storage: Storage,
+    bucket_name: String,
+    target: AclTarget,
+) -> AppResult<Vec<AccessControl>, Errors> {
+    storage.list_acl(&bucket_name, &target).await
+}
+
+pub async fn find_acl(
+    storage: Storage,
+    bucket_name: String,
+    target: AclTarget,
+    entity: String,
+) -> AppResult<AccessControl, Errors> {
+    storage.get_acl(&bucket_name, &target, &entity).await
+}
+
+pub async fn create_new_acl(
+    storage: Storage,
+    bucket_name: String,
+    target: AclTarget,
+    event: InsertAccessControl,
+) -> AppResult<AccessControl, Errors> {
+    storage
+        .upsert_acl(&bucket_name, &target, event.into())
+        .await
+}
+
+pub async fn update_existing_acl(
+    storage: Storage,
+    bucket_name: String,
+    target: AclTarget,
+    entity: String,
+    event: UpdateAccessControl,
+) -> AppResult<AccessControl, Errors> {
+    // The entry must exist on `update` and `patch`, unlike `insert`.
+    storage.get_acl(&bucket_name, &target, &entity).await?;
+    storage
+        .upsert_acl(
+            &bucket_name,
+            &target,
+            AccessControl::new(entity, event.role.into()),
+        )
+        .await
+}
+
+pub async fn delete_acl(
+    storage: Storage,
+    bucket_name: String,
+    target: AclTarget,
+    entity: String,
+) -> AppResult<AccessControl, Errors> {
+    storage.delete_acl(&bucket_name, &target, &entity).await
+}
@@ -1,36 +1,87 @@
-use std::convert::Infallible;
-
 use crate::{
-    api::models::bucket::{InsertBucket, UpdateBucket},
+    api::models::bucket::{
+        InsertBucket, InsertBucketParams, LockRetentionPolicyParams, PatchBucket, UpdateBucket,
+        UpdateBucketParams,
+    },
     libs::errors::{AppResult, Errors},
-    storage::{BucketStorageExt, Storage, StorageBucketAttr},
+    storage::{
+        retention::RetentionStorageExt, soft_delete::SoftDeleteStorageExt, BucketStorageExt,
+        CreateBucketAttr, Storage, StorageBucketAttr,
+    },
 };
 
-pub async fn list(storage: Storage) -> Result<Vec<StorageBucketAttr>, Infallible> {
-    Ok(storage.list().await.into_iter().collect())
+pub async fn list(storage: Storage, project: String) -> AppResult<Vec<StorageBucketAttr>, Errors> {
+    Ok(storage.list(&project).await.into_iter().collect())
+}
+
+pub async fn list_soft_deleted(
+    storage: Storage,
+    project: String,
+) -> AppResult<Vec<StorageBucketAttr>, Errors> {
+    Ok(storage.list_soft_deleted_buckets(&project).await)
 }
 
 pub async fn find_bucket(
     storage: Storage,
     bucket_name: String,
-) -> Result<Option<StorageBucketAttr>, Infallible> {
+) -> AppResult<Option<StorageBucketAttr>, Errors> {
     Ok(storage.get(&bucket_name).await)
 }
 
+pub async fn find_soft_deleted_bucket(
+    storage: Storage,
+    bucket_name: String,
+    generation: u64,
+) -> AppResult<StorageBucketAttr, Errors> {
+    storage
+        .get_soft_deleted_bucket(&bucket_name, generation)
+        .await
+}
+
 pub async fn create_new_bucket(
     storage: Storage,
+    params: InsertBucketParams,
     event: InsertBucket,
 ) -> AppResult<StorageBucketAttr, Errors> {
     let bucket_name = event.name.clone();
-    storage.create(&bucket_name, event.into()).await
+    storage.create(&bucket_name, (params, event).into()).await
+}
+
+/// Creates a bucket from the attributes built by the XML API.
+pub async fn create_bucket(
+    storage: Storage,
+    bucket_name: String,
+    attr: CreateBucketAttr,
+) -> AppResult<StorageBucketAttr, Errors> {
+    storage.create(&bucket_name, attr).await
 }
 
 pub async fn update_existing_bucket(
     storage: Storage,
     bucket_name: String,
+    params: UpdateBucketParams,
     event: UpdateBucket,
 ) -> AppResult<StorageBucketAttr, Errors> {
-    storage.update(&bucket_name, event.into()).await
+    storage.update(&bucket_name, (params, event).into()).await
+}
+
+pub async fn patch_existing_bucket(
+    storage: Storage,
+    bucket_name: String,
+    params: UpdateBucketParams,
+    event: PatchBucket,
+) -> AppResult<StorageBucketAttr, Errors> {
+    storage.update(&bucket_name, (params, event).into()).await
+}
+
+pub async fn lock_retention_policy(
+    storage: Storage,
+    bucket_name: String,
+    params: LockRetentionPolicyParams,
+) -> AppResult<StorageBucketAttr, Errors> {
+    storage
+        .lock_retention_policy(&bucket_name, params.if_metageneration_match)
+        .await
 }
 
 pub async fn delete_bucket(
@@ -39,3 +90,11 @@ pub async fn delete_bucket(
 ) -> AppResult<StorageBucketAttr, Errors> {
     storage.delete(&bucket_name).await
 }
+
+pub async fn restore_bucket(
+    storage: Storage,
+    bucket_name: String,
+    generation: u64,
+) -> AppResult<StorageBucketAttr, Errors> {
+    storage.restore_bucket(&bucket_name, generation).await
+}
@@ -0,0 +1,23 @@
+use crate::{
+    api::models::channel::{StopChannel, WatchAllObjectsParams, WatchChannel},
+    libs::errors::{AppResult, Errors},
+    storage::{
+        channel::{Channel, ChannelStorageExt},
+        Storage,
+    },
+};
+
+pub async fn watch_all(
+    storage: Storage,
+    bucket_name: String,
+    params: WatchAllObjectsParams,
+    event: WatchChannel,
+) -> AppResult<Channel, Errors> {
+    storage
+        .watch_all_objects(&bucket_name, (params, event).into())
+        .await
+}
+
+pub async fn stop(storage: Storage, event: StopChannel) -> AppResult<(), Errors> {
+    storage.stop_channel(&event.id, &event.resource_id).await
+}
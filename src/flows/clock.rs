@@ -0,0 +1,42 @@
+use chrono::Duration;
+
+use crate::{
+    api::models::clock::{AdvanceClock, ClockResponse, Mode, SetClock},
+    libs::clock::ClockMode,
+    storage::{lifecycle::LifecycleStorageExt, soft_delete::SoftDeleteStorageExt, Storage},
+};
+
+pub fn current_time(storage: Storage) -> ClockResponse {
+    let clock = storage.clock();
+    ClockResponse::new(clock.now(), clock.mode())
+}
+
+pub async fn set_time(storage: Storage, event: SetClock) -> ClockResponse {
+    let clock = storage.clock();
+    let fixed = match event.mode {
+        Some(mode) => mode == Mode::Fixed,
+        None => matches!(clock.mode(), ClockMode::Fixed { .. }),
+    };
+    clock.set(event.time, fixed);
+    catch_up(&storage).await;
+    current_time(storage)
+}
+
+pub async fn advance_time(storage: Storage, event: AdvanceClock) -> ClockResponse {
+    storage.clock().advance(Duration::seconds(event.seconds));
+    catch_up(&storage).await;
+    current_time(storage)
+}
+
+/// Applies what becomes due at the new time right away rather than on the next run of the
+/// lifecycle worker, so that a test moving the clock sees its effect in the next request.
+async fn catch_up(storage: &Storage) {
+    let applied = storage.apply_lifecycle().await;
+    if applied > 0 {
+        tracing::info!(lifecycle.applied = applied, "Applied lifecycle rules");
+    }
+    let purged = storage.purge_soft_deleted().await;
+    if purged > 0 {
+        tracing::info!(soft_delete.purged = purged, "Purged soft-deleted resources");
+    }
+}
@@ -0,0 +1,24 @@
+use futures_util::{stream, Stream};
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::storage::{
+    event::{EventFilter, StorageEvent},
+    Storage,
+};
+
+/// Streams events matching the filter, which starts receiving them right away rather than on the first poll.
+pub fn subscribe(storage: Storage, filter: EventFilter) -> impl Stream<Item = StorageEvent> {
+    let receiver = storage.events().subscribe();
+    stream::unfold((receiver, filter), |(mut receiver, filter)| async move {
+        loop {
+            match receiver.recv().await {
+                Ok(event) if filter.matches(&event) => return Some((event, (receiver, filter))),
+                Ok(_) => continue,
+                Err(RecvError::Lagged(skipped)) => {
+                    tracing::warn!(event.skipped = skipped, "Skipped streaming events");
+                }
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    })
+}
@@ -0,0 +1,55 @@
+use crate::{
+    api::models::hmac::{CreateHmacKeyParams, ListHmacKeysParams, UpdateHmacKey},
+    libs::errors::{AppResult, Errors},
+    storage::{
+        hmac::{HmacKey, HmacKeyStorageExt},
+        Storage,
+    },
+};
+
+pub async fn create(
+    storage: Storage,
+    project: String,
+    params: CreateHmacKeyParams,
+) -> AppResult<HmacKey, Errors> {
+    storage
+        .create_hmac_key(&project, &params.service_account_email)
+        .await
+}
+
+pub async fn list(storage: Storage, project: String, params: ListHmacKeysParams) -> Vec<HmacKey> {
+    storage
+        .list_hmac_keys(
+            &project,
+            params.service_account_email.as_deref(),
+            params.show_deleted_keys.unwrap_or_default(),
+        )
+        .await
+}
+
+pub async fn find_hmac_key(
+    storage: Storage,
+    project: String,
+    access_id: String,
+) -> AppResult<HmacKey, Errors> {
+    storage.get_hmac_key(&project, &access_id).await
+}
+
+pub async fn update_existing_hmac_key(
+    storage: Storage,
+    project: String,
+    access_id: String,
+    event: UpdateHmacKey,
+) -> AppResult<HmacKey, Errors> {
+    storage
+        .update_hmac_key(&project, &access_id, event.into())
+        .await
+}
+
+pub async fn delete_hmac_key(
+    storage: Storage,
+    project: String,
+    access_id: String,
+) -> AppResult<(), Errors> {
+    storage.delete_hmac_key(&project, &access_id).await
+}
@@ -0,0 +1,31 @@
+use crate::{
+    api::models::iam::SetIamPolicy,
+    libs::errors::{AppResult, Errors},
+    storage::{
+        iam::{IamPolicy, IamStorageExt},
+        Storage,
+    },
+};
+
+pub async fn find_policy(storage: Storage, bucket_name: String) -> AppResult<IamPolicy, Errors> {
+    storage.get_iam_policy(&bucket_name).await
+}
+
+pub async fn replace_policy(
+    storage: Storage,
+    bucket_name: String,
+    event: SetIamPolicy,
+) -> AppResult<IamPolicy, Errors> {
+    storage.set_iam_policy(&bucket_name, event.into()).await
+}
+
+pub async fn test_permissions(
+    storage: Storage,
+    bucket_name: String,
+    principal: Option<String>,
+    permissions: Vec<String>,
+) -> AppResult<Vec<String>, Errors> {
+    storage
+        .test_iam_permissions(&bucket_name, principal.as_deref(), permissions)
+        .await
+}
@@ -1 +1,10 @@
+pub mod acl;
 pub mod bucket;
+pub mod channel;
+pub mod clock;
+pub mod event;
+pub mod hmac;
+pub mod iam;
+pub mod multipart;
+pub mod notification;
+pub mod object;
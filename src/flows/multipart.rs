@@ -0,0 +1,85 @@
+use bytes::Bytes;
+
+use crate::{
+    api::models::object::ObjectUpload,
+    libs::errors::{AppResult, Errors},
+    storage::{
+        encryption::EncryptionKey,
+        multipart::{CompletedPart, MultipartStorageExt, MultipartUpload, Part},
+        Preconditions, Storage, StorageObjectAttr,
+    },
+};
+
+pub async fn initiate(
+    storage: Storage,
+    bucket_name: String,
+    upload: ObjectUpload,
+) -> AppResult<MultipartUpload, Errors> {
+    storage
+        .initiate_multipart_upload(&bucket_name, upload.attr)
+        .await
+}
+
+pub async fn upload_part(
+    storage: Storage,
+    bucket_name: String,
+    object_name: String,
+    upload_id: String,
+    part_number: u32,
+    content: Bytes,
+    encryption_key: Option<EncryptionKey>,
+) -> AppResult<Part, Errors> {
+    storage
+        .upload_part(
+            &bucket_name,
+            &object_name,
+            &upload_id,
+            part_number,
+            content,
+            encryption_key.as_ref(),
+        )
+        .await
+}
+
+pub async fn list_parts(
+    storage: Storage,
+    bucket_name: String,
+    object_name: String,
+    upload_id: String,
+) -> AppResult<MultipartUpload, Errors> {
+    storage
+        .list_parts(&bucket_name, &object_name, &upload_id)
+        .await
+}
+
+pub async fn complete(
+    storage: Storage,
+    bucket_name: String,
+    object_name: String,
+    upload_id: String,
+    parts: Vec<CompletedPart>,
+    conditions: Preconditions,
+    encryption_key: Option<EncryptionKey>,
+) -> AppResult<(StorageObjectAttr, String), Errors> {
+    storage
+        .complete_multipart_upload(
+            &bucket_name,
+            &object_name,
+            &upload_id,
+            parts,
+            conditions,
+            encryption_key,
+        )
+        .await
+}
+
+pub async fn abort(
+    storage: Storage,
+    bucket_name: String,
+    object_name: String,
+    upload_id: String,
+) -> AppResult<(), Errors> {
+    storage
+        .abort_multipart_upload(&bucket_name, &object_name, &upload_id)
+        .await
+}
@@ -0,0 +1,57 @@
+use crate::{
+    api::models::notification::InsertNotification,
+    libs::errors::{AppResult, Errors},
+    storage::{
+        notification::{normalize_topic, Notification, NotificationConfig, NotificationStorageExt},
+        Storage,
+    },
+};
+
+pub async fn list(
+    storage: Storage,
+    bucket_name: String,
+) -> AppResult<Vec<NotificationConfig>, Errors> {
+    storage.list_notification_configs(&bucket_name).await
+}
+
+pub async fn find_notification_config(
+    storage: Storage,
+    bucket_name: String,
+    id: String,
+) -> AppResult<NotificationConfig, Errors> {
+    storage.get_notification_config(&bucket_name, &id).await
+}
+
+pub async fn create_new_notification_config(
+    storage: Storage,
+    bucket_name: String,
+    event: InsertNotification,
+) -> AppResult<NotificationConfig, Errors> {
+    storage
+        .create_notification_config(&bucket_name, event.into())
+        .await
+}
+
+pub async fn delete_notification_config(
+    storage: Storage,
+    bucket_name: String,
+    id: String,
+) -> AppResult<(), Errors> {
+    storage.delete_notification_config(&bucket_name, &id).await
+}
+
+pub async fn list_published(
+    storage: Storage,
+    topic: Option<String>,
+) -> AppResult<Vec<Notification>, Errors> {
+    let topic = topic.map(|t| normalize_topic(&t)).transpose()?;
+    let published = storage.published_notifications().await;
+    Ok(published
+        .into_iter()
+        .filter(|n| topic.as_ref().is_none_or(|t| &n.topic == t))
+        .collect())
+}
+
+pub async fn clear_published(storage: Storage) {
+    storage.clear_notifications().await
+}
@@ -0,0 +1,151 @@
+use crate::{
+    api::models::object::{
+        ObjectRewrite, ObjectUpload, PatchObject, RestoreObjectParams, RewriteObjectParams,
+        UpdateObject, UpdateObjectParams,
+    },
+    libs::errors::{AppResult, Errors},
+    storage::{
+        encryption::EncryptionKey, soft_delete::SoftDeleteStorageExt, ListObjectsAttr, ObjectList,
+        ObjectStorageExt, OnMemoryStorageObject, Preconditions, Storage, StorageObjectAttr,
+    },
+};
+
+pub async fn list(
+    storage: Storage,
+    bucket_name: String,
+    attr: ListObjectsAttr,
+) -> AppResult<ObjectList, Errors> {
+    storage.list_objects(&bucket_name, attr).await
+}
+
+pub async fn find_object(
+    storage: Storage,
+    bucket_name: String,
+    object_name: String,
+    generation: Option<u64>,
+    conditions: Preconditions,
+) -> AppResult<OnMemoryStorageObject, Errors> {
+    storage
+        .get_object(&bucket_name, &object_name, generation, conditions)
+        .await
+}
+
+pub async fn find_soft_deleted_object(
+    storage: Storage,
+    bucket_name: String,
+    object_name: String,
+    generation: u64,
+) -> AppResult<StorageObjectAttr, Errors> {
+    storage
+        .get_soft_deleted_object(&bucket_name, &object_name, generation)
+        .await
+}
+
+pub async fn create_new_object(
+    storage: Storage,
+    bucket_name: String,
+    upload: ObjectUpload,
+    conditions: Preconditions,
+) -> AppResult<StorageObjectAttr, Errors> {
+    storage
+        .create_object(&bucket_name, upload.attr, upload.content, conditions)
+        .await
+}
+
+pub async fn update_existing_object(
+    storage: Storage,
+    bucket_name: String,
+    object_name: String,
+    params: UpdateObjectParams,
+    event: UpdateObject,
+) -> AppResult<StorageObjectAttr, Errors> {
+    let generation = params.generation;
+    let conditions = params.preconditions();
+    storage
+        .update_object(
+            &bucket_name,
+            &object_name,
+            generation,
+            (params, event).into(),
+            conditions,
+        )
+        .await
+}
+
+pub async fn patch_existing_object(
+    storage: Storage,
+    bucket_name: String,
+    object_name: String,
+    params: UpdateObjectParams,
+    event: PatchObject,
+) -> AppResult<StorageObjectAttr, Errors> {
+    let generation = params.generation;
+    let conditions = params.preconditions();
+    storage
+        .update_object(
+            &bucket_name,
+            &object_name,
+            generation,
+            (params, event).into(),
+            conditions,
+        )
+        .await
+}
+
+pub async fn delete_object(
+    storage: Storage,
+    bucket_name: String,
+    object_name: String,
+    generation: Option<u64>,
+    conditions: Preconditions,
+) -> AppResult<StorageObjectAttr, Errors> {
+    storage
+        .delete_object(&bucket_name, &object_name, generation, conditions)
+        .await
+}
+
+pub async fn restore_object(
+    storage: Storage,
+    bucket_name: String,
+    object_name: String,
+    params: RestoreObjectParams,
+) -> AppResult<StorageObjectAttr, Errors> {
+    storage
+        .restore_object(
+            &bucket_name,
+            &object_name,
+            params.generation,
+            params.copy_source_acl.unwrap_or_default(),
+            params.preconditions(),
+        )
+        .await
+}
+
+/// Copies the source object to the destination. The content is decrypted by the key of the source
+/// and encrypted by the key of the destination, so that `rewrite` can rotate the key.
+pub async fn rewrite_object(
+    storage: Storage,
+    (source_bucket, source_object): (String, String),
+    source_key: Option<EncryptionKey>,
+    destination_bucket: String,
+    destination: ObjectRewrite,
+    params: RewriteObjectParams,
+) -> AppResult<StorageObjectAttr, Errors> {
+    let source = storage
+        .get_object(
+            &source_bucket,
+            &source_object,
+            params.source_generation,
+            params.source_preconditions(),
+        )
+        .await?
+        .decrypt(source_key.as_ref())?;
+    storage
+        .create_object(
+            &destination_bucket,
+            destination.attr(&source.attr),
+            source.content,
+            params.preconditions(),
+        )
+        .await
+}
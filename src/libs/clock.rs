@@ -0,0 +1,92 @@
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, Duration, Utc};
+
+/// The time source of the emulator, which every timestamp of buckets and objects comes from.
+/// It can be set or moved forward so that tests get deterministic timestamps,
+/// and time based features such as lifecycle rules are testable without waiting.
+#[derive(Debug, Clone, Default)]
+pub struct Clock {
+    mode: Arc<Mutex<ClockMode>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ClockMode {
+    /// Follows the system clock shifted by the offset.
+    System { offset: Duration },
+    /// Stays at the given time until it's set or advanced.
+    Fixed { time: DateTime<Utc> },
+}
+
+impl Default for ClockMode {
+    fn default() -> Self {
+        ClockMode::System {
+            offset: Duration::zero(),
+        }
+    }
+}
+
+impl Clock {
+    /// A clock which stays at `time`.
+    pub fn fixed(time: DateTime<Utc>) -> Self {
+        Self {
+            mode: Arc::new(Mutex::new(ClockMode::Fixed { time })),
+        }
+    }
+
+    pub fn now(&self) -> DateTime<Utc> {
+        match *self.mode.lock().unwrap() {
+            ClockMode::System { offset } => Utc::now() + offset,
+            ClockMode::Fixed { time } => time,
+        }
+    }
+
+    pub fn mode(&self) -> ClockMode {
+        *self.mode.lock().unwrap()
+    }
+
+    /// Sets the current time. A fixed clock stays there, and a system clock keeps ticking from there.
+    pub fn set(&self, time: DateTime<Utc>, fixed: bool) {
+        *self.mode.lock().unwrap() = if fixed {
+            ClockMode::Fixed { time }
+        } else {
+            ClockMode::System {
+                offset: time - Utc::now(),
+            }
+        };
+    }
+
+    /// Moves the clock forward by `by`, which is shared by every clone of the clock.
+    pub fn advance(&self, by: Duration) {
+        match &mut *self.mode.lock().unwrap() {
+            ClockMode::System { offset } => *offset += by,
+            ClockMode::Fixed { time } => *time += by,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, Duration};
+    use googletest::prelude::*;
+
+    use crate::libs::clock::Clock;
+
+    #[googletest::test]
+    fn stay_at_fixed_time_until_advanced() {
+        // Arrange
+        let time = DateTime::parse_from_rfc3339("2024-01-01T00:00:00Z")
+            .unwrap()
+            .to_utc();
+        let clock = Clock::fixed(time);
+
+        // Act
+        let before = clock.now();
+        clock.advance(Duration::days(1));
+        let after = clock.now();
+
+        // Assert
+        expect_that!(before, eq(time));
+        assert_that!(after, eq(time + Duration::days(1)));
+    }
+}
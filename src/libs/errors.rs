@@ -9,6 +9,30 @@ pub enum Errors {
     FailedToWriteStorage { id: String, message: String },
     #[error("Bucket not found: {message}")]
     BucketNotFound { message: String },
+    #[error("{message}")]
+    BucketNotEmpty { message: String },
+    #[error("Object not found: {message}")]
+    ObjectNotFound { message: String },
+    #[error("{message}")]
+    AccessControlNotFound { message: String },
+    #[error("{message}")]
+    NotificationConfigNotFound { message: String },
+    #[error("{message}")]
+    ChannelNotFound { message: String },
+    #[error("{message}")]
+    UploadNotFound { message: String },
+    #[error("{message}")]
+    HmacKeyNotFound { message: String },
+    #[error("{message}")]
+    InvalidAccessKeyId { message: String },
+    #[error("{message}")]
+    SignatureDoesNotMatch { message: String },
+    #[error("{message}")]
+    Forbidden { message: String },
+    #[error("{message}")]
+    PreconditionFailed { message: String },
+    #[error("{message}")]
+    BadRequest { message: String },
 }
 
 pub type AppResult<T, E = eyre::Report> = Result<T, E>;
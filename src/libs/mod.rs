@@ -1,3 +1,8 @@
+pub mod clock;
 pub mod errors;
+pub mod multipart;
+pub mod policy;
 pub mod registry;
+pub mod signature;
 pub mod telemetry;
+pub mod transcoding;
@@ -0,0 +1,137 @@
+use std::collections::HashMap;
+
+use bytes::Bytes;
+
+use super::errors::{AppResult, Errors};
+
+/// A part of a `multipart/related` or `multipart/form-data` body.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Part {
+    /// Header names are lowercased.
+    pub headers: HashMap<String, String>,
+    pub body: Bytes,
+}
+
+impl Part {
+    /// Takes `name` and `filename` of a form field from `Content-Disposition: form-data; name="file"; filename="a.txt"`.
+    pub fn form_field(&self) -> Option<(String, Option<String>)> {
+        let disposition = self.headers.get("content-disposition")?;
+        let param = |key: &str| {
+            disposition
+                .split(';')
+                .filter_map(|p| p.trim().split_once('='))
+                .find(|(k, _)| k.eq_ignore_ascii_case(key))
+                .map(|(_, v)| v.trim_matches('"').to_string())
+        };
+        Some((param("name")?, param("filename")))
+    }
+}
+
+/// Splits a `multipart/related` body, which GCS uses to upload metadata and media in a single request.
+/// https://cloud.google.com/storage/docs/uploading-objects#uploading-an-object
+pub fn parse_related(content_type: &str, body: &Bytes) -> AppResult<Vec<Part>, Errors> {
+    split(content_type, body)
+}
+
+/// Splits a `multipart/form-data` body of an HTML form, whose parts are named by `Content-Disposition`.
+/// https://cloud.google.com/storage/docs/xml-api/post-object-forms
+pub fn parse_form_data(content_type: &str, body: &Bytes) -> AppResult<Vec<Part>, Errors> {
+    split(content_type, body)
+}
+
+fn split(content_type: &str, body: &Bytes) -> AppResult<Vec<Part>, Errors> {
+    let boundary = content_type
+        .split(';')
+        .filter_map(|param| param.trim().strip_prefix("boundary="))
+        .map(|boundary| boundary.trim_matches('"'))
+        .next()
+        .ok_or_else(|| Errors::BadRequest {
+            message: format!("No boundary found in Content-Type: {content_type}"),
+        })?;
+    let delimiter = format!("--{boundary}");
+    let malformed = || Errors::BadRequest {
+        message: "Malformed multipart body".into(),
+    };
+
+    let mut parts = Vec::new();
+    let mut cursor = find(body, delimiter.as_bytes(), 0).ok_or_else(malformed)? + delimiter.len();
+    while !body[cursor..].starts_with(b"--") {
+        let start = cursor + skip_line_break(&body[cursor..]);
+        let end = find(body, delimiter.as_bytes(), start).ok_or_else(malformed)?;
+        let header_end = find(&body[..end], b"\r\n\r\n", start).ok_or_else(malformed)?;
+        let headers = String::from_utf8_lossy(&body[start..header_end])
+            .split("\r\n")
+            .filter_map(|line| line.split_once(':'))
+            .map(|(name, value)| (name.trim().to_lowercase(), value.trim().to_string()))
+            .collect();
+        let content_end = end - trailing_line_break(&body[..end]);
+        parts.push(Part {
+            headers,
+            body: body.slice(header_end + 4..content_end),
+        });
+        cursor = end + delimiter.len();
+    }
+    Ok(parts)
+}
+
+fn find(haystack: &[u8], needle: &[u8], from: usize) -> Option<usize> {
+    haystack
+        .get(from..)?
+        .windows(needle.len())
+        .position(|window| window == needle)
+        .map(|idx| idx + from)
+}
+
+fn skip_line_break(bytes: &[u8]) -> usize {
+    if bytes.starts_with(b"\r\n") {
+        2
+    } else if bytes.starts_with(b"\n") {
+        1
+    } else {
+        0
+    }
+}
+
+fn trailing_line_break(bytes: &[u8]) -> usize {
+    if bytes.ends_with(b"\r\n") {
+        2
+    } else if bytes.ends_with(b"\n") {
+        1
+    } else {
+        0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+    use googletest::prelude::*;
+
+    use crate::libs::multipart::parse_form_data;
+
+    #[googletest::test]
+    fn parse_form_fields() {
+        // Arrange
+        let body = Bytes::from_static(
+            b"--xyz\r\nContent-Disposition: form-data; name=\"key\"\r\n\r\nuploads/${filename}\r\n\
+              --xyz\r\nContent-Disposition: form-data; name=\"file\"; filename=\"a.txt\"\r\n\
+              Content-Type: text/plain\r\n\r\nhello\r\n--xyz--\r\n",
+        );
+
+        // Act
+        let parts = parse_form_data("multipart/form-data; boundary=xyz", &body).unwrap();
+
+        // Assert
+        expect_that!(parts, len(eq(2)));
+        expect_that!(parts[0].form_field(), some(eq(&("key".to_string(), None))));
+        expect_that!(
+            parts[0].body,
+            eq(&Bytes::from_static(b"uploads/${filename}"))
+        );
+        expect_that!(
+            parts[1].form_field(),
+            some(eq(&("file".to_string(), Some("a.txt".to_string()))))
+        );
+        assert_that!(parts[1].body, eq(&Bytes::from_static(b"hello")));
+    }
+}
@@ -0,0 +1,276 @@
+use std::fmt::Display;
+
+use base64::{prelude::BASE64_STANDARD, Engine};
+use chrono::{DateTime, Utc};
+use serde_json::{json, Value};
+
+use super::errors::{AppResult, Errors};
+
+/// Form fields which a policy document doesn't have to cover.
+/// `bucket` is taken from the URL rather than the form.
+const EXEMPT_FIELDS: [&str; 5] = [
+    "bucket",
+    "file",
+    "policy",
+    "x-goog-signature",
+    "x-amz-signature",
+];
+
+/// The policy document of an HTML form upload, which is signed and sent in base64 as the `policy` field.
+/// https://cloud.google.com/storage/docs/authentication/signatures#policy-document
+#[derive(Debug, Clone, PartialEq)]
+pub struct PolicyDocument {
+    pub expiration: DateTime<Utc>,
+    pub conditions: Vec<Condition>,
+}
+
+/// A condition a form upload has to meet. Field names are lowercased without the leading `$`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Condition {
+    /// `{"key": "value"}` or `["eq", "$key", "value"]`.
+    Eq { field: String, value: String },
+    /// `["starts-with", "$key", "prefix"]`, where an empty prefix allows any value.
+    StartsWith { field: String, prefix: String },
+    /// `["content-length-range", min, max]` on the size of the file in bytes.
+    ContentLengthRange { min: u64, max: u64 },
+}
+
+impl PolicyDocument {
+    /// Decodes the `policy` field, i.e. a JSON document in base64.
+    pub fn decode(policy: &str) -> AppResult<Self, Errors> {
+        let invalid = |reason: &str| Errors::BadRequest {
+            message: format!("Invalid policy document: {reason}"),
+        };
+        let document = BASE64_STANDARD
+            .decode(policy.trim())
+            .ok()
+            .and_then(|d| serde_json::from_slice::<Value>(&d).ok())
+            .ok_or_else(|| invalid("not a JSON document in base64"))?;
+        let expiration = document
+            .get("expiration")
+            .and_then(Value::as_str)
+            .and_then(|e| DateTime::parse_from_rfc3339(e).ok())
+            .ok_or_else(|| invalid("expiration is required in RFC 3339 format"))?
+            .to_utc();
+        let conditions = document
+            .get("conditions")
+            .and_then(Value::as_array)
+            .ok_or_else(|| invalid("conditions are required"))?
+            .iter()
+            .map(|c| Condition::parse(c).ok_or_else(|| invalid(&format!("unknown condition {c}"))))
+            .collect::<AppResult<Vec<_>, Errors>>()?;
+        Ok(PolicyDocument {
+            expiration,
+            conditions,
+        })
+    }
+
+    /// Checks the form fields and the size of the file against the document.
+    /// Every field but the exempt ones has to be covered by a condition.
+    pub fn check(
+        &self,
+        fields: &[(String, String)],
+        content_length: u64,
+        now: DateTime<Utc>,
+    ) -> AppResult<(), Errors> {
+        let rejected = |reason: String| Errors::Forbidden {
+            message: format!("Invalid according to Policy: {reason}"),
+        };
+        if now > self.expiration {
+            return Err(rejected("Policy expired.".into()));
+        }
+        let field = |name: &str| {
+            fields
+                .iter()
+                .find(|(k, _)| k.eq_ignore_ascii_case(name))
+                .map(|(_, v)| v.as_str())
+        };
+        for condition in &self.conditions {
+            let satisfied = match condition {
+                Condition::Eq { field: name, value } => field(name) == Some(value),
+                Condition::StartsWith {
+                    field: name,
+                    prefix,
+                } => field(name).is_some_and(|v| v.starts_with(prefix.as_str())),
+                Condition::ContentLengthRange { min, max } => {
+                    (*min..=*max).contains(&content_length)
+                }
+            };
+            if !satisfied {
+                return Err(rejected(format!("Policy Condition failed: {condition}")));
+            }
+        }
+        let extra = fields
+            .iter()
+            .map(|(k, _)| k.to_lowercase())
+            .filter(|k| !EXEMPT_FIELDS.contains(&k.as_str()) && !k.starts_with("x-ignore-"))
+            .filter(|k| !self.conditions.iter().any(|c| c.field() == Some(k)))
+            .collect::<Vec<_>>();
+        if !extra.is_empty() {
+            return Err(rejected(format!(
+                "Extra input fields: {}",
+                extra.join(", ")
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl Condition {
+    fn parse(value: &Value) -> Option<Self> {
+        let field = |name: &Value| Some(name.as_str()?.strip_prefix('$')?.to_lowercase());
+        let number = |n: &Value| n.as_u64().or_else(|| n.as_str()?.parse().ok());
+        match value {
+            Value::Object(map) if map.len() == 1 => {
+                let (name, value) = map.iter().next()?;
+                Some(Condition::Eq {
+                    field: name.to_lowercase(),
+                    value: value.as_str()?.to_string(),
+                })
+            }
+            Value::Array(items) => match items.as_slice() {
+                [op, name, value] if op == "eq" => Some(Condition::Eq {
+                    field: field(name)?,
+                    value: value.as_str()?.to_string(),
+                }),
+                [op, name, prefix] if op == "starts-with" => Some(Condition::StartsWith {
+                    field: field(name)?,
+                    prefix: prefix.as_str()?.to_string(),
+                }),
+                [op, min, max] if op == "content-length-range" => {
+                    Some(Condition::ContentLengthRange {
+                        min: number(min)?,
+                        max: number(max)?,
+                    })
+                }
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// The form field the condition is on, if any.
+    fn field(&self) -> Option<&str> {
+        match self {
+            Condition::Eq { field, .. } | Condition::StartsWith { field, .. } => Some(field),
+            Condition::ContentLengthRange { .. } => None,
+        }
+    }
+}
+
+/// Formats the condition as it's written in the document.
+impl Display for Condition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let value = match self {
+            Condition::Eq { field, value } => json!(["eq", format!("${field}"), value]),
+            Condition::StartsWith { field, prefix } => {
+                json!(["starts-with", format!("${field}"), prefix])
+            }
+            Condition::ContentLengthRange { min, max } => {
+                json!(["content-length-range", min, max])
+            }
+        };
+        write!(f, "{value}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use base64::{prelude::BASE64_STANDARD, Engine};
+    use chrono::{TimeZone, Utc};
+    use googletest::prelude::*;
+
+    use crate::libs::{
+        errors::Errors,
+        policy::{Condition, PolicyDocument},
+    };
+
+    fn encode(document: &str) -> String {
+        BASE64_STANDARD.encode(document)
+    }
+
+    #[googletest::test]
+    fn decode_policy_document() {
+        // Arrange
+        let policy = encode(
+            r#"{"expiration": "2024-01-01T00:00:00Z", "conditions": [
+                {"bucket": "my-bucket"},
+                ["starts-with", "$key", "uploads/"],
+                ["eq", "$Content-Type", "image/png"],
+                ["content-length-range", 0, "1024"]
+            ]}"#,
+        );
+
+        // Act
+        let document = PolicyDocument::decode(&policy);
+
+        // Assert
+        assert_that!(
+            document,
+            ok(eq(&PolicyDocument {
+                expiration: Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap(),
+                conditions: vec![
+                    Condition::Eq {
+                        field: "bucket".into(),
+                        value: "my-bucket".into()
+                    },
+                    Condition::StartsWith {
+                        field: "key".into(),
+                        prefix: "uploads/".into()
+                    },
+                    Condition::Eq {
+                        field: "content-type".into(),
+                        value: "image/png".into()
+                    },
+                    Condition::ContentLengthRange { min: 0, max: 1024 },
+                ],
+            }))
+        );
+    }
+
+    #[googletest::test]
+    fn check_form_against_conditions() {
+        // Arrange
+        let document = PolicyDocument::decode(&encode(
+            r#"{"expiration": "2024-01-01T00:00:00Z", "conditions": [
+                {"bucket": "my-bucket"},
+                ["starts-with", "$key", "uploads/"],
+                ["content-length-range", 1, 10]
+            ]}"#,
+        ))
+        .unwrap();
+        let fields = |key: &str| {
+            vec![
+                ("bucket".to_string(), "my-bucket".to_string()),
+                ("key".to_string(), key.to_string()),
+                ("policy".to_string(), "...".to_string()),
+            ]
+        };
+        let now = Utc.with_ymd_and_hms(2023, 12, 31, 0, 0, 0).unwrap();
+
+        // Act
+        let accepted = document.check(&fields("uploads/a.txt"), 5, now);
+        let wrong_key = document.check(&fields("other/a.txt"), 5, now);
+        let too_large = document.check(&fields("uploads/a.txt"), 11, now);
+        let mut extra_fields = fields("uploads/a.txt");
+        extra_fields.push(("acl".to_string(), "public-read".to_string()));
+        let extra = document.check(&extra_fields, 5, now);
+        let expired = document.check(
+            &fields("uploads/a.txt"),
+            5,
+            Utc.with_ymd_and_hms(2024, 1, 2, 0, 0, 0).unwrap(),
+        );
+
+        // Assert
+        expect_that!(accepted, ok(anything()));
+        expect_that!(wrong_key, err(matches_pattern!(Errors::Forbidden { .. })));
+        expect_that!(too_large, err(matches_pattern!(Errors::Forbidden { .. })));
+        expect_that!(
+            extra,
+            err(matches_pattern!(Errors::Forbidden {
+                message: contains_substring("acl")
+            }))
+        );
+        assert_that!(expired, err(matches_pattern!(Errors::Forbidden { .. })));
+    }
+}
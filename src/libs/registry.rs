@@ -1 +1,69 @@
+use std::{collections::HashMap, sync::Arc};
 
+/// Project number returned for projects that have no configured mapping.
+pub const DEFAULT_PROJECT_NUMBER: u64 = 1;
+
+/// A project identified by both its ID and its number, as GCS exposes both of them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Project {
+    pub id: String,
+    pub number: u64,
+}
+
+/// Resolves the `project` request parameter, which can be either a project ID or a project number.
+#[derive(Debug, Clone)]
+pub struct ProjectRegistry {
+    numbers: Arc<HashMap<String, u64>>,
+    default_number: u64,
+}
+
+impl Default for ProjectRegistry {
+    fn default() -> Self {
+        Self::new(HashMap::new(), DEFAULT_PROJECT_NUMBER)
+    }
+}
+
+impl ProjectRegistry {
+    pub fn new(numbers: HashMap<String, u64>, default_number: u64) -> Self {
+        Self {
+            numbers: Arc::new(numbers),
+            default_number,
+        }
+    }
+
+    pub fn resolve(&self, project: &str) -> Project {
+        if let Some(number) = self.numbers.get(project) {
+            return Project {
+                id: project.to_string(),
+                number: *number,
+            };
+        }
+        match project.parse::<u64>() {
+            Ok(number) => Project {
+                id: self
+                    .numbers
+                    .iter()
+                    .find(|(_, n)| **n == number)
+                    .map_or_else(|| project.to_string(), |(id, _)| id.clone()),
+                number,
+            },
+            Err(_) => Project {
+                id: project.to_string(),
+                number: self.default_number,
+            },
+        }
+    }
+
+    /// Whether a resource owned by `owner` belongs to the `project` request parameter.
+    /// Numbers are compared only when the parameter is a number or both IDs are mapped,
+    /// since unmapped project IDs share the default number.
+    pub fn matches(&self, project: &str, owner: &Project) -> bool {
+        let resolved = self.resolve(project);
+        if resolved.id == owner.id {
+            return true;
+        }
+        let by_number = project.parse::<u64>().is_ok()
+            || (self.numbers.contains_key(project) && self.numbers.contains_key(&owner.id));
+        by_number && resolved.number == owner.number
+    }
+}
@@ -0,0 +1,92 @@
+use std::io::Read;
+
+use bytes::Bytes;
+use flate2::read::GzDecoder;
+
+/// Whether an object stored with `Content-Encoding: gzip` is decompressed on download, which it is
+/// unless the request accepts gzip or the object has `Cache-Control: no-transform`.
+/// https://cloud.google.com/storage/docs/transcoding#decompressive_transcoding
+pub fn is_transcoded(
+    content_encoding: Option<&str>,
+    cache_control: Option<&str>,
+    accept_encoding: Option<&str>,
+) -> bool {
+    is_transcodable(content_encoding, cache_control)
+        && !accepts_gzip(accept_encoding.unwrap_or_default())
+}
+
+/// Whether the content served depends on `Accept-Encoding`, i.e. the object is gzipped without `no-transform`.
+pub fn is_transcodable(content_encoding: Option<&str>, cache_control: Option<&str>) -> bool {
+    let gzipped = content_encoding.is_some_and(|e| e.trim().eq_ignore_ascii_case("gzip"));
+    let no_transform = cache_control.is_some_and(|c| {
+        c.split(',')
+            .any(|d| d.trim().eq_ignore_ascii_case("no-transform"))
+    });
+    gzipped && !no_transform
+}
+
+/// Decompresses gzipped content, or `None` if it isn't valid gzip.
+pub fn gunzip(content: &[u8]) -> Option<Bytes> {
+    let mut decompressed = Vec::new();
+    GzDecoder::new(content)
+        .read_to_end(&mut decompressed)
+        .ok()?;
+    Some(decompressed.into())
+}
+
+/// Whether `Accept-Encoding` lists gzip, which `q=0` refuses.
+fn accepts_gzip(accept_encoding: &str) -> bool {
+    accept_encoding.split(',').any(|coding| {
+        let mut params = coding.split(';').map(str::trim);
+        let name = params.next().unwrap_or_default();
+        let refused = params.any(|p| {
+            p.strip_prefix("q=")
+                .and_then(|q| q.parse::<f32>().ok())
+                .is_some_and(|q| q == 0.0)
+        });
+        name.eq_ignore_ascii_case("gzip") && !refused
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use flate2::{write::GzEncoder, Compression};
+    use googletest::prelude::*;
+
+    use crate::libs::transcoding::{gunzip, is_transcoded};
+
+    #[googletest::test]
+    fn transcode_unless_gzip_is_accepted() {
+        // Act
+        let plain = is_transcoded(None, None, None);
+        let gzipped = is_transcoded(Some("gzip"), None, None);
+        let accepted = is_transcoded(Some("gzip"), None, Some("deflate, gzip;q=0.8"));
+        let refused = is_transcoded(Some("gzip"), None, Some("gzip;q=0"));
+        let no_transform = is_transcoded(Some("gzip"), Some("public, no-transform"), None);
+
+        // Assert
+        expect_that!(plain, eq(false));
+        expect_that!(gzipped, eq(true));
+        expect_that!(accepted, eq(false));
+        expect_that!(refused, eq(true));
+        assert_that!(no_transform, eq(false));
+    }
+
+    #[googletest::test]
+    fn decompress_gzipped_content() {
+        // Arrange
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(b"hello, world").unwrap();
+        let gzipped = encoder.finish().unwrap();
+
+        // Act
+        let decompressed = gunzip(&gzipped);
+        let invalid = gunzip(b"hello, world");
+
+        // Assert
+        expect_that!(decompressed, some(eq(&b"hello, world"[..])));
+        assert_that!(invalid, none());
+    }
+}
@@ -0,0 +1,38 @@
+use crate::{
+    api::models::{object::ObjectResponse, Projection},
+    storage::{channel::ChannelMessage, Storage},
+};
+
+/// Posts messages of notification channels to their addresses as they come.
+pub async fn run_channel_worker(storage: Storage) {
+    let receiver = storage.channels().subscribe();
+    super::delivery::run("channel", receiver, |client, message| {
+        vec![request(client, message)]
+    })
+    .await
+}
+
+/// Builds the request GCS sends for Object Change Notification, whose body is the object resource.
+/// https://cloud.google.com/storage/docs/object-change-notification#notification_types
+fn request(client: &reqwest::Client, message: ChannelMessage) -> reqwest::RequestBuilder {
+    let mut req = client
+        .post(&message.address)
+        .header("X-Goog-Channel-ID", &message.channel_id)
+        .header("X-Goog-Resource-ID", &message.resource_id)
+        .header("X-Goog-Resource-URI", &message.resource_uri)
+        .header("X-Goog-Resource-State", message.state.as_str())
+        .header("X-Goog-Message-Number", message.message_number.to_string());
+    if let Some(token) = &message.token {
+        req = req.header("X-Goog-Channel-Token", token);
+    }
+    if let Some(expiration) = message.expiration {
+        req = req.header(
+            "X-Goog-Channel-Expiration",
+            expiration.format("%a, %d %b %Y %H:%M:%S GMT").to_string(),
+        );
+    }
+    match message.payload {
+        Some(object) => req.json(&ObjectResponse::from(object).with_projection(Projection::NoAcl)),
+        None => req,
+    }
+}
@@ -1,4 +1,8 @@
+use chrono::{DateTime, Utc};
 use clap::Parser;
+use rsa::{pkcs1::DecodeRsaPublicKey, pkcs8::DecodePublicKey, RsaPublicKey};
+
+use crate::libs::registry::DEFAULT_PROJECT_NUMBER;
 
 #[derive(Parser, Debug)]
 #[command(version, about, long_about = None)]
@@ -9,6 +13,77 @@ pub struct CommandArgs {
     pub port: u16,
     #[arg(long, default_value_t = Protocol::Http)]
     pub scheme: Protocol,
+    /// Maps a project ID to its project number, e.g. `--project my-project=123456789`.
+    /// Can be specified multiple times.
+    #[arg(long = "project", value_name = "ID=NUMBER", value_parser = parse_project_mapping)]
+    pub projects: Vec<(String, u64)>,
+    /// Project number returned for projects without a mapping given by `--project`.
+    #[arg(long, default_value_t = DEFAULT_PROJECT_NUMBER)]
+    pub default_project_number: u64,
+    /// Authorizes requests by IAM policies and ACLs of buckets and objects, and denies the rest with `403`.
+    /// The caller is identified by the `x-emulator-principal` header or the bearer token.
+    #[arg(long)]
+    pub enforce_auth: bool,
+    /// Interval in seconds at which lifecycle rules of buckets are applied.
+    #[arg(long, default_value_t = 60, value_parser = clap::value_parser!(u64).range(1..))]
+    pub lifecycle_interval: u64,
+    /// Starts the emulator clock fixed at the given RFC 3339 time, e.g. `2024-01-01T00:00:00Z`.
+    /// The clock can be set or advanced through `/_emulator/clock` either way.
+    #[arg(long, value_name = "TIME", value_parser = parse_time)]
+    pub fixed_time: Option<DateTime<Utc>>,
+    /// Sends every message published by notification configurations to the URL as a Pub/Sub push request.
+    #[arg(long, value_name = "URL")]
+    pub notification_push_url: Option<String>,
+    /// Publishes every message of notification configurations to a Pub/Sub emulator at `host:port`.
+    /// Topics must exist in the Pub/Sub emulator.
+    #[arg(long, value_name = "HOST")]
+    pub pubsub_emulator_host: Option<String>,
+    /// Registers an HMAC key of a service account, e.g. `--hmac-key sa@my-project.iam.gserviceaccount.com=GOOG1EXAMPLE:secret`.
+    /// Requests signed with the key are authorized as the service account. Can be specified multiple times.
+    #[arg(long = "hmac-key", value_name = "EMAIL=ACCESS_ID:SECRET", value_parser = parse_hmac_key)]
+    pub hmac_keys: Vec<HmacKeyArg>,
+    /// Registers a PEM encoded RSA public key of a service account, e.g. `--service-account-key sa@my-project.iam.gserviceaccount.com=key.pem`.
+    /// Signed URLs made with the private key are authorized as the service account. Can be specified multiple times.
+    #[arg(long = "service-account-key", value_name = "EMAIL=PATH", value_parser = parse_service_account_key)]
+    pub service_account_keys: Vec<ServiceAccountKeyArg>,
+}
+
+/// An HMAC key given by `--hmac-key`, whose project is taken from the service account email.
+#[derive(Clone)]
+pub struct HmacKeyArg {
+    pub project: String,
+    pub service_account_email: String,
+    pub access_id: String,
+    pub secret: String,
+}
+
+// The arguments are logged on startup, where the secret shouldn't appear.
+impl std::fmt::Debug for HmacKeyArg {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HmacKeyArg")
+            .field("project", &self.project)
+            .field("service_account_email", &self.service_account_email)
+            .field("access_id", &self.access_id)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A public key given by `--service-account-key`, whose project is taken from the service account email.
+#[derive(Clone)]
+pub struct ServiceAccountKeyArg {
+    pub project: String,
+    pub service_account_email: String,
+    pub public_key: RsaPublicKey,
+}
+
+// The key itself is too long to be logged on startup.
+impl std::fmt::Debug for ServiceAccountKeyArg {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ServiceAccountKeyArg")
+            .field("project", &self.project)
+            .field("service_account_email", &self.service_account_email)
+            .finish_non_exhaustive()
+    }
 }
 
 #[derive(Debug, Clone, clap::ValueEnum, strum::Display)]
@@ -17,3 +92,64 @@ pub enum Protocol {
     Http,
     Https,
 }
+
+fn parse_time(s: &str) -> Result<DateTime<Utc>, String> {
+    DateTime::parse_from_rfc3339(s)
+        .map(|t| t.to_utc())
+        .map_err(|e| format!("invalid RFC 3339 time `{s}`: {e}"))
+}
+
+fn parse_hmac_key(s: &str) -> Result<HmacKeyArg, String> {
+    let (email, key) = s
+        .split_once('=')
+        .ok_or_else(|| format!("invalid EMAIL=ACCESS_ID:SECRET: no `=` found in `{s}`"))?;
+    let (access_id, secret) = key
+        .split_once(':')
+        .ok_or_else(|| format!("invalid ACCESS_ID:SECRET: no `:` found in `{key}`"))?;
+    Ok(HmacKeyArg {
+        project: service_account_project(email)?,
+        service_account_email: email.to_string(),
+        access_id: access_id.to_string(),
+        secret: secret.to_string(),
+    })
+}
+
+fn parse_service_account_key(s: &str) -> Result<ServiceAccountKeyArg, String> {
+    let (email, path) = s
+        .split_once('=')
+        .ok_or_else(|| format!("invalid EMAIL=PATH: no `=` found in `{s}`"))?;
+    let pem = std::fs::read_to_string(path)
+        .map_err(|e| format!("failed to read the public key `{path}`: {e}"))?;
+    // Both `BEGIN PUBLIC KEY` and `BEGIN RSA PUBLIC KEY` are accepted.
+    let public_key = RsaPublicKey::from_public_key_pem(&pem)
+        .or_else(|_| RsaPublicKey::from_pkcs1_pem(&pem))
+        .map_err(|e| format!("invalid RSA public key `{path}`: {e}"))?;
+    Ok(ServiceAccountKeyArg {
+        project: service_account_project(email)?,
+        service_account_email: email.to_string(),
+        public_key,
+    })
+}
+
+fn service_account_project(email: &str) -> Result<String, String> {
+    email
+        .split_once('@')
+        .and_then(|(_, domain)| domain.strip_suffix(".iam.gserviceaccount.com"))
+        .filter(|p| !p.is_empty())
+        .map(str::to_string)
+        .ok_or_else(|| {
+            format!(
+                "invalid service account `{email}`: expected NAME@PROJECT.iam.gserviceaccount.com"
+            )
+        })
+}
+
+fn parse_project_mapping(s: &str) -> Result<(String, u64), String> {
+    let (id, number) = s
+        .split_once('=')
+        .ok_or_else(|| format!("invalid ID=NUMBER: no `=` found in `{s}`"))?;
+    let number = number
+        .parse()
+        .map_err(|e| format!("invalid project number `{number}`: {e}"))?;
+    Ok((id.to_string(), number))
+}
@@ -0,0 +1,43 @@
+use std::time::Duration;
+
+use tokio::sync::broadcast::{self, error::RecvError};
+
+/// How long a delivery may take before it's given up.
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Posts the requests built by `requests` for every message received, until the sender is dropped.
+/// Each request is sent on its own task, so that an unresponsive target doesn't hold up the others,
+/// and messages may arrive out of order as they do in GCS.
+/// Delivery is best effort, so failures are only logged.
+pub async fn run<T: Clone>(
+    kind: &'static str,
+    mut receiver: broadcast::Receiver<T>,
+    mut requests: impl FnMut(&reqwest::Client, T) -> Vec<reqwest::RequestBuilder>,
+) {
+    let client = reqwest::Client::builder()
+        .timeout(DELIVERY_TIMEOUT)
+        .build()
+        .expect("A client with the bundled root certificates is always built");
+    loop {
+        let message = match receiver.recv().await {
+            Ok(message) => message,
+            Err(RecvError::Lagged(skipped)) => {
+                tracing::warn!(
+                    delivery.kind = kind,
+                    delivery.skipped = skipped,
+                    "Skipped delivering messages"
+                );
+                continue;
+            }
+            Err(RecvError::Closed) => return,
+        };
+        for request in requests(&client, message) {
+            tokio::spawn(async move {
+                let res = request.send().await.and_then(|res| res.error_for_status());
+                if let Err(e) = res {
+                    tracing::warn!(delivery.kind = kind, error = %e, "Failed to deliver a message");
+                }
+            });
+        }
+    }
+}
@@ -0,0 +1,20 @@
+use std::time::Duration;
+
+use crate::storage::{lifecycle::LifecycleStorageExt, soft_delete::SoftDeleteStorageExt, Storage};
+
+/// Applies lifecycle rules of every bucket periodically, as GCS does in the background.
+/// Soft-deleted objects and buckets past their `hardDeleteTime` are purged along the way.
+pub async fn run_lifecycle_worker(storage: Storage, interval: Duration) {
+    let mut ticker = tokio::time::interval(interval);
+    loop {
+        ticker.tick().await;
+        let applied = storage.apply_lifecycle().await;
+        if applied > 0 {
+            tracing::info!(lifecycle.applied = applied, "Applied lifecycle rules");
+        }
+        let purged = storage.purge_soft_deleted().await;
+        if purged > 0 {
+            tracing::info!(soft_delete.purged = purged, "Purged soft-deleted resources");
+        }
+    }
+}
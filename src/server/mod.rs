@@ -1,10 +1,25 @@
+use std::time::Duration;
+
+use axum::{extract::Request, Extension, ServiceExt};
 use commands::CommandArgs;
 use eyre::Context;
 use tokio::net::TcpListener;
+use tower::Layer;
 
-use crate::{api::routes::routes, libs::errors::AppResult, storage::Storage};
+use crate::{
+    api::{
+        handlers::context::AuthMode,
+        routes::{routes, xml::rewrite_virtual_host},
+    },
+    libs::{clock::Clock, errors::AppResult, registry::ProjectRegistry},
+    storage::{hmac::HmacKeyStorageExt, service_account::ServiceAccountKeyStorageExt, Storage},
+};
 
+mod channel;
 pub mod commands;
+mod delivery;
+mod lifecycle;
+mod notification;
 
 pub struct Server {
     cfg: CommandArgs,
@@ -19,20 +34,80 @@ impl Server {
     pub async fn bootstrap(&self) -> AppResult<()> {
         tracing::debug!(server.args = ?self.cfg, "Bootstrapping the server with given configuration");
 
-        let CommandArgs { host, port, scheme } = &self.cfg;
+        let CommandArgs {
+            host,
+            port,
+            scheme,
+            projects,
+            default_project_number,
+            enforce_auth,
+            lifecycle_interval,
+            fixed_time,
+            notification_push_url,
+            pubsub_emulator_host,
+            hmac_keys,
+            service_account_keys,
+        } = &self.cfg;
 
         tracing::info!(
             server.cfg.host=%host,
             server.cfg.port=%port,
             server.cfg.mode=%scheme,
+            server.cfg.enforce_auth=%enforce_auth,
+            server.cfg.lifecycle_interval=%lifecycle_interval,
             "Starting server..."
         );
 
-        let router = routes().with_state(Storage::new());
+        let registry =
+            ProjectRegistry::new(projects.iter().cloned().collect(), *default_project_number);
+        let clock = fixed_time.map_or_else(Clock::default, Clock::fixed);
+        let storage = Storage::new(registry, clock);
+        for key in hmac_keys {
+            storage
+                .import_hmac_key(
+                    &key.project,
+                    &key.service_account_email,
+                    &key.access_id,
+                    &key.secret,
+                )
+                .await
+                .context("Failed to register an HMAC key")?;
+        }
+        for key in service_account_keys {
+            storage
+                .import_service_account_key(
+                    &key.project,
+                    &key.service_account_email,
+                    key.public_key.clone(),
+                )
+                .await;
+        }
+        tokio::spawn(lifecycle::run_lifecycle_worker(
+            storage.clone(),
+            Duration::from_secs(*lifecycle_interval),
+        ));
+        tokio::spawn(channel::run_channel_worker(storage.clone()));
+        let targets = notification::NotificationTargets {
+            push_url: notification_push_url.clone(),
+            pubsub_emulator_host: pubsub_emulator_host.clone(),
+        };
+        if !targets.is_empty() {
+            tokio::spawn(notification::run_notification_worker(
+                storage.clone(),
+                targets,
+            ));
+        }
+        let router = routes(&storage)
+            .layer(Extension(AuthMode {
+                enforce: *enforce_auth,
+            }))
+            .with_state(storage);
+        // Virtual-hosted-style requests of the XML API have to be rewritten before they're routed.
+        let router = tower::util::MapRequestLayer::new(rewrite_virtual_host).layer(router);
         let listener = TcpListener::bind(format!("{host}:{port}"))
             .await
             .context("Unexpected error has been occurred in constructing TcpListener")?;
-        axum::serve(listener, router)
+        axum::serve(listener, ServiceExt::<Request>::into_make_service(router))
             .await
             .context("Failed to start the server!")
     }
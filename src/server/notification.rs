@@ -0,0 +1,73 @@
+use serde_json::json;
+
+use crate::{
+    api::models::notification::PubsubMessage,
+    storage::{notification::Notification, Storage},
+};
+
+/// Where published messages are delivered besides the in-memory queue under `/_emulator/notifications`.
+#[derive(Debug, Clone, Default)]
+pub struct NotificationTargets {
+    /// An HTTP endpoint receiving a Pub/Sub push request per message.
+    pub push_url: Option<String>,
+    /// `host:port` of a Pub/Sub emulator, to whose topics messages are published.
+    pub pubsub_emulator_host: Option<String>,
+}
+
+impl NotificationTargets {
+    pub fn is_empty(&self) -> bool {
+        self.push_url.is_none() && self.pubsub_emulator_host.is_none()
+    }
+}
+
+/// Delivers messages published by notification configurations to the targets as they come.
+/// Failures are only logged like GCS does for a missing topic.
+pub async fn run_notification_worker(storage: Storage, targets: NotificationTargets) {
+    let receiver = storage.notifications().subscribe();
+    super::delivery::run("notification", receiver, |client, notification| {
+        let mut requests = vec![];
+        if let Some(url) = &targets.push_url {
+            requests.push(client.post(url).json(&push_request(&notification)));
+        }
+        if let Some(host) = &targets.pubsub_emulator_host {
+            let url = format!("http://{host}/v1/{}:publish", topic_path(&notification));
+            requests.push(client.post(url).json(&publish_request(notification)));
+        }
+        requests
+    })
+    .await
+}
+
+/// Strips `//pubsub.googleapis.com/` from the topic, leaving `projects/{project}/topics/{topic}`.
+fn topic_path(notification: &Notification) -> &str {
+    notification
+        .topic
+        .strip_prefix("//pubsub.googleapis.com/")
+        .unwrap_or(&notification.topic)
+}
+
+/// Builds the body Pub/Sub sends to a push subscription.
+/// https://cloud.google.com/pubsub/docs/push#receive_push
+fn push_request(notification: &Notification) -> serde_json::Value {
+    let subscription = format!("{}-emulator-push", topic_path(notification)).replacen(
+        "/topics/",
+        "/subscriptions/",
+        1,
+    );
+    json!({
+        "message": PubsubMessage::from(notification.clone()),
+        "subscription": subscription,
+    })
+}
+
+/// Builds the body of Pub/Sub `publish`, where the message ID and publish time are assigned by Pub/Sub.
+/// https://cloud.google.com/pubsub/docs/reference/rest/v1/projects.topics/publish
+fn publish_request(notification: Notification) -> serde_json::Value {
+    let message = PubsubMessage::from(notification);
+    json!({
+        "messages": [{
+            "data": message.data,
+            "attributes": message.attributes,
+        }],
+    })
+}
@@ -0,0 +1,495 @@
+use crate::libs::{
+    errors::{AppResult, Errors},
+    registry::Project,
+};
+
+use super::{
+    event::Action,
+    notification::EventType,
+    object::{etag, find_object},
+    ObjectGeneration, ObjectName, Storage,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, strum::Display, strum::EnumString)]
+#[strum(serialize_all = "SCREAMING_SNAKE_CASE")]
+pub enum AclRole {
+    Owner,
+    Writer,
+    Reader,
+}
+
+/// An access control entry granting `role` to `entity`, e.g. `user-alice@example.com` or `allUsers`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AccessControl {
+    pub entity: String,
+    pub role: AclRole,
+}
+
+impl AccessControl {
+    pub fn new(entity: impl Into<String>, role: AclRole) -> Self {
+        Self {
+            entity: entity.into(),
+            role,
+        }
+    }
+}
+
+/// Predefined sets of access controls given by `predefinedAcl` and `predefinedDefaultObjectAcl`.
+/// https://cloud.google.com/storage/docs/access-control/lists#predefined-acl
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PredefinedAcl {
+    AuthenticatedRead,
+    BucketOwnerFullControl,
+    BucketOwnerRead,
+    Private,
+    ProjectPrivate,
+    PublicRead,
+    PublicReadWrite,
+}
+
+/// How to set the ACL of a bucket or an object.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AclSpec {
+    Predefined(PredefinedAcl),
+    Entries(Vec<AccessControl>),
+}
+
+impl AclSpec {
+    /// Expands the spec into entries. The owner of every resource is the project owners in the emulator.
+    pub fn resolve(self, project: &Project) -> Vec<AccessControl> {
+        let owner = AccessControl::new(project_owner(project), AclRole::Owner);
+        let mut entries = match self {
+            AclSpec::Entries(entries) => return entries,
+            AclSpec::Predefined(PredefinedAcl::ProjectPrivate) => return project_private(project),
+            AclSpec::Predefined(PredefinedAcl::Private)
+            | AclSpec::Predefined(PredefinedAcl::BucketOwnerFullControl) => vec![],
+            AclSpec::Predefined(PredefinedAcl::AuthenticatedRead) => {
+                vec![AccessControl::new(ALL_AUTHENTICATED_USERS, AclRole::Reader)]
+            }
+            AclSpec::Predefined(PredefinedAcl::BucketOwnerRead) => vec![],
+            AclSpec::Predefined(PredefinedAcl::PublicRead) => {
+                vec![AccessControl::new(ALL_USERS, AclRole::Reader)]
+            }
+            AclSpec::Predefined(PredefinedAcl::PublicReadWrite) => {
+                vec![AccessControl::new(ALL_USERS, AclRole::Writer)]
+            }
+        };
+        entries.insert(0, owner);
+        entries
+    }
+}
+
+pub const ALL_USERS: &str = "allUsers";
+pub const ALL_AUTHENTICATED_USERS: &str = "allAuthenticatedUsers";
+
+/// The entity that owns buckets and objects, which is always the project owners in the emulator.
+pub fn project_owner(project: &Project) -> String {
+    format!("project-owners-{}", project.number)
+}
+
+/// The ACL GCS applies when neither an ACL nor a predefined ACL is given, a.k.a. `projectPrivate`.
+pub fn project_private(project: &Project) -> Vec<AccessControl> {
+    vec![
+        AccessControl::new(project_owner(project), AclRole::Owner),
+        AccessControl::new(
+            format!("project-editors-{}", project.number),
+            AclRole::Owner,
+        ),
+        AccessControl::new(
+            format!("project-viewers-{}", project.number),
+            AclRole::Reader,
+        ),
+    ]
+}
+
+/// Returns whether the entity designates the principal given as an IAM member, e.g. `user:alice@example.com`.
+/// Service accounts appear as `user-` entities like GCS does, and project teams match project convenience members.
+pub fn entity_matches(entity: &str, principal: Option<&str>, project: &Project) -> bool {
+    match entity {
+        ALL_USERS => return true,
+        ALL_AUTHENTICATED_USERS => return principal.is_some(),
+        _ => {}
+    }
+    let Some((kind, id)) = principal.and_then(|p| p.split_once(':')) else {
+        return false;
+    };
+    let Some((entity_kind, value)) = entity.split_once('-') else {
+        return false;
+    };
+    match (entity_kind, kind) {
+        ("user", "user" | "serviceAccount") | ("group", "group") => value.eq_ignore_ascii_case(id),
+        ("domain", _) => id
+            .rsplit_once('@')
+            .is_some_and(|(_, domain)| domain.eq_ignore_ascii_case(value)),
+        ("project", _) => {
+            let (member_kind, number) = match value.split_once('-') {
+                Some(("owners", n)) => ("projectOwner", n),
+                Some(("editors", n)) => ("projectEditor", n),
+                Some(("viewers", n)) => ("projectViewer", n),
+                _ => return false,
+            };
+            member_kind == kind && number == project.number.to_string() && id == project.id
+        }
+        _ => false,
+    }
+}
+
+/// Permissions an ACL role grants on a bucket, or on an object if `object` is `true`.
+/// https://cloud.google.com/storage/docs/access-control/lists#permissions
+pub fn role_permissions(role: AclRole, object: bool) -> &'static [&'static str] {
+    match (role, object) {
+        (AclRole::Reader, false) => &["storage.buckets.get", "storage.objects.list"],
+        (AclRole::Writer, false) => &[
+            "storage.buckets.get",
+            "storage.objects.list",
+            "storage.objects.create",
+            "storage.objects.delete",
+        ],
+        (AclRole::Owner, false) => &[
+            "storage.buckets.get",
+            "storage.objects.list",
+            "storage.objects.create",
+            "storage.objects.delete",
+            "storage.buckets.update",
+            "storage.buckets.getIamPolicy",
+            "storage.buckets.setIamPolicy",
+        ],
+        (AclRole::Reader | AclRole::Writer, true) => &["storage.objects.get"],
+        (AclRole::Owner, true) => &[
+            "storage.objects.get",
+            "storage.objects.update",
+            "storage.objects.getIamPolicy",
+            "storage.objects.setIamPolicy",
+        ],
+    }
+}
+
+/// The resource an access control list belongs to.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AclTarget {
+    /// `bucketAccessControls`
+    Bucket,
+    /// `defaultObjectAccessControls`
+    DefaultObject,
+    /// `objectAccessControls`, which targets the live version unless `generation` is given.
+    Object {
+        name: String,
+        generation: Option<u64>,
+    },
+}
+
+/// Aggregates operations for access control lists.
+/// https://cloud.google.com/storage/docs/json_api/v1/bucketAccessControls
+/// https://cloud.google.com/storage/docs/json_api/v1/defaultObjectAccessControls
+/// https://cloud.google.com/storage/docs/json_api/v1/objectAccessControls
+pub trait AclStorageExt {
+    /// Corresponds to `list` operation.
+    async fn list_acl(
+        &self,
+        bucket: &str,
+        target: &AclTarget,
+    ) -> AppResult<Vec<AccessControl>, Errors>;
+
+    /// Corresponds to `get` operation.
+    async fn get_acl(
+        &self,
+        bucket: &str,
+        target: &AclTarget,
+        entity: &str,
+    ) -> AppResult<AccessControl, Errors>;
+
+    /// Corresponds to `insert`, `update` and `patch` operation, which replace the role of the entity.
+    async fn upsert_acl(
+        &self,
+        bucket: &str,
+        target: &AclTarget,
+        acl: AccessControl,
+    ) -> AppResult<AccessControl, Errors>;
+
+    /// Corresponds to `delete` operation.
+    async fn delete_acl(
+        &self,
+        bucket: &str,
+        target: &AclTarget,
+        entity: &str,
+    ) -> AppResult<AccessControl, Errors>;
+}
+
+impl AclStorageExt for Storage {
+    async fn list_acl(
+        &self,
+        bucket: &str,
+        target: &AclTarget,
+    ) -> AppResult<Vec<AccessControl>, Errors> {
+        self.modify_acl(bucket, target, |_| Ok(false))
+            .map(|(acl, _)| acl)
+    }
+
+    async fn get_acl(
+        &self,
+        bucket: &str,
+        target: &AclTarget,
+        entity: &str,
+    ) -> AppResult<AccessControl, Errors> {
+        self.list_acl(bucket, target)
+            .await?
+            .into_iter()
+            .find(|a| a.entity == entity)
+            .ok_or_else(|| acl_not_found(entity))
+    }
+
+    async fn upsert_acl(
+        &self,
+        bucket: &str,
+        target: &AclTarget,
+        acl: AccessControl,
+    ) -> AppResult<AccessControl, Errors> {
+        if matches!(target, AclTarget::Object { .. } | AclTarget::DefaultObject)
+            && acl.role == AclRole::Writer
+        {
+            return Err(Errors::BadRequest {
+                message: "WRITER is not a valid role for objects".into(),
+            });
+        }
+        self.bucket(bucket)?
+            .lock()
+            .unwrap()
+            .attr
+            .iam_configuration
+            .check_acl(std::slice::from_ref(&acl))?;
+        self.modify_acl(bucket, target, |entries| {
+            match entries.iter_mut().find(|a| a.entity == acl.entity) {
+                Some(entry) => entry.role = acl.role,
+                None => entries.push(acl.clone()),
+            }
+            Ok(true)
+        })?;
+        Ok(acl)
+    }
+
+    async fn delete_acl(
+        &self,
+        bucket: &str,
+        target: &AclTarget,
+        entity: &str,
+    ) -> AppResult<AccessControl, Errors> {
+        let mut deleted = None;
+        self.modify_acl(bucket, target, |entries| {
+            let idx = entries
+                .iter()
+                .position(|a| a.entity == entity)
+                .ok_or_else(|| acl_not_found(entity))?;
+            deleted = Some(entries.remove(idx));
+            Ok(true)
+        })?;
+        Ok(deleted.unwrap())
+    }
+}
+
+impl Storage {
+    /// Applies `f` to the ACL of the target, and returns the ACL after the modification.
+    /// `f` returns whether it changed the ACL so that the owning resource is marked as updated.
+    fn modify_acl(
+        &self,
+        bucket: &str,
+        target: &AclTarget,
+        f: impl FnOnce(&mut Vec<AccessControl>) -> AppResult<bool, Errors>,
+    ) -> AppResult<(Vec<AccessControl>, bool), Errors> {
+        let bucket = self.bucket(bucket)?;
+        let mut bucket = bucket.lock().unwrap();
+        bucket.attr.iam_configuration.check_legacy_acl()?;
+        let now = self.clock.now();
+        match target {
+            AclTarget::Bucket | AclTarget::DefaultObject => {
+                let entries = match target {
+                    AclTarget::Bucket => &mut bucket.attr.acl,
+                    _ => &mut bucket.attr.default_object_acl,
+                };
+                let changed = f(entries)?;
+                let entries = entries.clone();
+                if changed {
+                    bucket.attr.metageneration += 1;
+                    bucket.attr.updated = now;
+                    self.events
+                        .publish_bucket(Action::Update, &bucket.attr, now);
+                }
+                Ok((entries, changed))
+            }
+            AclTarget::Object { name, generation } => {
+                let current = find_object(&bucket, name, *generation)?;
+                let key = (
+                    ObjectName(current.attr.name),
+                    ObjectGeneration(current.attr.generation),
+                );
+                let (object, changed) = {
+                    let mut object = bucket.objects.get_mut(&key).unwrap();
+                    let changed = f(&mut object.attr.acl)?;
+                    if changed {
+                        object.attr.metageneration += 1;
+                        object.attr.etag = etag(object.attr.generation, object.attr.metageneration);
+                        object.attr.updated = now;
+                    }
+                    (object.attr.clone(), changed)
+                };
+                if changed {
+                    self.publish_object_event(
+                        &bucket,
+                        EventType::MetadataUpdate,
+                        &object,
+                        None,
+                        now,
+                    );
+                }
+                Ok((object.acl, changed))
+            }
+        }
+    }
+}
+
+fn acl_not_found(entity: &str) -> Errors {
+    Errors::AccessControlNotFound {
+        message: format!("No access control entry for {entity}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+    use googletest::prelude::*;
+
+    use crate::{
+        libs::{errors::Errors, registry::Project},
+        storage::{
+            acl::{AccessControl, AclRole, AclSpec, AclStorageExt, AclTarget, PredefinedAcl},
+            BucketStorageExt, CreateBucketAttr, CreateObjectAttr, ObjectStorageExt, Preconditions,
+            Storage,
+        },
+    };
+
+    fn project() -> Project {
+        Project {
+            id: "test-project".into(),
+            number: 1,
+        }
+    }
+
+    #[googletest::test]
+    fn expand_predefined_acl_with_project_owner() {
+        // Act
+        let res = AclSpec::Predefined(PredefinedAcl::PublicRead).resolve(&project());
+
+        // Assert
+        assert_that!(
+            res,
+            elements_are![
+                eq(&AccessControl::new("project-owners-1", AclRole::Owner)),
+                eq(&AccessControl::new("allUsers", AclRole::Reader)),
+            ]
+        );
+    }
+
+    #[googletest::test]
+    #[tokio::test]
+    async fn apply_default_object_acl_to_new_object_and_replace_its_entry() {
+        // Arrange
+        let storage = Storage::default();
+        let attr = CreateBucketAttr {
+            project: "test-project".into(),
+            default_object_acl: Some(AclSpec::Predefined(PredefinedAcl::Private)),
+            ..Default::default()
+        };
+        let _ = storage.create("test_bucket", attr).await;
+        let attr = CreateObjectAttr {
+            name: "a".into(),
+            ..Default::default()
+        };
+        let created = storage
+            .create_object("test_bucket", attr, Bytes::new(), Preconditions::default())
+            .await
+            .unwrap();
+        let target = AclTarget::Object {
+            name: "a".into(),
+            generation: None,
+        };
+
+        // Act
+        let _ = storage
+            .upsert_acl(
+                "test_bucket",
+                &target,
+                AccessControl::new("user-alice@example.com", AclRole::Reader),
+            )
+            .await;
+        let _ = storage
+            .upsert_acl(
+                "test_bucket",
+                &target,
+                AccessControl::new("user-alice@example.com", AclRole::Owner),
+            )
+            .await;
+        let res = storage.list_acl("test_bucket", &target).await;
+
+        // Assert
+        expect_that!(
+            created.acl,
+            elements_are![eq(&AccessControl::new("project-owners-1", AclRole::Owner))]
+        );
+        assert_that!(
+            res,
+            ok(elements_are![
+                eq(&AccessControl::new("project-owners-1", AclRole::Owner)),
+                eq(&AccessControl::new(
+                    "user-alice@example.com",
+                    AclRole::Owner
+                )),
+            ])
+        );
+    }
+
+    #[googletest::test]
+    #[tokio::test]
+    async fn return_not_found_error_while_deleting_non_existing_entity() {
+        // Arrange
+        let storage = Storage::default();
+        let attr = CreateBucketAttr {
+            project: "test-project".into(),
+            ..Default::default()
+        };
+        let _ = storage.create("test_bucket", attr).await;
+
+        // Act
+        let res = storage
+            .delete_acl("test_bucket", &AclTarget::Bucket, "user-bob@example.com")
+            .await;
+
+        // Assert
+        assert_that!(
+            res,
+            err(matches_pattern!(Errors::AccessControlNotFound { .. }))
+        );
+    }
+
+    #[googletest::test]
+    #[tokio::test]
+    async fn reject_writer_role_for_objects() {
+        // Arrange
+        let storage = Storage::default();
+        let attr = CreateBucketAttr {
+            project: "test-project".into(),
+            ..Default::default()
+        };
+        let _ = storage.create("test_bucket", attr).await;
+
+        // Act
+        let res = storage
+            .upsert_acl(
+                "test_bucket",
+                &AclTarget::DefaultObject,
+                AccessControl::new("allUsers", AclRole::Writer),
+            )
+            .await;
+
+        // Assert
+        assert_that!(res, err(matches_pattern!(Errors::BadRequest { .. })));
+    }
+}
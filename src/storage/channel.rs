@@ -0,0 +1,330 @@
+use std::sync::Arc;
+
+use base64::{prelude::BASE64_URL_SAFE_NO_PAD, Engine};
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use tokio::sync::broadcast;
+
+use crate::libs::errors::{AppResult, Errors};
+
+use super::{notification::EventType, Storage, StorageObjectAttr};
+
+/// A notification channel watching changes of objects in a bucket, which is called Object Change Notification.
+/// https://cloud.google.com/storage/docs/object-change-notification
+#[derive(Debug, Clone, PartialEq)]
+pub struct Channel {
+    pub id: String,
+    /// An opaque ID of the watched resource, which is required to stop the channel along with `id`.
+    pub resource_id: String,
+    pub resource_uri: String,
+    pub bucket: String,
+    /// The URL notifications are sent to.
+    pub address: String,
+    pub token: Option<String>,
+    pub expiration: Option<DateTime<Utc>>,
+    pub prefix: Option<String>,
+    next_message_number: u64,
+}
+
+impl Channel {
+    fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expiration.is_some_and(|e| e <= now)
+    }
+
+    fn watches(&self, bucket: &str, object: &StorageObjectAttr) -> bool {
+        self.bucket == bucket
+            && self
+                .prefix
+                .as_ref()
+                .is_none_or(|p| object.name.starts_with(p))
+    }
+
+    fn message(
+        &mut self,
+        state: ResourceState,
+        payload: Option<StorageObjectAttr>,
+    ) -> ChannelMessage {
+        let message_number = self.next_message_number;
+        self.next_message_number += 1;
+        ChannelMessage {
+            channel_id: self.id.clone(),
+            resource_id: self.resource_id.clone(),
+            resource_uri: self.resource_uri.clone(),
+            address: self.address.clone(),
+            token: self.token.clone(),
+            expiration: self.expiration,
+            state,
+            message_number,
+            payload,
+        }
+    }
+}
+
+/// The value of `X-Goog-Resource-State` header.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ResourceState {
+    /// Sent once when the channel is created.
+    Sync,
+    /// An object is created, overwritten or has its metadata updated.
+    Exists,
+    /// An object is deleted or becomes noncurrent.
+    NotExists,
+}
+
+impl ResourceState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ResourceState::Sync => "sync",
+            ResourceState::Exists => "exists",
+            ResourceState::NotExists => "not_exists",
+        }
+    }
+}
+
+/// A notification sent to the address of a channel.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChannelMessage {
+    pub channel_id: String,
+    pub resource_id: String,
+    pub resource_uri: String,
+    pub address: String,
+    pub token: Option<String>,
+    pub expiration: Option<DateTime<Utc>>,
+    pub state: ResourceState,
+    /// Starts with `1` for `sync`, and increases by one for each message of the channel.
+    pub message_number: u64,
+    /// The changed object sent as the body, which `sync` doesn't have.
+    pub payload: Option<StorageObjectAttr>,
+}
+
+/// Fields given on `watchAll`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CreateChannelAttr {
+    pub id: String,
+    pub address: String,
+    pub token: Option<String>,
+    pub expiration: Option<DateTime<Utc>>,
+    pub prefix: Option<String>,
+}
+
+/// Open channels, whose messages are broadcast to the delivery worker.
+#[derive(Debug, Clone)]
+pub struct Channels {
+    channels: Arc<DashMap<String, Channel>>,
+    sender: broadcast::Sender<ChannelMessage>,
+}
+
+/// The number of messages buffered for the delivery worker.
+const CHANNEL_CAPACITY: usize = 1000;
+
+impl Default for Channels {
+    fn default() -> Self {
+        Channels {
+            channels: Arc::new(DashMap::new()),
+            sender: broadcast::channel(CHANNEL_CAPACITY).0,
+        }
+    }
+}
+
+impl Channels {
+    /// Receives messages sent from now on.
+    pub fn subscribe(&self) -> broadcast::Receiver<ChannelMessage> {
+        self.sender.subscribe()
+    }
+
+    /// Sends a message to every open channel watching the object, and closes expired channels.
+    pub(super) fn notify(
+        &self,
+        bucket: &str,
+        event_type: EventType,
+        object: &StorageObjectAttr,
+        now: DateTime<Utc>,
+    ) {
+        self.channels.retain(|_, c| !c.is_expired(now));
+        let state = match event_type {
+            EventType::Finalize | EventType::MetadataUpdate => ResourceState::Exists,
+            EventType::Delete | EventType::Archive => ResourceState::NotExists,
+        };
+        for mut channel in self.channels.iter_mut() {
+            if channel.watches(bucket, object) {
+                // Nobody may be listening, in which case the message is dropped like an unreachable address.
+                let _ = self
+                    .sender
+                    .send(channel.message(state, Some(object.clone())));
+            }
+        }
+    }
+}
+
+/// Aggregates operations for notification channels.
+pub trait ChannelStorageExt {
+    /// Corresponds to `watchAll` operation: https://cloud.google.com/storage/docs/json_api/v1/objects/watchAll
+    /// A `sync` message is sent to the channel right away.
+    async fn watch_all_objects(
+        &self,
+        bucket: &str,
+        attr: CreateChannelAttr,
+    ) -> AppResult<Channel, Errors>;
+
+    /// Corresponds to `stop` operation: https://cloud.google.com/storage/docs/json_api/v1/channels/stop
+    async fn stop_channel(&self, id: &str, resource_id: &str) -> AppResult<(), Errors>;
+}
+
+impl ChannelStorageExt for Storage {
+    async fn watch_all_objects(
+        &self,
+        bucket: &str,
+        attr: CreateChannelAttr,
+    ) -> AppResult<Channel, Errors> {
+        self.bucket(bucket)?;
+        let channels = &self.channels.channels;
+        channels.retain(|_, c| !c.is_expired(self.clock.now()));
+        let entry = match channels.entry(attr.id.clone()) {
+            dashmap::Entry::Occupied(_) => {
+                return Err(Errors::BadRequest {
+                    message: format!("Channel id not unique: {}", attr.id),
+                })
+            }
+            dashmap::Entry::Vacant(entry) => entry,
+        };
+        let mut channel = Channel {
+            resource_id: BASE64_URL_SAFE_NO_PAD.encode(format!("{bucket}/{}", attr.id)),
+            resource_uri: format!("https://www.googleapis.com/storage/v1/b/{bucket}/o?alt=json"),
+            id: attr.id,
+            bucket: bucket.to_string(),
+            address: attr.address,
+            token: attr.token,
+            expiration: attr.expiration,
+            prefix: attr.prefix.filter(|p| !p.is_empty()),
+            next_message_number: 1,
+        };
+        let _ = self
+            .channels
+            .sender
+            .send(channel.message(ResourceState::Sync, None));
+        Ok(entry.insert(channel).clone())
+    }
+
+    async fn stop_channel(&self, id: &str, resource_id: &str) -> AppResult<(), Errors> {
+        self.channels
+            .channels
+            .remove_if(id, |_, c| c.resource_id == resource_id)
+            .map(|_| ())
+            .ok_or_else(|| Errors::ChannelNotFound {
+                message: format!("Channel not found: {id}"),
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+    use googletest::prelude::*;
+
+    use crate::{
+        libs::errors::Errors,
+        storage::{
+            channel::{ChannelStorageExt, CreateChannelAttr, ResourceState},
+            BucketStorageExt, CreateBucketAttr, CreateObjectAttr, ObjectStorageExt, Preconditions,
+            Storage,
+        },
+    };
+
+    async fn storage_with_bucket() -> Storage {
+        let storage = Storage::default();
+        let attr = CreateBucketAttr {
+            project: "test-project".into(),
+            ..Default::default()
+        };
+        let _ = storage.create("test_bucket", attr).await;
+        storage
+    }
+
+    fn channel_attr(id: &str) -> CreateChannelAttr {
+        CreateChannelAttr {
+            id: id.into(),
+            address: "http://localhost:8080/notify".into(),
+            ..Default::default()
+        }
+    }
+
+    #[googletest::test]
+    #[tokio::test]
+    async fn send_sync_then_exists_and_not_exists() {
+        // Arrange
+        let storage = storage_with_bucket().await;
+        let mut receiver = storage.channels().subscribe();
+        let _ = storage
+            .watch_all_objects("test_bucket", channel_attr("channel-1"))
+            .await;
+
+        // Act
+        let _ = storage
+            .create_object(
+                "test_bucket",
+                CreateObjectAttr {
+                    name: "a".into(),
+                    ..Default::default()
+                },
+                Bytes::new(),
+                Preconditions::default(),
+            )
+            .await;
+        let _ = storage
+            .delete_object("test_bucket", "a", None, Preconditions::default())
+            .await;
+
+        // Assert
+        let mut messages = vec![];
+        while let Ok(message) = receiver.try_recv() {
+            messages.push((message.state, message.message_number));
+        }
+        assert_that!(
+            messages,
+            elements_are![
+                eq(&(ResourceState::Sync, 1)),
+                eq(&(ResourceState::Exists, 2)),
+                eq(&(ResourceState::NotExists, 3))
+            ]
+        );
+    }
+
+    #[googletest::test]
+    #[tokio::test]
+    async fn stop_channel_requires_matching_resource_id() {
+        // Arrange
+        let storage = storage_with_bucket().await;
+        let channel = storage
+            .watch_all_objects("test_bucket", channel_attr("channel-1"))
+            .await
+            .unwrap();
+
+        // Act
+        let wrong = storage.stop_channel("channel-1", "unknown").await;
+        let stopped = storage
+            .stop_channel("channel-1", &channel.resource_id)
+            .await;
+
+        // Assert
+        expect_that!(wrong, err(matches_pattern!(Errors::ChannelNotFound { .. })));
+        assert_that!(stopped, ok(anything()));
+    }
+
+    #[googletest::test]
+    #[tokio::test]
+    async fn reject_duplicate_channel_id() {
+        // Arrange
+        let storage = storage_with_bucket().await;
+        let _ = storage
+            .watch_all_objects("test_bucket", channel_attr("channel-1"))
+            .await;
+
+        // Act
+        let res = storage
+            .watch_all_objects("test_bucket", channel_attr("channel-1"))
+            .await;
+
+        // Assert
+        assert_that!(res, err(matches_pattern!(Errors::BadRequest { .. })));
+    }
+}
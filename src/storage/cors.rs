@@ -0,0 +1,102 @@
+/// A rule of the `cors` configuration of a bucket, which lets browsers on the origins send requests of the methods.
+/// https://cloud.google.com/storage/docs/cross-origin
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CorsRule {
+    /// Origins such as `https://example.com`, where `*` allows any origin.
+    pub origins: Vec<String>,
+    pub methods: Vec<String>,
+    /// Headers a browser can send on requests and read from responses.
+    pub response_headers: Vec<String>,
+    pub max_age_seconds: Option<u32>,
+}
+
+impl CorsRule {
+    fn allows(&self, origin: &str, method: &str) -> bool {
+        self.origins.iter().any(|o| o == "*" || o == origin)
+            && self.methods.iter().any(|m| m.eq_ignore_ascii_case(method))
+    }
+
+    fn allows_headers<'a>(&self, mut headers: impl Iterator<Item = &'a str>) -> bool {
+        headers.all(|h| {
+            self.response_headers
+                .iter()
+                .any(|r| r == "*" || r.eq_ignore_ascii_case(h))
+        })
+    }
+}
+
+/// Finds the first rule allowing a request from the origin by the method.
+pub fn matching_rule<'a>(
+    rules: &'a [CorsRule],
+    origin: &str,
+    method: &str,
+) -> Option<&'a CorsRule> {
+    rules.iter().find(|r| r.allows(origin, method))
+}
+
+/// Finds the first rule allowing a preflight request, whose requested headers have to be listed in `responseHeader`.
+pub fn matching_preflight_rule<'a>(
+    rules: &'a [CorsRule],
+    origin: &str,
+    method: &str,
+    request_headers: &[&str],
+) -> Option<&'a CorsRule> {
+    rules
+        .iter()
+        .find(|r| r.allows(origin, method) && r.allows_headers(request_headers.iter().copied()))
+}
+
+#[cfg(test)]
+mod tests {
+    use googletest::prelude::*;
+
+    use crate::storage::cors::{matching_preflight_rule, matching_rule, CorsRule};
+
+    fn rules() -> Vec<CorsRule> {
+        vec![
+            CorsRule {
+                origins: vec!["https://example.com".into()],
+                methods: vec!["GET".into(), "PUT".into()],
+                response_headers: vec!["Content-Type".into()],
+                max_age_seconds: Some(3600),
+            },
+            CorsRule {
+                origins: vec!["*".into()],
+                methods: vec!["GET".into()],
+                ..Default::default()
+            },
+        ]
+    }
+
+    #[googletest::test]
+    fn match_origin_and_method() {
+        // Arrange
+        let rules = rules();
+
+        // Act
+        let put = matching_rule(&rules, "https://example.com", "PUT");
+        let any_origin = matching_rule(&rules, "https://other.example.com", "GET");
+        let denied = matching_rule(&rules, "https://other.example.com", "PUT");
+
+        // Assert
+        expect_that!(put, some(eq(&rules[0])));
+        expect_that!(any_origin, some(eq(&rules[1])));
+        assert_that!(denied, none());
+    }
+
+    #[googletest::test]
+    fn match_preflight_headers() {
+        // Arrange
+        let rules = rules();
+
+        // Act
+        let listed =
+            matching_preflight_rule(&rules, "https://example.com", "PUT", &["content-type"]);
+        let unlisted =
+            matching_preflight_rule(&rules, "https://example.com", "PUT", &["x-goog-meta-a"]);
+
+        // Assert
+        expect_that!(listed, some(eq(&rules[0])));
+        assert_that!(unlisted, none());
+    }
+}
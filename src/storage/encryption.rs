@@ -0,0 +1,246 @@
+use std::fmt::Debug;
+
+use aes_gcm::{
+    aead::{Aead, AeadCore, OsRng},
+    Aes256Gcm, Key, KeyInit, Nonce,
+};
+use base64::{prelude::BASE64_STANDARD, Engine};
+use bytes::Bytes;
+use sha2::{Digest, Sha256};
+
+use crate::libs::errors::{AppResult, Errors};
+
+use super::OnMemoryStorageObject;
+
+/// The only algorithm of customer-supplied encryption keys.
+const ENCRYPTION_ALGORITHM: &str = "AES256";
+
+/// The nonce prepended to encrypted content.
+const NONCE_SIZE: usize = 12;
+
+/// A customer-supplied encryption key, which is given along with each request and never stored.
+/// An object encrypted by the key keeps only its SHA-256 hash.
+/// https://cloud.google.com/storage/docs/encryption/customer-supplied-keys
+#[derive(Clone, PartialEq)]
+pub struct EncryptionKey {
+    key: [u8; 32],
+    key_sha256: String,
+}
+
+/// Tells which key an object is encrypted by, i.e. `customerEncryption` of the object.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CustomerEncryption {
+    pub encryption_algorithm: String,
+    pub key_sha256: String,
+}
+
+impl EncryptionKey {
+    /// Decodes a 256-bit key in base64, which has to match its SHA-256 hash in base64.
+    pub fn decode(algorithm: &str, key: &str, key_sha256: &str) -> AppResult<Self, Errors> {
+        if algorithm != ENCRYPTION_ALGORITHM {
+            return Err(Errors::BadRequest {
+                message: format!(
+                    "Encryption algorithm {algorithm} is not supported, use {ENCRYPTION_ALGORITHM}"
+                ),
+            });
+        }
+        let key = BASE64_STANDARD
+            .decode(key.trim())
+            .ok()
+            .and_then(|k| <[u8; 32]>::try_from(k).ok())
+            .ok_or_else(|| Errors::BadRequest {
+                message: "The encryption key must be a base64-encoded 256-bit key".into(),
+            })?;
+        let hash = BASE64_STANDARD.encode(Sha256::digest(key));
+        if hash != key_sha256.trim() {
+            return Err(Errors::BadRequest {
+                message: "The SHA-256 hash of the encryption key doesn't match the key".into(),
+            });
+        }
+        Ok(EncryptionKey {
+            key,
+            key_sha256: hash,
+        })
+    }
+
+    pub fn customer_encryption(&self) -> CustomerEncryption {
+        CustomerEncryption {
+            encryption_algorithm: ENCRYPTION_ALGORITHM.to_string(),
+            key_sha256: self.key_sha256.clone(),
+        }
+    }
+
+    /// Encrypts content by AES-256-GCM, whose nonce is prepended.
+    pub(super) fn encrypt(&self, content: &[u8]) -> Bytes {
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let encrypted = self
+            .cipher()
+            .encrypt(&nonce, content)
+            .expect("AES-GCM encrypts content of any size held in memory");
+        [nonce.as_slice(), &encrypted].concat().into()
+    }
+
+    fn decrypt(&self, content: &[u8]) -> Option<Bytes> {
+        let (nonce, encrypted) = content.split_at_checked(NONCE_SIZE)?;
+        self.cipher()
+            .decrypt(Nonce::from_slice(nonce), encrypted)
+            .ok()
+            .map(Bytes::from)
+    }
+
+    fn cipher(&self) -> Aes256Gcm {
+        Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&self.key))
+    }
+}
+
+/// Leaves out the key itself.
+impl Debug for EncryptionKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EncryptionKey")
+            .field("key_sha256", &self.key_sha256)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Checks the key given along with a request against the key the target is encrypted by.
+/// The key is required for an encrypted target, and rejected for a target which isn't.
+pub(super) fn verify_key(
+    encryption: Option<&CustomerEncryption>,
+    key: Option<&EncryptionKey>,
+) -> AppResult<(), Errors> {
+    let bad_request = |message: &str| Errors::BadRequest {
+        message: message.into(),
+    };
+    match (encryption, key) {
+        (None, None) => Ok(()),
+        (None, Some(_)) => Err(bad_request(
+            "The target object is not encrypted by a customer-supplied encryption key.",
+        )),
+        (Some(_), None) => Err(bad_request(
+            "The target object is encrypted by a customer-supplied encryption key.",
+        )),
+        (Some(encryption), Some(key)) if encryption.key_sha256 != key.key_sha256 => {
+            Err(bad_request("The provided encryption key is incorrect."))
+        }
+        (Some(_), Some(_)) => Ok(()),
+    }
+}
+
+/// Decrypts content stored by the key given along with a request, which is checked by `verify_key` first.
+pub(super) fn decrypt_content(
+    encryption: Option<&CustomerEncryption>,
+    key: Option<&EncryptionKey>,
+    content: &Bytes,
+) -> AppResult<Bytes, Errors> {
+    verify_key(encryption, key)?;
+    let Some(key) = key else {
+        return Ok(content.clone());
+    };
+    key.decrypt(content).ok_or_else(|| Errors::BadRequest {
+        message: "The provided encryption key is incorrect.".into(),
+    })
+}
+
+impl OnMemoryStorageObject {
+    /// Decrypts the content by the key the object is encrypted by. The key is required
+    /// for an encrypted object, and rejected for an object which isn't.
+    pub fn decrypt(self, key: Option<&EncryptionKey>) -> AppResult<Self, Errors> {
+        let content = decrypt_content(self.attr.customer_encryption.as_ref(), key, &self.content)?;
+        Ok(OnMemoryStorageObject { content, ..self })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use base64::{prelude::BASE64_STANDARD, Engine};
+    use bytes::Bytes;
+    use googletest::prelude::*;
+    use sha2::{Digest, Sha256};
+
+    use crate::{
+        libs::errors::Errors,
+        storage::{
+            encryption::EncryptionKey, BucketStorageExt, CreateBucketAttr, CreateObjectAttr,
+            ObjectStorageExt, OnMemoryStorageObject, Preconditions, Storage,
+        },
+    };
+
+    fn encryption_key(seed: u8) -> EncryptionKey {
+        let key = [seed; 32];
+        EncryptionKey::decode(
+            "AES256",
+            &BASE64_STANDARD.encode(key),
+            &BASE64_STANDARD.encode(Sha256::digest(key)),
+        )
+        .unwrap()
+    }
+
+    #[googletest::test]
+    fn reject_key_not_matching_its_hash() {
+        // Arrange
+        let key = BASE64_STANDARD.encode([1; 32]);
+        let hash = BASE64_STANDARD.encode(Sha256::digest([2; 32]));
+
+        // Act
+        let mismatched = EncryptionKey::decode("AES256", &key, &hash);
+        let short = EncryptionKey::decode("AES256", &BASE64_STANDARD.encode([1; 16]), &hash);
+
+        // Assert
+        expect_that!(mismatched, err(matches_pattern!(Errors::BadRequest { .. })));
+        assert_that!(short, err(matches_pattern!(Errors::BadRequest { .. })));
+    }
+
+    #[googletest::test]
+    #[tokio::test]
+    async fn encrypt_content_at_rest() {
+        // Arrange
+        let storage = Storage::default();
+        let _ = storage
+            .create(
+                "test_bucket",
+                CreateBucketAttr {
+                    project: "test-project".into(),
+                    ..Default::default()
+                },
+            )
+            .await;
+        let attr = CreateObjectAttr {
+            name: "secret.txt".into(),
+            encryption_key: Some(encryption_key(1)),
+            ..Default::default()
+        };
+        let _ = storage
+            .create_object(
+                "test_bucket",
+                attr,
+                Bytes::from("hello"),
+                Preconditions::default(),
+            )
+            .await;
+
+        // Act
+        let stored = storage
+            .get_object("test_bucket", "secret.txt", None, Preconditions::default())
+            .await
+            .unwrap();
+        let decrypted = stored.clone().decrypt(Some(&encryption_key(1)));
+        let wrong_key = stored.clone().decrypt(Some(&encryption_key(2)));
+        let without_key = stored.clone().decrypt(None);
+
+        // Assert
+        expect_that!(stored.content, not(eq(&Bytes::from("hello"))));
+        expect_that!(stored.attr.size, eq(5));
+        expect_that!(
+            decrypted,
+            ok(field!(
+                OnMemoryStorageObject.content,
+                eq(&Bytes::from("hello"))
+            ))
+        );
+        expect_that!(wrong_key, err(matches_pattern!(Errors::BadRequest { .. })));
+        assert_that!(
+            without_key,
+            err(matches_pattern!(Errors::BadRequest { .. }))
+        );
+    }
+}
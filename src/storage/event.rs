@@ -0,0 +1,260 @@
+use chrono::{DateTime, Utc};
+use tokio::sync::broadcast;
+
+use super::{StorageBucketAttr, StorageObjectAttr};
+
+/// A mutation of a bucket or an object, which is streamed by the emulator for tests to wait on.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StorageEvent {
+    pub resource: Resource,
+    pub action: Action,
+    pub bucket: String,
+    /// The object name, which bucket events don't have.
+    pub object: Option<String>,
+    pub generation: u64,
+    pub metageneration: u64,
+    pub time: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Resource {
+    Bucket,
+    Object,
+}
+
+impl Resource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Resource::Bucket => "bucket",
+            Resource::Object => "object",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Action {
+    /// A bucket is created or restored, or a new generation of an object is written.
+    Create,
+    /// The metadata of a bucket or an object changes.
+    Update,
+    /// A bucket is deleted, or an object is deleted or becomes noncurrent.
+    Delete,
+}
+
+impl Action {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Action::Create => "create",
+            Action::Update => "update",
+            Action::Delete => "delete",
+        }
+    }
+}
+
+/// Narrows events down to a bucket and object names starting with a prefix.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EventFilter {
+    pub bucket: Option<String>,
+    /// Bucket events are left out when given.
+    pub prefix: Option<String>,
+}
+
+impl EventFilter {
+    pub fn matches(&self, event: &StorageEvent) -> bool {
+        self.bucket.as_ref().is_none_or(|b| &event.bucket == b)
+            && self.prefix.as_ref().is_none_or(|p| {
+                event
+                    .object
+                    .as_ref()
+                    .is_some_and(|name| name.starts_with(p))
+            })
+    }
+}
+
+/// Broadcasts every mutation of buckets and objects to subscribers.
+#[derive(Debug, Clone)]
+pub struct Events {
+    sender: broadcast::Sender<StorageEvent>,
+}
+
+/// The number of events buffered for each subscriber, beyond which a slow one misses the oldest.
+const EVENT_CAPACITY: usize = 1000;
+
+impl Default for Events {
+    fn default() -> Self {
+        Events {
+            sender: broadcast::channel(EVENT_CAPACITY).0,
+        }
+    }
+}
+
+impl Events {
+    /// Receives events occurring from now on.
+    pub fn subscribe(&self) -> broadcast::Receiver<StorageEvent> {
+        self.sender.subscribe()
+    }
+
+    pub(super) fn publish_bucket(
+        &self,
+        action: Action,
+        bucket: &StorageBucketAttr,
+        now: DateTime<Utc>,
+    ) {
+        self.send(StorageEvent {
+            resource: Resource::Bucket,
+            action,
+            bucket: bucket.name.clone(),
+            object: None,
+            generation: bucket.generation,
+            metageneration: bucket.metageneration,
+            time: now,
+        });
+    }
+
+    pub(super) fn publish_object(
+        &self,
+        action: Action,
+        object: &StorageObjectAttr,
+        now: DateTime<Utc>,
+    ) {
+        self.send(StorageEvent {
+            resource: Resource::Object,
+            action,
+            bucket: object.bucket_name.clone(),
+            object: Some(object.name.clone()),
+            generation: object.generation,
+            metageneration: object.metageneration,
+            time: now,
+        });
+    }
+
+    fn send(&self, event: StorageEvent) {
+        // Nobody may be subscribing, in which case the event is just dropped.
+        let _ = self.sender.send(event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+    use googletest::prelude::*;
+
+    use crate::storage::{
+        event::{Action, EventFilter, Resource},
+        BucketStorageExt, CreateBucketAttr, CreateObjectAttr, ObjectStorageExt, Preconditions,
+        Storage, UpdateBucketAttr,
+    };
+
+    async fn upload(storage: &Storage, name: &str) -> u64 {
+        storage
+            .create_object(
+                "test_bucket",
+                CreateObjectAttr {
+                    name: name.into(),
+                    ..Default::default()
+                },
+                Bytes::new(),
+                Preconditions::default(),
+            )
+            .await
+            .unwrap()
+            .generation
+    }
+
+    #[googletest::test]
+    #[tokio::test]
+    async fn publish_bucket_mutations() {
+        // Arrange
+        let storage = Storage::default();
+        let mut receiver = storage.events().subscribe();
+
+        // Act
+        let _ = storage
+            .create("test_bucket", CreateBucketAttr::default())
+            .await;
+        let _ = storage
+            .update(
+                "test_bucket",
+                UpdateBucketAttr {
+                    versioning: Some(true),
+                    ..Default::default()
+                },
+            )
+            .await;
+        let _ = storage.delete("test_bucket").await;
+
+        // Assert
+        let mut events = vec![];
+        while let Ok(event) = receiver.try_recv() {
+            events.push((event.resource, event.action, event.metageneration));
+        }
+        assert_that!(
+            events,
+            elements_are![
+                eq(&(Resource::Bucket, Action::Create, 1)),
+                eq(&(Resource::Bucket, Action::Update, 2)),
+                eq(&(Resource::Bucket, Action::Delete, 2))
+            ]
+        );
+    }
+
+    #[googletest::test]
+    #[tokio::test]
+    async fn publish_object_mutations_with_generation() {
+        // Arrange
+        let storage = Storage::default();
+        let _ = storage
+            .create("test_bucket", CreateBucketAttr::default())
+            .await;
+        let mut receiver = storage.events().subscribe();
+
+        // Act
+        let first = upload(&storage, "a").await;
+        let second = upload(&storage, "a").await;
+
+        // Assert
+        let mut events = vec![];
+        while let Ok(event) = receiver.try_recv() {
+            events.push((event.action, event.object.unwrap(), event.generation));
+        }
+        assert_that!(
+            events,
+            elements_are![
+                eq(&(Action::Create, "a".to_string(), first)),
+                eq(&(Action::Delete, "a".to_string(), first)),
+                eq(&(Action::Create, "a".to_string(), second))
+            ]
+        );
+    }
+
+    #[googletest::test]
+    #[tokio::test]
+    async fn filter_events_by_bucket_and_prefix() {
+        // Arrange
+        let storage = Storage::default();
+        let _ = storage
+            .create("test_bucket", CreateBucketAttr::default())
+            .await;
+        let mut receiver = storage.events().subscribe();
+        let filter = EventFilter {
+            bucket: Some("test_bucket".into()),
+            prefix: Some("logs/".into()),
+        };
+
+        // Act
+        upload(&storage, "logs/a").await;
+        upload(&storage, "b").await;
+        let _ = storage
+            .create("other_bucket", CreateBucketAttr::default())
+            .await;
+
+        // Assert
+        let mut names = vec![];
+        while let Ok(event) = receiver.try_recv() {
+            if filter.matches(&event) {
+                names.push(event.object.unwrap());
+            }
+        }
+        assert_that!(names, elements_are![eq("logs/a")]);
+    }
+}
@@ -0,0 +1,371 @@
+use std::sync::Arc;
+
+use base64::{prelude::BASE64_STANDARD, Engine};
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use rand::{distributions::Uniform, Rng, RngCore};
+
+use crate::libs::{
+    errors::{AppResult, Errors},
+    registry::Project,
+};
+
+use super::Storage;
+
+/// The prefix of access IDs of service account keys, which are 61 characters long.
+const ACCESS_ID_PREFIX: &str = "GOOG1E";
+const ACCESS_ID_LEN: usize = 61;
+
+/// The number of random bytes of a secret, which is 40 characters long in base64.
+const SECRET_BYTES: usize = 30;
+
+/// An HMAC key of a service account, which signs requests of the XML API and S3-compatible tools.
+/// https://cloud.google.com/storage/docs/authentication/hmackeys
+#[derive(Debug, Clone, PartialEq)]
+pub struct HmacKey {
+    pub access_id: String,
+    pub secret: String,
+    pub project: Project,
+    pub service_account_email: String,
+    pub state: HmacKeyState,
+    pub time_created: DateTime<Utc>,
+    pub updated: DateTime<Utc>,
+}
+
+impl HmacKey {
+    /// The member signed requests are authorized as.
+    pub fn principal(&self) -> String {
+        format!("serviceAccount:{}", self.service_account_email)
+    }
+
+    /// Changes whenever the key is updated.
+    pub fn etag(&self) -> String {
+        BASE64_STANDARD.encode(self.updated.timestamp_micros().to_string())
+    }
+}
+
+/// A key is created as `Active`, and has to be `Inactive` to be deleted. `Deleted` keys can't be changed anymore.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HmacKeyState {
+    Active,
+    Inactive,
+    Deleted,
+}
+
+/// Fields given on `update`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UpdateHmacKeyAttr {
+    pub state: HmacKeyState,
+    /// The update is rejected unless it matches the current etag.
+    pub etag: Option<String>,
+}
+
+/// HMAC keys keyed by their access ID.
+#[derive(Debug, Clone, Default)]
+pub struct HmacKeys {
+    keys: Arc<DashMap<String, HmacKey>>,
+}
+
+/// Aggregates operations for HMAC keys.
+pub trait HmacKeyStorageExt {
+    /// Corresponds to `create` operation: https://cloud.google.com/storage/docs/json_api/v1/projects/hmacKeys/create
+    /// The secret is generated along with the access ID.
+    async fn create_hmac_key(
+        &self,
+        project: &str,
+        service_account_email: &str,
+    ) -> AppResult<HmacKey, Errors>;
+
+    /// Registers a key whose access ID and secret are given, e.g. by `--hmac-key`.
+    async fn import_hmac_key(
+        &self,
+        project: &str,
+        service_account_email: &str,
+        access_id: &str,
+        secret: &str,
+    ) -> AppResult<HmacKey, Errors>;
+
+    /// Corresponds to `list` operation: https://cloud.google.com/storage/docs/json_api/v1/projects/hmacKeys/list
+    /// Keys are limited to the service account if given, and deleted keys are included only if `show_deleted` is set.
+    async fn list_hmac_keys(
+        &self,
+        project: &str,
+        service_account_email: Option<&str>,
+        show_deleted: bool,
+    ) -> Vec<HmacKey>;
+
+    /// Corresponds to `get` operation: https://cloud.google.com/storage/docs/json_api/v1/projects/hmacKeys/get
+    async fn get_hmac_key(&self, project: &str, access_id: &str) -> AppResult<HmacKey, Errors>;
+
+    /// Corresponds to `update` operation: https://cloud.google.com/storage/docs/json_api/v1/projects/hmacKeys/update
+    async fn update_hmac_key(
+        &self,
+        project: &str,
+        access_id: &str,
+        attr: UpdateHmacKeyAttr,
+    ) -> AppResult<HmacKey, Errors>;
+
+    /// Corresponds to `delete` operation: https://cloud.google.com/storage/docs/json_api/v1/projects/hmacKeys/delete
+    /// The key is kept in `Deleted` state, so that it's still listed with `showDeletedKeys`.
+    async fn delete_hmac_key(&self, project: &str, access_id: &str) -> AppResult<(), Errors>;
+
+    /// Finds the key a request is signed with, which has to be active.
+    async fn active_hmac_key(&self, access_id: &str) -> AppResult<HmacKey, Errors>;
+}
+
+impl HmacKeyStorageExt for Storage {
+    async fn create_hmac_key(
+        &self,
+        project: &str,
+        service_account_email: &str,
+    ) -> AppResult<HmacKey, Errors> {
+        if service_account_email.is_empty() {
+            return Err(Errors::BadRequest {
+                message: "serviceAccountEmail is required".into(),
+            });
+        }
+        // The generator isn't `Send`, so it's dropped before awaiting.
+        let (access_id, secret) = {
+            let mut rng = rand::thread_rng();
+            let access_id = ACCESS_ID_PREFIX
+                .chars()
+                .chain(
+                    (&mut rng)
+                        .sample_iter(Uniform::from(0..36))
+                        .take(ACCESS_ID_LEN - ACCESS_ID_PREFIX.len())
+                        .map(|i| char::from_digit(i, 36).unwrap().to_ascii_uppercase()),
+                )
+                .collect::<String>();
+            let mut secret = [0u8; SECRET_BYTES];
+            rng.fill_bytes(&mut secret);
+            (access_id, secret)
+        };
+        self.import_hmac_key(
+            project,
+            service_account_email,
+            &access_id,
+            &BASE64_STANDARD.encode(secret),
+        )
+        .await
+    }
+
+    async fn import_hmac_key(
+        &self,
+        project: &str,
+        service_account_email: &str,
+        access_id: &str,
+        secret: &str,
+    ) -> AppResult<HmacKey, Errors> {
+        let now = self.clock.now();
+        let key = HmacKey {
+            access_id: access_id.to_string(),
+            secret: secret.to_string(),
+            project: self.projects.resolve(project),
+            service_account_email: service_account_email.to_string(),
+            state: HmacKeyState::Active,
+            time_created: now,
+            updated: now,
+        };
+        match self.hmac_keys.keys.entry(access_id.to_string()) {
+            dashmap::Entry::Occupied(_) => Err(Errors::AlreadyExists {
+                message: format!("HMAC key already exists: {access_id}"),
+            }),
+            dashmap::Entry::Vacant(entry) => Ok(entry.insert(key).clone()),
+        }
+    }
+
+    async fn list_hmac_keys(
+        &self,
+        project: &str,
+        service_account_email: Option<&str>,
+        show_deleted: bool,
+    ) -> Vec<HmacKey> {
+        let project = self.projects.resolve(project);
+        let mut keys = self
+            .hmac_keys
+            .keys
+            .iter()
+            .filter(|k| k.project.id == project.id)
+            .filter(|k| service_account_email.is_none_or(|e| k.service_account_email == e))
+            .filter(|k| show_deleted || k.state != HmacKeyState::Deleted)
+            .map(|k| k.clone())
+            .collect::<Vec<_>>();
+        keys.sort_by(|a, b| (a.time_created, &a.access_id).cmp(&(b.time_created, &b.access_id)));
+        keys
+    }
+
+    async fn get_hmac_key(&self, project: &str, access_id: &str) -> AppResult<HmacKey, Errors> {
+        let project = self.projects.resolve(project);
+        self.hmac_keys
+            .keys
+            .get(access_id)
+            .filter(|k| k.project.id == project.id)
+            .map(|k| k.clone())
+            .ok_or_else(|| hmac_key_not_found(access_id))
+    }
+
+    async fn update_hmac_key(
+        &self,
+        project: &str,
+        access_id: &str,
+        attr: UpdateHmacKeyAttr,
+    ) -> AppResult<HmacKey, Errors> {
+        let project = self.projects.resolve(project);
+        let mut key = self
+            .hmac_keys
+            .keys
+            .get_mut(access_id)
+            .filter(|k| k.project.id == project.id)
+            .ok_or_else(|| hmac_key_not_found(access_id))?;
+        if attr.etag.as_ref().is_some_and(|e| *e != key.etag()) {
+            return Err(Errors::PreconditionFailed {
+                message: "The etag of the HMAC key doesn't match".into(),
+            });
+        }
+        if key.state == HmacKeyState::Deleted {
+            return Err(Errors::BadRequest {
+                message: "Cannot update a deleted HMAC key".into(),
+            });
+        }
+        if attr.state == HmacKeyState::Deleted {
+            return Err(Errors::BadRequest {
+                message: "The state has to be either ACTIVE or INACTIVE".into(),
+            });
+        }
+        key.state = attr.state;
+        key.updated = self.clock.now();
+        Ok(key.clone())
+    }
+
+    async fn delete_hmac_key(&self, project: &str, access_id: &str) -> AppResult<(), Errors> {
+        let project = self.projects.resolve(project);
+        let mut key = self
+            .hmac_keys
+            .keys
+            .get_mut(access_id)
+            .filter(|k| k.project.id == project.id)
+            .ok_or_else(|| hmac_key_not_found(access_id))?;
+        if key.state != HmacKeyState::Inactive {
+            return Err(Errors::BadRequest {
+                message: "Only an INACTIVE HMAC key can be deleted".into(),
+            });
+        }
+        key.state = HmacKeyState::Deleted;
+        key.updated = self.clock.now();
+        Ok(())
+    }
+
+    async fn active_hmac_key(&self, access_id: &str) -> AppResult<HmacKey, Errors> {
+        self.hmac_keys
+            .keys
+            .get(access_id)
+            .filter(|k| k.state == HmacKeyState::Active)
+            .map(|k| k.clone())
+            .ok_or_else(|| Errors::InvalidAccessKeyId {
+                message: format!(
+                    "The access ID you provided does not exist or is not active: {access_id}"
+                ),
+            })
+    }
+}
+
+fn hmac_key_not_found(access_id: &str) -> Errors {
+    Errors::HmacKeyNotFound {
+        message: format!("HMAC key not found: {access_id}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use googletest::prelude::*;
+
+    use crate::{
+        libs::errors::Errors,
+        storage::{
+            hmac::{HmacKey, HmacKeyState, HmacKeyStorageExt, UpdateHmacKeyAttr},
+            Storage,
+        },
+    };
+
+    const SERVICE_ACCOUNT: &str = "sa@test-project.iam.gserviceaccount.com";
+
+    #[googletest::test]
+    #[tokio::test]
+    async fn find_imported_key() {
+        // Arrange
+        let storage = Storage::default();
+        let _ = storage
+            .import_hmac_key("test-project", SERVICE_ACCOUNT, "GOOG1EXAMPLE", "secret")
+            .await;
+
+        // Act
+        let found = storage.active_hmac_key("GOOG1EXAMPLE").await;
+        let unknown = storage.active_hmac_key("GOOG1UNKNOWN").await;
+
+        // Assert
+        expect_that!(found, ok(field!(HmacKey.secret, eq("secret"))));
+        assert_that!(
+            unknown,
+            err(matches_pattern!(Errors::InvalidAccessKeyId { .. }))
+        );
+    }
+
+    #[googletest::test]
+    #[tokio::test]
+    async fn create_key_with_generated_secret() {
+        // Arrange
+        let storage = Storage::default();
+
+        // Act
+        let key = storage
+            .create_hmac_key("test-project", SERVICE_ACCOUNT)
+            .await
+            .unwrap();
+
+        // Assert
+        expect_that!(key.access_id, starts_with("GOOG1E"));
+        expect_that!(key.access_id.len(), eq(61));
+        expect_that!(key.secret.len(), eq(40));
+        assert_that!(key.state, eq(HmacKeyState::Active));
+    }
+
+    #[googletest::test]
+    #[tokio::test]
+    async fn delete_only_inactive_key() {
+        // Arrange
+        let storage = Storage::default();
+        let key = storage
+            .create_hmac_key("test-project", SERVICE_ACCOUNT)
+            .await
+            .unwrap();
+        let deactivate = UpdateHmacKeyAttr {
+            state: HmacKeyState::Inactive,
+            etag: None,
+        };
+
+        // Act
+        let active = storage
+            .delete_hmac_key("test-project", &key.access_id)
+            .await;
+        let _ = storage
+            .update_hmac_key("test-project", &key.access_id, deactivate)
+            .await;
+        let inactive = storage
+            .delete_hmac_key("test-project", &key.access_id)
+            .await;
+
+        // Assert
+        expect_that!(active, err(matches_pattern!(Errors::BadRequest { .. })));
+        expect_that!(inactive, ok(anything()));
+        expect_that!(
+            storage
+                .list_hmac_keys("test-project", Some(SERVICE_ACCOUNT), false)
+                .await,
+            empty()
+        );
+        assert_that!(
+            storage.active_hmac_key(&key.access_id).await,
+            err(matches_pattern!(Errors::InvalidAccessKeyId { .. }))
+        );
+    }
+}
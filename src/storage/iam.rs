@@ -0,0 +1,620 @@
+use base64::{prelude::BASE64_STANDARD, Engine};
+use chrono::{DateTime, Duration, Utc};
+
+use crate::libs::{
+    errors::{AppResult, Errors},
+    registry::Project,
+};
+
+use super::{
+    acl::{self, AccessControl},
+    event::Action,
+    object::find_object,
+    OnMemoryStorageBucket, Storage,
+};
+
+/// The IAM policy of a bucket.
+/// https://cloud.google.com/storage/docs/json_api/v1/buckets/getIamPolicy
+#[derive(Debug, Clone, PartialEq)]
+pub struct IamPolicy {
+    pub version: u32,
+    pub bindings: Vec<IamBinding>,
+    /// Incremented on every `setIamPolicy` to derive the etag.
+    pub revision: u64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct IamBinding {
+    pub role: String,
+    pub members: Vec<String>,
+}
+
+impl IamBinding {
+    pub fn new(role: impl Into<String>, members: &[String]) -> Self {
+        Self {
+            role: role.into(),
+            members: members.to_vec(),
+        }
+    }
+}
+
+impl IamPolicy {
+    /// The policy GCS gives a new bucket, which grants the legacy roles to the project teams.
+    pub fn project_default(project: &Project) -> Self {
+        Self {
+            version: 1,
+            bindings: vec![
+                IamBinding::new(
+                    "roles/storage.legacyBucketOwner",
+                    &[
+                        format!("projectEditor:{}", project.id),
+                        format!("projectOwner:{}", project.id),
+                    ],
+                ),
+                IamBinding::new(
+                    "roles/storage.legacyBucketReader",
+                    &[format!("projectViewer:{}", project.id)],
+                ),
+            ],
+            revision: 1,
+        }
+    }
+
+    pub fn etag(&self) -> String {
+        BASE64_STANDARD.encode(format!("{}", self.revision))
+    }
+}
+
+/// The `iamConfiguration` of a bucket.
+/// https://cloud.google.com/storage/docs/json_api/v1/buckets#iamConfiguration
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct IamConfiguration {
+    /// Set while uniform bucket-level access is enabled.
+    pub uniform_bucket_level_access: Option<UniformBucketLevelAccess>,
+    pub public_access_prevention: PublicAccessPrevention,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct UniformBucketLevelAccess {
+    /// The deadline until which uniform bucket-level access can be disabled.
+    pub locked_time: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum PublicAccessPrevention {
+    #[default]
+    Inherited,
+    Enforced,
+}
+
+/// GCS allows disabling uniform bucket-level access for 90 days after enabling it.
+const UNIFORM_BUCKET_LEVEL_ACCESS_LOCK_PERIOD: Duration = Duration::days(90);
+
+impl IamConfiguration {
+    pub fn new(
+        uniform_bucket_level_access: bool,
+        public_access_prevention: PublicAccessPrevention,
+        now: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            uniform_bucket_level_access: uniform_bucket_level_access.then(|| {
+                UniformBucketLevelAccess {
+                    locked_time: now + UNIFORM_BUCKET_LEVEL_ACCESS_LOCK_PERIOD,
+                }
+            }),
+            public_access_prevention,
+        }
+    }
+
+    pub fn ubla_enabled(&self) -> bool {
+        self.uniform_bucket_level_access.is_some()
+    }
+
+    /// Turns uniform bucket-level access on or off, which fails once it's locked.
+    pub fn set_ubla(&mut self, enabled: bool, now: DateTime<Utc>) -> AppResult<(), Errors> {
+        match (&self.uniform_bucket_level_access, enabled) {
+            (Some(ubla), false) if ubla.locked_time <= now => Err(Errors::BadRequest {
+                message: "Uniform bucket-level access is locked and can no longer be disabled"
+                    .into(),
+            }),
+            (Some(_), false) => {
+                self.uniform_bucket_level_access = None;
+                Ok(())
+            }
+            (None, true) => {
+                *self = IamConfiguration::new(true, self.public_access_prevention, now);
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Rejects legacy ACLs under uniform bucket-level access, and public entries under public access prevention.
+    pub fn check_acl(&self, acl: &[AccessControl]) -> AppResult<(), Errors> {
+        self.check_legacy_acl()?;
+        self.check_public(acl.iter().map(|a| a.entity.as_str()))
+    }
+
+    /// Rejects any use of the ACL API under uniform bucket-level access.
+    pub fn check_legacy_acl(&self) -> AppResult<(), Errors> {
+        if self.ubla_enabled() {
+            return Err(Errors::BadRequest {
+                message: "Cannot use ACL API when uniform bucket-level access is enabled".into(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Rejects `allUsers` and `allAuthenticatedUsers` under public access prevention.
+    pub fn check_public<'a>(
+        &self,
+        mut members: impl Iterator<Item = &'a str>,
+    ) -> AppResult<(), Errors> {
+        if self.public_access_prevention == PublicAccessPrevention::Enforced
+            && members.any(|m| m == acl::ALL_USERS || m == acl::ALL_AUTHENTICATED_USERS)
+        {
+            return Err(Errors::PreconditionFailed {
+                message: "Public access prevention is enforced on the bucket".into(),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Fields given on `setIamPolicy`. The policy is replaced only if `etag` matches the current one.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SetIamPolicyAttr {
+    pub version: Option<u32>,
+    pub bindings: Vec<IamBinding>,
+    pub etag: Option<String>,
+}
+
+const BUCKET_PERMISSIONS: &[&str] = &[
+    "storage.buckets.create",
+    "storage.buckets.delete",
+    "storage.buckets.get",
+    "storage.buckets.getIamPolicy",
+    "storage.buckets.list",
+    "storage.buckets.restore",
+    "storage.buckets.setIamPolicy",
+    "storage.buckets.update",
+];
+
+const OBJECT_PERMISSIONS: &[&str] = &[
+    "storage.objects.create",
+    "storage.objects.delete",
+    "storage.objects.get",
+    "storage.objects.getIamPolicy",
+    "storage.objects.list",
+    "storage.objects.restore",
+    "storage.objects.setIamPolicy",
+    "storage.objects.update",
+];
+
+/// Permissions of the predefined roles related to Cloud Storage.
+/// https://cloud.google.com/storage/docs/access-control/iam-roles
+pub fn role_permissions(role: &str) -> Vec<&'static str> {
+    match role {
+        "roles/owner" | "roles/editor" | "roles/storage.admin" => {
+            [BUCKET_PERMISSIONS, OBJECT_PERMISSIONS].concat()
+        }
+        "roles/viewer" => vec![
+            "storage.buckets.get",
+            "storage.buckets.list",
+            "storage.objects.get",
+            "storage.objects.list",
+        ],
+        "roles/storage.objectAdmin" => OBJECT_PERMISSIONS.to_vec(),
+        "roles/storage.objectUser" => vec![
+            "storage.objects.create",
+            "storage.objects.delete",
+            "storage.objects.get",
+            "storage.objects.list",
+            "storage.objects.update",
+        ],
+        "roles/storage.objectCreator" => vec!["storage.objects.create"],
+        "roles/storage.objectViewer" => vec!["storage.objects.get", "storage.objects.list"],
+        "roles/storage.legacyBucketOwner" => vec![
+            "storage.buckets.get",
+            "storage.buckets.getIamPolicy",
+            "storage.buckets.setIamPolicy",
+            "storage.buckets.update",
+            "storage.objects.create",
+            "storage.objects.delete",
+            "storage.objects.list",
+        ],
+        "roles/storage.legacyBucketWriter" => vec![
+            "storage.buckets.get",
+            "storage.objects.create",
+            "storage.objects.delete",
+            "storage.objects.list",
+        ],
+        "roles/storage.legacyBucketReader" => vec!["storage.buckets.get", "storage.objects.list"],
+        "roles/storage.legacyObjectOwner" => vec![
+            "storage.objects.get",
+            "storage.objects.getIamPolicy",
+            "storage.objects.setIamPolicy",
+            "storage.objects.update",
+        ],
+        "roles/storage.legacyObjectReader" => vec!["storage.objects.get"],
+        _ => vec![],
+    }
+}
+
+/// Matches a member of a binding, e.g. `user:alice@example.com` or `domain:example.com`, against the principal.
+/// Project convenience members such as `projectOwner:my-project` only match the principal spelled the same way.
+pub fn member_matches(member: &str, principal: Option<&str>) -> bool {
+    match (member, principal) {
+        ("allUsers", _) => true,
+        ("allAuthenticatedUsers", principal) => principal.is_some(),
+        (member, Some(principal)) => {
+            if let Some(domain) = member.strip_prefix("domain:") {
+                return principal
+                    .split_once(':')
+                    .and_then(|(_, email)| email.rsplit_once('@'))
+                    .is_some_and(|(_, d)| d.eq_ignore_ascii_case(domain));
+            }
+            member.eq_ignore_ascii_case(principal)
+        }
+        (_, None) => false,
+    }
+}
+
+/// Aggregates IAM operations for a bucket.
+pub trait IamStorageExt {
+    /// Corresponds to `getIamPolicy` operation: https://cloud.google.com/storage/docs/json_api/v1/buckets/getIamPolicy
+    async fn get_iam_policy(&self, bucket: &str) -> AppResult<IamPolicy, Errors>;
+
+    /// Corresponds to `setIamPolicy` operation: https://cloud.google.com/storage/docs/json_api/v1/buckets/setIamPolicy
+    async fn set_iam_policy(
+        &self,
+        bucket: &str,
+        attr: SetIamPolicyAttr,
+    ) -> AppResult<IamPolicy, Errors>;
+
+    /// Checks whether the principal has `permission` on the bucket, or on the live version of `object` if given.
+    /// Either the IAM policy or the ACLs grant permissions, and project teams of the bucket have the basic roles.
+    /// Missing buckets and objects are left for the operation itself to report.
+    async fn authorize(
+        &self,
+        bucket: &str,
+        object: Option<&str>,
+        principal: Option<&str>,
+        permission: &str,
+    ) -> AppResult<(), Errors>;
+
+    /// Corresponds to `testIamPermissions` operation: https://cloud.google.com/storage/docs/json_api/v1/buckets/testIamPermissions
+    /// Returns the subset of `permissions` the principal has.
+    async fn test_iam_permissions(
+        &self,
+        bucket: &str,
+        principal: Option<&str>,
+        permissions: Vec<String>,
+    ) -> AppResult<Vec<String>, Errors>;
+}
+
+impl IamStorageExt for Storage {
+    async fn get_iam_policy(&self, bucket: &str) -> AppResult<IamPolicy, Errors> {
+        let bucket = self.bucket(bucket)?;
+        let policy = bucket.lock().unwrap().attr.iam_policy.clone();
+        Ok(policy)
+    }
+
+    async fn set_iam_policy(
+        &self,
+        bucket: &str,
+        attr: SetIamPolicyAttr,
+    ) -> AppResult<IamPolicy, Errors> {
+        let bucket = self.bucket(bucket)?;
+        let mut bucket = bucket.lock().unwrap();
+        let current = &bucket.attr.iam_policy;
+        if attr.etag.as_ref().is_some_and(|e| e != &current.etag()) {
+            return Err(Errors::PreconditionFailed {
+                message: "The etag of the policy doesn't match the current one".into(),
+            });
+        }
+
+        bucket.attr.iam_configuration.check_public(
+            attr.bindings
+                .iter()
+                .flat_map(|b| b.members.iter().map(String::as_str)),
+        )?;
+
+        let policy = IamPolicy {
+            version: attr.version.unwrap_or(current.version),
+            bindings: attr
+                .bindings
+                .into_iter()
+                .filter(|b| !b.members.is_empty())
+                .collect(),
+            revision: current.revision + 1,
+        };
+        bucket.attr.iam_policy = policy.clone();
+        bucket.attr.metageneration += 1;
+        bucket.attr.updated = self.clock.now();
+        self.events
+            .publish_bucket(Action::Update, &bucket.attr, bucket.attr.updated);
+        Ok(policy)
+    }
+
+    async fn authorize(
+        &self,
+        bucket: &str,
+        object: Option<&str>,
+        principal: Option<&str>,
+        permission: &str,
+    ) -> AppResult<(), Errors> {
+        let Ok(bucket) = self.bucket(bucket) else {
+            return Ok(());
+        };
+        let bucket = bucket.lock().unwrap();
+        if !allows(&bucket, object, principal, permission) {
+            return Err(Errors::Forbidden {
+                message: format!(
+                    "{} does not have {permission} access to the Google Cloud Storage bucket.",
+                    principal.unwrap_or("Anonymous caller")
+                ),
+            });
+        }
+        Ok(())
+    }
+
+    async fn test_iam_permissions(
+        &self,
+        bucket: &str,
+        principal: Option<&str>,
+        permissions: Vec<String>,
+    ) -> AppResult<Vec<String>, Errors> {
+        let bucket = self.bucket(bucket)?;
+        let bucket = bucket.lock().unwrap();
+        Ok(permissions
+            .into_iter()
+            .filter(|p| allows(&bucket, None, principal, p))
+            .collect())
+    }
+}
+
+fn allows(
+    bucket: &OnMemoryStorageBucket,
+    object: Option<&str>,
+    principal: Option<&str>,
+    permission: &str,
+) -> bool {
+    let attr = &bucket.attr;
+    let is_public = |member: &str| {
+        attr.iam_configuration.public_access_prevention == PublicAccessPrevention::Enforced
+            && (member == acl::ALL_USERS || member == acl::ALL_AUTHENTICATED_USERS)
+    };
+    let acl_allows = |acl: &[AccessControl], object: bool| {
+        !attr.iam_configuration.ubla_enabled()
+            && acl.iter().any(|a| {
+                acl::role_permissions(a.role, object).contains(&permission)
+                    && !is_public(&a.entity)
+                    && acl::entity_matches(&a.entity, principal, &attr.project)
+            })
+    };
+    let project_role = match principal.and_then(|p| p.split_once(':')) {
+        Some(("projectOwner" | "projectEditor", id)) if id == attr.project.id => "roles/editor",
+        Some(("projectViewer", id)) if id == attr.project.id => "roles/viewer",
+        _ => "",
+    };
+
+    role_permissions(project_role).contains(&permission)
+        || attr
+            .iam_policy
+            .bindings
+            .iter()
+            .filter(|b| role_permissions(&b.role).contains(&permission))
+            .flat_map(|b| b.members.iter())
+            .any(|m| !is_public(m) && member_matches(m, principal))
+        || acl_allows(&attr.acl, false)
+        || object
+            .and_then(|o| find_object(bucket, o, None).ok())
+            .is_some_and(|o| acl_allows(&o.attr.acl, true))
+}
+
+#[cfg(test)]
+mod tests {
+    use googletest::prelude::*;
+
+    use crate::{
+        libs::errors::Errors,
+        storage::{
+            acl::{AccessControl, AclRole, AclStorageExt, AclTarget},
+            iam::{IamBinding, IamStorageExt, PublicAccessPrevention, SetIamPolicyAttr},
+            BucketStorageExt, CreateBucketAttr, Storage, UpdateBucketAttr,
+        },
+    };
+
+    async fn storage_with_bucket() -> Storage {
+        let storage = Storage::default();
+        let attr = CreateBucketAttr {
+            project: "test-project".into(),
+            ..Default::default()
+        };
+        let _ = storage.create("test_bucket", attr).await;
+        storage
+    }
+
+    #[googletest::test]
+    #[tokio::test]
+    async fn return_only_permissions_granted_to_principal() {
+        // Arrange
+        let storage = storage_with_bucket().await;
+        let _ = storage
+            .set_iam_policy(
+                "test_bucket",
+                SetIamPolicyAttr {
+                    bindings: vec![
+                        IamBinding::new(
+                            "roles/storage.objectViewer",
+                            &["domain:example.com".to_string()],
+                        ),
+                        IamBinding::new(
+                            "roles/storage.objectCreator",
+                            &["user:bob@example.com".to_string()],
+                        ),
+                    ],
+                    ..Default::default()
+                },
+            )
+            .await;
+
+        // Act
+        let res = storage
+            .test_iam_permissions(
+                "test_bucket",
+                Some("user:alice@example.com"),
+                vec![
+                    "storage.objects.get".into(),
+                    "storage.objects.create".into(),
+                ],
+            )
+            .await;
+
+        // Assert
+        assert_that!(res, ok(elements_are![eq("storage.objects.get")]));
+    }
+
+    #[googletest::test]
+    #[tokio::test]
+    async fn authorize_principal_by_either_iam_policy_or_acl() {
+        // Arrange
+        let storage = storage_with_bucket().await;
+        let _ = storage
+            .upsert_acl(
+                "test_bucket",
+                &AclTarget::Bucket,
+                AccessControl::new("user-writer@example.com", AclRole::Writer),
+            )
+            .await;
+
+        // Act
+        let by_acl = storage
+            .authorize(
+                "test_bucket",
+                None,
+                Some("user:writer@example.com"),
+                "storage.objects.create",
+            )
+            .await;
+        let by_project = storage
+            .authorize(
+                "test_bucket",
+                None,
+                Some("projectViewer:test-project"),
+                "storage.objects.list",
+            )
+            .await;
+        let denied = storage
+            .authorize(
+                "test_bucket",
+                None,
+                Some("user:writer@example.com"),
+                "storage.buckets.delete",
+            )
+            .await;
+
+        // Assert
+        expect_that!(by_acl, ok(anything()));
+        expect_that!(by_project, ok(anything()));
+        assert_that!(denied, err(matches_pattern!(Errors::Forbidden { .. })));
+    }
+
+    #[googletest::test]
+    #[tokio::test]
+    async fn return_precondition_failed_when_etag_is_stale() {
+        // Arrange
+        let storage = storage_with_bucket().await;
+        let stale = storage.get_iam_policy("test_bucket").await.unwrap().etag();
+        let _ = storage
+            .set_iam_policy("test_bucket", SetIamPolicyAttr::default())
+            .await;
+
+        // Act
+        let res = storage
+            .set_iam_policy(
+                "test_bucket",
+                SetIamPolicyAttr {
+                    etag: Some(stale),
+                    ..Default::default()
+                },
+            )
+            .await;
+
+        // Assert
+        assert_that!(
+            res,
+            err(matches_pattern!(Errors::PreconditionFailed { .. }))
+        );
+    }
+
+    #[googletest::test]
+    #[tokio::test]
+    async fn reject_acl_api_under_uniform_bucket_level_access() {
+        // Arrange
+        let storage = storage_with_bucket().await;
+        let _ = storage
+            .update(
+                "test_bucket",
+                UpdateBucketAttr {
+                    uniform_bucket_level_access: Some(true),
+                    ..Default::default()
+                },
+            )
+            .await;
+
+        // Act
+        let res = storage.list_acl("test_bucket", &AclTarget::Bucket).await;
+
+        // Assert
+        assert_that!(res, err(matches_pattern!(Errors::BadRequest { .. })));
+    }
+
+    #[googletest::test]
+    #[tokio::test]
+    async fn reject_public_members_under_public_access_prevention() {
+        // Arrange
+        let storage = storage_with_bucket().await;
+        let _ = storage
+            .update(
+                "test_bucket",
+                UpdateBucketAttr {
+                    public_access_prevention: Some(PublicAccessPrevention::Enforced),
+                    ..Default::default()
+                },
+            )
+            .await;
+
+        // Act
+        let by_acl = storage
+            .upsert_acl(
+                "test_bucket",
+                &AclTarget::Bucket,
+                AccessControl::new("allUsers", AclRole::Reader),
+            )
+            .await;
+        let by_policy = storage
+            .set_iam_policy(
+                "test_bucket",
+                SetIamPolicyAttr {
+                    bindings: vec![IamBinding::new(
+                        "roles/storage.objectViewer",
+                        &["allUsers".to_string()],
+                    )],
+                    ..Default::default()
+                },
+            )
+            .await;
+
+        // Assert
+        expect_that!(
+            by_acl,
+            err(matches_pattern!(Errors::PreconditionFailed { .. }))
+        );
+        assert_that!(
+            by_policy,
+            err(matches_pattern!(Errors::PreconditionFailed { .. }))
+        );
+    }
+}
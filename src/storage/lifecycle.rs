@@ -0,0 +1,272 @@
+use chrono::{DateTime, NaiveDate, Utc};
+
+use crate::libs::errors::{AppResult, Errors};
+
+use super::{
+    notification::EventType, object::check_hold, retention::check_retention,
+    soft_delete::discard_object, ObjectGeneration, ObjectName, OnMemoryStorageBucket, Storage,
+    StorageObjectAttr,
+};
+
+/// A rule of the `lifecycle` configuration of a bucket.
+/// https://cloud.google.com/storage/docs/lifecycle
+#[derive(Debug, Clone, PartialEq)]
+pub struct LifecycleRule {
+    pub action: LifecycleAction,
+    pub condition: LifecycleCondition,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum LifecycleAction {
+    Delete,
+    SetStorageClass(String),
+}
+
+/// Conditions of a rule, all of which have to be met. Unset conditions are ignored.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LifecycleCondition {
+    /// Days since the object was created.
+    pub age: Option<u32>,
+    pub created_before: Option<NaiveDate>,
+    pub num_newer_versions: Option<u32>,
+    pub is_live: Option<bool>,
+    pub matches_prefix: Vec<String>,
+    pub matches_suffix: Vec<String>,
+    /// Days since the object became noncurrent.
+    pub days_since_noncurrent_time: Option<u32>,
+    pub matches_storage_class: Vec<String>,
+}
+
+impl LifecycleCondition {
+    fn is_empty(&self) -> bool {
+        self == &LifecycleCondition::default()
+    }
+
+    fn matches(
+        &self,
+        object: &StorageObjectAttr,
+        newer_versions: usize,
+        now: DateTime<Utc>,
+    ) -> bool {
+        let days_since = |time: DateTime<Utc>| (now - time).num_days();
+        self.age
+            .is_none_or(|age| days_since(object.time_created) >= i64::from(age))
+            && self
+                .created_before
+                .is_none_or(|date| object.time_created.to_utc().date_naive() < date)
+            && self
+                .num_newer_versions
+                .is_none_or(|n| newer_versions >= n as usize)
+            && self.is_live.is_none_or(|live| object.is_live() == live)
+            && (self.matches_prefix.is_empty()
+                || self
+                    .matches_prefix
+                    .iter()
+                    .any(|p| object.name.starts_with(p)))
+            && (self.matches_suffix.is_empty()
+                || self.matches_suffix.iter().any(|s| object.name.ends_with(s)))
+            && self.days_since_noncurrent_time.is_none_or(|days| {
+                object
+                    .time_deleted
+                    .is_some_and(|t| days_since(t) >= i64::from(days))
+            })
+            && (self.matches_storage_class.is_empty()
+                || self.matches_storage_class.contains(&object.storage_class))
+    }
+}
+
+/// Rejects rules without any condition, which GCS doesn't accept.
+pub(super) fn check_lifecycle(rules: &[LifecycleRule]) -> AppResult<(), Errors> {
+    if rules.iter().any(|r| r.condition.is_empty()) {
+        return Err(Errors::BadRequest {
+            message: "A lifecycle rule must have at least one condition".into(),
+        });
+    }
+    Ok(())
+}
+
+/// Aggregates operations for Object Lifecycle Management.
+pub trait LifecycleStorageExt {
+    /// Applies the lifecycle rules of every bucket as of the emulator clock.
+    /// Returns the number of objects deleted or transitioned to another storage class.
+    async fn apply_lifecycle(&self) -> usize;
+}
+
+impl LifecycleStorageExt for Storage {
+    async fn apply_lifecycle(&self) -> usize {
+        let now = self.clock.now();
+        let buckets = self
+            .buckets
+            .iter()
+            .map(|b| b.value().clone())
+            .collect::<Vec<_>>();
+        buckets
+            .iter()
+            .map(|bucket| apply_rules(self, &bucket.lock().unwrap(), now))
+            .sum()
+    }
+}
+
+/// Deleting takes precedence over changing the storage class when both match an object.
+/// Objects under a hold or a retention are never deleted, and deleted ones are kept as soft-deleted
+/// if the bucket enables soft delete.
+fn apply_rules(storage: &Storage, bucket: &OnMemoryStorageBucket, now: DateTime<Utc>) -> usize {
+    if bucket.attr.lifecycle.is_empty() {
+        return 0;
+    }
+    let objects = bucket
+        .objects
+        .iter()
+        .map(|o| o.attr.clone())
+        .filter(|o| !o.is_soft_deleted())
+        .collect::<Vec<_>>();
+
+    let mut applied = 0;
+    for object in &objects {
+        let newer_versions = objects
+            .iter()
+            .filter(|o| o.name == object.name && o.generation > object.generation)
+            .count();
+        let actions = bucket
+            .attr
+            .lifecycle
+            .iter()
+            .filter(|r| r.condition.matches(object, newer_versions, now))
+            .map(|r| &r.action)
+            .collect::<Vec<_>>();
+        let key = (
+            ObjectName(object.name.clone()),
+            ObjectGeneration(object.generation),
+        );
+
+        if actions.contains(&&LifecycleAction::Delete) {
+            if check_hold(bucket, object).is_err() || check_retention(bucket, object, now).is_err()
+            {
+                continue;
+            }
+            let event_type = if object.is_live() && bucket.attr.versioning {
+                if let Some(mut noncurrent) = bucket.objects.get_mut(&key) {
+                    noncurrent.attr.time_deleted = Some(now);
+                }
+                EventType::Archive
+            } else {
+                discard_object(bucket, &key, now);
+                EventType::Delete
+            };
+            let deleted = bucket
+                .objects
+                .get(&key)
+                .map_or_else(|| object.clone(), |o| o.attr.clone());
+            storage.publish_object_event(bucket, event_type, &deleted, None, now);
+            applied += 1;
+        } else if let Some(LifecycleAction::SetStorageClass(storage_class)) = actions.last() {
+            if storage_class == &object.storage_class {
+                continue;
+            }
+            let transitioned = bucket.objects.get_mut(&key).map(|mut transitioned| {
+                transitioned.attr.storage_class = storage_class.clone();
+                transitioned.attr.updated = now;
+                transitioned.attr.clone()
+            });
+            if let Some(transitioned) = transitioned {
+                storage.publish_object_event(
+                    bucket,
+                    EventType::MetadataUpdate,
+                    &transitioned,
+                    None,
+                    now,
+                );
+                applied += 1;
+            }
+        }
+    }
+    applied
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+    use chrono::Duration;
+    use googletest::prelude::*;
+
+    use crate::storage::{
+        lifecycle::{LifecycleAction, LifecycleCondition, LifecycleRule, LifecycleStorageExt},
+        BucketStorageExt, CreateBucketAttr, CreateObjectAttr, ObjectStorageExt, Preconditions,
+        Storage,
+    };
+
+    async fn storage_with_object(lifecycle: Vec<LifecycleRule>) -> Storage {
+        let storage = Storage::default();
+        let attr = CreateBucketAttr {
+            project: "test-project".into(),
+            lifecycle,
+            ..Default::default()
+        };
+        let _ = storage.create("test_bucket", attr).await;
+        let _ = storage
+            .create_object(
+                "test_bucket",
+                CreateObjectAttr {
+                    name: "logs/a.txt".into(),
+                    ..Default::default()
+                },
+                Bytes::new(),
+                Preconditions::default(),
+            )
+            .await;
+        storage
+    }
+
+    #[googletest::test]
+    #[tokio::test]
+    async fn delete_object_once_clock_passes_its_age() {
+        // Arrange
+        let storage = storage_with_object(vec![LifecycleRule {
+            action: LifecycleAction::Delete,
+            condition: LifecycleCondition {
+                age: Some(30),
+                matches_prefix: vec!["logs/".into()],
+                ..Default::default()
+            },
+        }])
+        .await;
+
+        // Act
+        let before = storage.apply_lifecycle().await;
+        storage.clock().advance(Duration::days(30));
+        let after = storage.apply_lifecycle().await;
+        let res = storage
+            .get_object("test_bucket", "logs/a.txt", None, Preconditions::default())
+            .await;
+
+        // Assert
+        expect_that!(before, eq(0));
+        expect_that!(after, eq(1));
+        assert_that!(res, err(anything()));
+    }
+
+    #[googletest::test]
+    #[tokio::test]
+    async fn change_storage_class_of_matching_object() {
+        // Arrange
+        let storage = storage_with_object(vec![LifecycleRule {
+            action: LifecycleAction::SetStorageClass("COLDLINE".into()),
+            condition: LifecycleCondition {
+                matches_storage_class: vec!["STANDARD".into()],
+                ..Default::default()
+            },
+        }])
+        .await;
+
+        // Act
+        let applied = storage.apply_lifecycle().await;
+        let res = storage
+            .get_object("test_bucket", "logs/a.txt", None, Preconditions::default())
+            .await
+            .unwrap();
+
+        // Assert
+        expect_that!(applied, eq(1));
+        assert_that!(res.attr.storage_class, eq("COLDLINE"));
+    }
+}
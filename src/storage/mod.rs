@@ -3,19 +3,75 @@ use std::{
     sync::{Arc, Mutex},
 };
 
-use chrono::{DateTime, Local};
-use dashmap::DashMap;
+use acl::{AccessControl, AclSpec};
+use bytes::Bytes;
+use channel::Channels;
+use chrono::{DateTime, Utc};
+use cors::CorsRule;
+use dashmap::{mapref::entry::Entry, DashMap};
+use encryption::CustomerEncryption;
+use event::{Action, Events};
+use hmac::HmacKeys;
+use iam::{IamConfiguration, IamPolicy, PublicAccessPrevention};
+use lifecycle::LifecycleRule;
+use multipart::MultipartUploads;
+use notification::{EventType, NotificationConfig, Notifications};
+use retention::{ObjectRetention, RetentionPolicy};
+use service_account::ServiceAccountKeys;
+use soft_delete::SoftDeletePolicy;
+
+use crate::libs::{
+    clock::Clock,
+    errors::{AppResult, Errors},
+    registry::{Project, ProjectRegistry},
+};
 
-use crate::libs::errors::{AppResult, Errors};
+pub mod acl;
+pub mod channel;
+pub mod cors;
+pub mod encryption;
+pub mod event;
+pub mod hmac;
+pub mod iam;
+pub mod lifecycle;
+pub mod multipart;
+pub mod notification;
+mod object;
+pub mod retention;
+pub mod service_account;
+pub mod soft_delete;
+
+pub use object::{
+    CreateObjectAttr, ListObjectsAttr, MetadataUpdate, ObjectList, ObjectStorageExt, Preconditions,
+    UpdateObjectAttr,
+};
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct StorageBucketAttr {
     pub name: String,
+    pub project: Project,
     pub versioning: bool,
     pub default_event_based_hold: bool,
     pub location: String,
-    pub time_created: DateTime<Local>,
-    pub updated: DateTime<Local>,
+    pub acl: Vec<AccessControl>,
+    pub default_object_acl: Vec<AccessControl>,
+    pub iam_policy: IamPolicy,
+    pub iam_configuration: IamConfiguration,
+    pub retention_policy: Option<RetentionPolicy>,
+    /// Whether objects accept their own retention, which is given only on `insert`.
+    pub object_retention: bool,
+    pub lifecycle: Vec<LifecycleRule>,
+    pub cors: Vec<CorsRule>,
+    pub soft_delete_policy: SoftDeletePolicy,
+    pub labels: HashMap<String, String>,
+    pub time_created: DateTime<Utc>,
+    pub updated: DateTime<Utc>,
+    /// Set while the bucket is soft-deleted.
+    pub soft_delete_time: Option<DateTime<Utc>>,
+    pub hard_delete_time: Option<DateTime<Utc>>,
+    /// Tells a bucket apart from soft-deleted buckets of the same name.
+    pub generation: u64,
+    pub metageneration: u64,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -27,35 +83,86 @@ pub struct StorageObjectAttr {
     pub md5_hash: String,
     pub crc32c: String,
     pub etag: String,
-    // TODO: Add ACL Rules later
-    // pub acl: String,
+    pub acl: Vec<AccessControl>,
+    pub owner: String,
 
     // HTTP headers related
     pub content_type: String,
-    pub content_encoding: String,
-    pub content_disposition: String,
-    pub content_language: String,
-    pub cache_control: String,
-
-    pub crated: DateTime<Local>,
-    pub updated: DateTime<Local>,
-    pub deleted: DateTime<Local>,
+    pub content_encoding: Option<String>,
+    pub content_disposition: Option<String>,
+    pub content_language: Option<String>,
+    pub cache_control: Option<String>,
+    /// Set when the content is encrypted by a customer-supplied encryption key.
+    pub customer_encryption: Option<CustomerEncryption>,
+
+    pub time_created: DateTime<Utc>,
+    pub updated: DateTime<Utc>,
+    /// Set once the object becomes noncurrent, i.e. it's overwritten or deleted in a versioned bucket.
+    pub time_deleted: Option<DateTime<Utc>>,
+    /// Set while the object is soft-deleted, which can be restored until `hard_delete_time`.
+    pub soft_delete_time: Option<DateTime<Utc>>,
+    pub hard_delete_time: Option<DateTime<Utc>>,
+
+    // Holds related
+    pub event_based_hold: bool,
+    pub temporary_hold: bool,
+    /// The earliest time the object can be deleted by the retention policy of the bucket.
+    pub retention_expiration_time: Option<DateTime<Utc>>,
+    pub retention: Option<ObjectRetention>,
+    pub storage_class: String,
 
     pub generation: u64,
+    pub metageneration: u64,
     pub metadata: HashMap<String, String>,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+impl StorageObjectAttr {
+    pub fn is_live(&self) -> bool {
+        self.time_deleted.is_none()
+    }
+
+    pub fn is_soft_deleted(&self) -> bool {
+        self.soft_delete_time.is_some()
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
 pub struct CreateBucketAttr {
+    /// The project ID or project number given by the `project` parameter.
+    pub project: String,
     pub versioning: bool,
     pub default_event_based_hold: bool,
     pub location: String,
+    /// `projectPrivate` is applied when `None`.
+    pub acl: Option<AclSpec>,
+    pub default_object_acl: Option<AclSpec>,
+    pub uniform_bucket_level_access: bool,
+    pub public_access_prevention: PublicAccessPrevention,
+    /// The retention period in seconds.
+    pub retention_period: Option<u64>,
+    pub object_retention: bool,
+    pub lifecycle: Vec<LifecycleRule>,
+    pub cors: Vec<CorsRule>,
+    /// The soft delete retention duration in seconds, where `0` disables soft delete.
+    pub soft_delete_retention_duration: u64,
+    pub labels: HashMap<String, String>,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+/// Fields to overwrite on an existing bucket. `None` leaves the current value untouched.
+#[derive(Debug, Clone, Default, PartialEq)]
 pub struct UpdateBucketAttr {
     pub versioning: Option<bool>,
-    pub default_event_based_hold: bool,
+    pub default_event_based_hold: Option<bool>,
+    pub acl: Option<AclSpec>,
+    pub default_object_acl: Option<AclSpec>,
+    pub uniform_bucket_level_access: Option<bool>,
+    pub public_access_prevention: Option<PublicAccessPrevention>,
+    /// `Some(None)` removes the retention policy.
+    pub retention_period: Option<Option<u64>>,
+    pub lifecycle: Option<Vec<LifecycleRule>>,
+    pub cors: Option<Vec<CorsRule>>,
+    pub soft_delete_retention_duration: Option<u64>,
+    pub labels: Option<MetadataUpdate>,
 }
 
 pub type ObjectKey = (ObjectName, ObjectGeneration);
@@ -69,9 +176,8 @@ pub struct ObjectGeneration(u64);
 #[derive(Clone, Debug)]
 pub struct OnMemoryStorageBucket {
     pub attr: StorageBucketAttr,
-    // FIXME: Remove the attribute later
-    #[allow(dead_code)]
     pub objects: DashMap<ObjectKey, OnMemoryStorageObject>,
+    pub notification_configs: Vec<NotificationConfig>,
 }
 
 impl OnMemoryStorageBucket {
@@ -83,25 +189,43 @@ impl OnMemoryStorageBucket {
 #[derive(Clone, Debug, PartialEq)]
 pub struct OnMemoryStorageObject {
     pub attr: StorageObjectAttr,
-    pub content: Vec<u8>,
+    pub content: Bytes,
 }
 
 type StorageBucket = Arc<Mutex<OnMemoryStorageBucket>>;
 
+/// Buckets keyed by their name. When both are held, the shard lock of an entry is taken before the
+/// mutex of its bucket, e.g. by `get_mut` or `remove_if`. Never look up an entry while holding a bucket's mutex.
 type StorageBuckets = Arc<DashMap<String, StorageBucket>>;
 
+/// Soft-deleted buckets keyed by their name and generation, since the name can be reused.
+/// When both are held, the shard lock of `StorageBuckets` is taken first.
+type SoftDeletedBuckets = Arc<DashMap<(String, u64), StorageBucket>>;
+
 #[derive(Clone)]
-pub struct Storage(StorageBuckets);
+pub struct Storage {
+    buckets: StorageBuckets,
+    soft_deleted_buckets: SoftDeletedBuckets,
+    projects: ProjectRegistry,
+    clock: Clock,
+    notifications: Notifications,
+    channels: Channels,
+    events: Events,
+    multipart_uploads: MultipartUploads,
+    hmac_keys: HmacKeys,
+    service_account_keys: ServiceAccountKeys,
+}
 impl Default for Storage {
     fn default() -> Self {
-        Self::new()
+        Self::new(ProjectRegistry::default(), Clock::default())
     }
 }
 
 /// Aggregates operations for a bucket.
 pub trait BucketStorageExt {
     /// Corresponds to `list` operation: https://cloud.google.com/storage/docs/json_api/v1/buckets/list
-    async fn list(&self) -> Vec<StorageBucketAttr>;
+    /// Only buckets owned by the given project ID or project number are returned.
+    async fn list(&self, project: &str) -> Vec<StorageBucketAttr>;
 
     /// Corresponds to `get` operation: https://cloud.google.com/storage/docs/json_api/v1/buckets/get
     async fn get(&self, name: &str) -> Option<StorageBucketAttr>;
@@ -127,22 +251,25 @@ pub trait BucketStorageExt {
 }
 
 impl BucketStorageExt for Storage {
-    async fn list(&self) -> Vec<StorageBucketAttr> {
+    async fn list(&self, project: &str) -> Vec<StorageBucketAttr> {
         let buckets = self
-            .0
+            .buckets
             .iter()
             .map(|b| {
                 let bucket = b.value();
-                bucket.lock().unwrap().clone()
+                bucket.lock().unwrap().attr.clone()
             })
-            .collect::<Vec<OnMemoryStorageBucket>>();
-        buckets.iter().map(|b| b.attr.clone()).collect()
+            .collect::<Vec<StorageBucketAttr>>();
+        buckets
+            .into_iter()
+            .filter(|b| self.projects.matches(project, &b.project))
+            .collect()
     }
 
     async fn get(&self, name: &str) -> Option<StorageBucketAttr> {
-        self.0.get(name).map(|b| {
+        self.buckets.get(name).map(|b| {
             let bucket = b.value();
-            bucket.lock().unwrap().clone().attr
+            bucket.lock().unwrap().attr.clone()
         })
     }
 
@@ -151,37 +278,74 @@ impl BucketStorageExt for Storage {
         name: &str,
         attr: CreateBucketAttr,
     ) -> AppResult<StorageBucketAttr, Errors> {
-        if self.0.contains_key(name) {
+        // The name is held until the bucket is inserted, so that concurrent creations can't both succeed.
+        let Entry::Vacant(entry) = self.buckets.entry(name.to_string()) else {
             return Err(Errors::AlreadyExists {
                 message: "Bucket already exists".into(),
             });
-        }
+        };
 
-        self.0.insert(
-            name.to_string(),
-            Arc::new(Mutex::new(OnMemoryStorageBucket {
-                attr: StorageBucketAttr {
-                    name: name.to_string(),
-                    versioning: attr.versioning,
-                    default_event_based_hold: attr.default_event_based_hold,
-                    location: attr.location,
-                    time_created: Local::now(),
-                    updated: Local::now(),
-                },
-                objects: DashMap::new(),
-            })),
+        let project = self.projects.resolve(&attr.project);
+        let now = self.clock.now();
+        let iam_configuration = IamConfiguration::new(
+            attr.uniform_bucket_level_access,
+            attr.public_access_prevention,
+            now,
         );
+        let resolve = |spec: Option<AclSpec>| match spec {
+            Some(spec) => {
+                let acl = spec.resolve(&project);
+                iam_configuration.check_acl(&acl).map(|_| acl)
+            }
+            None => Ok(acl::project_private(&project)),
+        };
+        let acl = resolve(attr.acl)?;
+        let default_object_acl = resolve(attr.default_object_acl)?;
+        let retention_policy = RetentionPolicy::replace(None, attr.retention_period, now)?;
+        check_versioning(attr.versioning, retention_policy.as_ref())?;
+        lifecycle::check_lifecycle(&attr.lifecycle)?;
+        let soft_delete_policy = SoftDeletePolicy::new(attr.soft_delete_retention_duration, now)?;
+        entry.insert(Arc::new(Mutex::new(OnMemoryStorageBucket {
+            attr: StorageBucketAttr {
+                name: name.to_string(),
+                versioning: attr.versioning,
+                default_event_based_hold: attr.default_event_based_hold,
+                location: attr.location,
+                acl,
+                default_object_acl,
+                iam_policy: IamPolicy::project_default(&project),
+                iam_configuration,
+                retention_policy,
+                object_retention: attr.object_retention,
+                lifecycle: attr.lifecycle,
+                cors: attr.cors,
+                soft_delete_policy,
+                labels: attr.labels,
+                project,
+                time_created: now,
+                updated: now,
+                soft_delete_time: None,
+                hard_delete_time: None,
+                generation: now.timestamp_micros() as u64,
+                metageneration: 1,
+            },
+            objects: DashMap::new(),
+            notification_configs: vec![],
+        })));
 
-        self.0
+        let created = self
+            .buckets
             .get(name)
             .map(|b| {
                 let bucket = b.value();
-                bucket.lock().unwrap().clone().attr
+                bucket.lock().unwrap().attr.clone()
             })
             .ok_or(Errors::FailedToWriteStorage {
                 id: name.to_string(),
                 message: "Failed to create a new bucket".into(),
-            })
+            })?;
+        self.events.publish_bucket(Action::Create, &created, now);
+        Ok(created)
     }
 
     async fn update(
@@ -189,41 +353,209 @@ impl BucketStorageExt for Storage {
         name: &str,
         attr: UpdateBucketAttr,
     ) -> AppResult<StorageBucketAttr, Errors> {
-        let existence_bucket = self.0.get_mut(name).ok_or(Errors::BucketNotFound {
+        let existence_bucket = self.buckets.get_mut(name).ok_or(Errors::BucketNotFound {
             message: "Bucket not found".into(),
         })?;
 
+        // Locked under the entry, which follows the lock order of `StorageBuckets`.
         let mut existence_bucket = existence_bucket.lock().unwrap();
 
+        let now = self.clock.now();
+        let mut iam_configuration = existence_bucket.attr.iam_configuration.clone();
+        if let Some(public_access_prevention) = attr.public_access_prevention {
+            iam_configuration.public_access_prevention = public_access_prevention;
+        }
+        if let Some(enabled) = attr.uniform_bucket_level_access {
+            iam_configuration.set_ubla(enabled, now)?;
+        }
+        let resolve = |spec: Option<AclSpec>, current: &Vec<AccessControl>| match spec {
+            Some(spec) => {
+                let acl = spec.resolve(&existence_bucket.attr.project);
+                iam_configuration.check_acl(&acl).map(|_| acl)
+            }
+            None => Ok(current.clone()),
+        };
+        let acl = resolve(attr.acl, &existence_bucket.attr.acl)?;
+        let default_object_acl = resolve(
+            attr.default_object_acl,
+            &existence_bucket.attr.default_object_acl,
+        )?;
+        let previous_retention_policy = existence_bucket.attr.retention_policy.clone();
+        let retention_policy = match attr.retention_period {
+            Some(period) => {
+                RetentionPolicy::replace(previous_retention_policy.as_ref(), period, now)?
+            }
+            None => previous_retention_policy.clone(),
+        };
+        let versioning = attr.versioning.unwrap_or(existence_bucket.attr.versioning);
+        check_versioning(versioning, retention_policy.as_ref())?;
+        let lifecycle = attr
+            .lifecycle
+            .unwrap_or_else(|| existence_bucket.attr.lifecycle.clone());
+        lifecycle::check_lifecycle(&lifecycle)?;
+        let soft_delete_policy = match attr.soft_delete_retention_duration {
+            Some(duration) => existence_bucket
+                .attr
+                .soft_delete_policy
+                .replace(duration, now)?,
+            None => existence_bucket.attr.soft_delete_policy.clone(),
+        };
+        let mut labels = existence_bucket.attr.labels.clone();
+        if let Some(update) = attr.labels {
+            update.apply(&mut labels);
+        }
+
         let new_attr = StorageBucketAttr {
             name: existence_bucket.attr.name.clone(),
-            versioning: attr.versioning.unwrap_or(existence_bucket.attr.versioning),
-            default_event_based_hold: attr.default_event_based_hold,
+            project: existence_bucket.attr.project.clone(),
+            versioning,
+            default_event_based_hold: attr
+                .default_event_based_hold
+                .unwrap_or(existence_bucket.attr.default_event_based_hold),
             location: existence_bucket.attr.location.clone(),
+            acl,
+            default_object_acl,
+            iam_policy: existence_bucket.attr.iam_policy.clone(),
+            iam_configuration,
+            retention_policy,
+            object_retention: existence_bucket.attr.object_retention,
+            lifecycle,
+            cors: attr
+                .cors
+                .unwrap_or_else(|| existence_bucket.attr.cors.clone()),
+            soft_delete_policy,
+            labels,
             time_created: existence_bucket.attr.time_created,
-            updated: Local::now(),
+            updated: now,
+            soft_delete_time: None,
+            hard_delete_time: None,
+            generation: existence_bucket.attr.generation,
+            metageneration: existence_bucket.attr.metageneration + 1,
         };
         existence_bucket.replace_attr(new_attr);
+        if existence_bucket.attr.retention_policy != previous_retention_policy {
+            retention::apply_retention_policy(
+                &existence_bucket,
+                previous_retention_policy.as_ref(),
+            );
+        }
+        self.events
+            .publish_bucket(Action::Update, &existence_bucket.attr, now);
         Ok(existence_bucket.attr.clone())
     }
 
     async fn delete(&self, name: &str) -> AppResult<StorageBucketAttr, Errors> {
-        self.0
-            .remove(name)
+        // The bucket is checked while its entry is locked, so that it's removed only if it's still empty.
+        // Its mutex is taken under the shard lock, which follows the lock order of `StorageBuckets`.
+        // Soft-deleted objects don't keep the bucket from being deleted.
+        let removed = self.buckets.remove_if(name, |_, bucket| {
+            bucket
+                .lock()
+                .unwrap()
+                .objects
+                .iter()
+                .all(|o| o.attr.is_soft_deleted())
+        });
+        let Some((_, bucket)) = removed else {
+            // Either the bucket doesn't exist or it isn't empty.
+            self.bucket(name)?;
+            return Err(Errors::BucketNotEmpty {
+                message: format!("The bucket you tried to delete is not empty: {name}"),
+            });
+        };
+        self.multipart_uploads.remove_bucket(name);
+        let now = self.clock.now();
+        let attr = {
+            let mut deleted = bucket.lock().unwrap();
+            if let Some(duration) = deleted.attr.soft_delete_policy.retention_duration() {
+                deleted.attr.soft_delete_time = Some(now);
+                deleted.attr.hard_delete_time = Some(now + duration);
+            }
+            deleted.attr.clone()
+        };
+        self.events.publish_bucket(Action::Delete, &attr, now);
+        if attr.soft_delete_time.is_some() {
+            self.soft_deleted_buckets
+                .insert((attr.name.clone(), attr.generation), bucket);
+        }
+        Ok(attr)
+    }
+}
+
+impl Storage {
+    pub fn new(projects: ProjectRegistry, clock: Clock) -> Self {
+        Storage {
+            buckets: Arc::new(DashMap::new()),
+            soft_deleted_buckets: Arc::new(DashMap::new()),
+            projects,
+            clock,
+            notifications: Notifications::default(),
+            channels: Channels::default(),
+            events: Events::default(),
+            multipart_uploads: MultipartUploads::default(),
+            hmac_keys: HmacKeys::default(),
+            service_account_keys: ServiceAccountKeys::default(),
+        }
+    }
+
+    pub fn clock(&self) -> &Clock {
+        &self.clock
+    }
+
+    pub fn notifications(&self) -> &Notifications {
+        &self.notifications
+    }
+
+    pub fn channels(&self) -> &Channels {
+        &self.channels
+    }
+
+    pub fn events(&self) -> &Events {
+        &self.events
+    }
+
+    /// Tells notification configurations of the bucket, watching channels and event subscribers that the object has changed.
+    fn publish_object_event(
+        &self,
+        bucket: &OnMemoryStorageBucket,
+        event_type: EventType,
+        object: &StorageObjectAttr,
+        overwrite_generation: Option<u64>,
+        now: DateTime<Utc>,
+    ) {
+        self.notifications
+            .publish(bucket, event_type, object, overwrite_generation, now);
+        self.channels
+            .notify(&bucket.attr.name, event_type, object, now);
+        let action = match event_type {
+            EventType::Finalize => Action::Create,
+            EventType::MetadataUpdate => Action::Update,
+            EventType::Delete | EventType::Archive => Action::Delete,
+        };
+        self.events.publish_object(action, object, now);
+    }
+
+    fn bucket(&self, name: &str) -> AppResult<StorageBucket, Errors> {
+        self.buckets
+            .get(name)
+            .map(|b| b.value().clone())
             .ok_or(Errors::BucketNotFound {
-                message: "Bucket not found".into(),
-            })
-            .map(|b| {
-                let bucket = b.1.lock().unwrap();
-                bucket.attr.clone()
+                message: format!("Bucket not found: {name}"),
             })
     }
 }
 
-impl Storage {
-    pub fn new() -> Self {
-        Storage(Arc::new(DashMap::new()))
+/// GCS doesn't allow a retention policy on a bucket with versioning enabled.
+fn check_versioning(
+    versioning: bool,
+    retention_policy: Option<&RetentionPolicy>,
+) -> AppResult<(), Errors> {
+    if versioning && retention_policy.is_some() {
+        return Err(Errors::BadRequest {
+            message: "Retention policies and object versioning cannot be enabled together".into(),
+        });
     }
+    Ok(())
 }
 
 #[cfg(test)]
@@ -234,19 +566,82 @@ mod tests {
     use googletest::{assert_pred, prelude::*};
 
     use crate::{
-        libs::errors::Errors,
+        libs::{
+            clock::Clock,
+            errors::Errors,
+            registry::{Project, ProjectRegistry},
+        },
         storage::{
+            acl,
+            channel::Channels,
+            event::Events,
+            hmac::HmacKeys,
+            iam::{IamConfiguration, IamPolicy},
+            multipart::MultipartUploads,
+            notification::Notifications,
+            service_account::ServiceAccountKeys,
+            soft_delete::SoftDeletePolicy,
             BucketStorageExt, CreateBucketAttr, OnMemoryStorageBucket, Storage, StorageBucketAttr,
+            UpdateBucketAttr,
         },
     };
 
     trait TestStorageExt {
         fn empty() -> Self;
+        fn with_buckets(buckets: DashMap<String, Arc<Mutex<OnMemoryStorageBucket>>>) -> Self;
     }
 
     impl TestStorageExt for Storage {
         fn empty() -> Self {
-            Storage(Arc::new(DashMap::new()))
+            Self::with_buckets(DashMap::new())
+        }
+
+        fn with_buckets(buckets: DashMap<String, Arc<Mutex<OnMemoryStorageBucket>>>) -> Self {
+            Storage {
+                buckets: Arc::new(buckets),
+                soft_deleted_buckets: Arc::new(DashMap::new()),
+                projects: ProjectRegistry::default(),
+                clock: Clock::default(),
+                notifications: Notifications::default(),
+                channels: Channels::default(),
+                events: Events::default(),
+                multipart_uploads: MultipartUploads::default(),
+                hmac_keys: HmacKeys::default(),
+                service_account_keys: ServiceAccountKeys::default(),
+            }
+        }
+    }
+
+    fn test_project() -> Project {
+        Project {
+            id: "test-project".into(),
+            number: 1,
+        }
+    }
+
+    fn bucket_attr(name: &str) -> StorageBucketAttr {
+        StorageBucketAttr {
+            name: name.to_string(),
+            project: test_project(),
+            versioning: false,
+            default_event_based_hold: false,
+            location: "US-EAST1".into(),
+            acl: acl::project_private(&test_project()),
+            default_object_acl: acl::project_private(&test_project()),
+            iam_policy: IamPolicy::project_default(&test_project()),
+            iam_configuration: IamConfiguration::default(),
+            retention_policy: None,
+            object_retention: false,
+            lifecycle: vec![],
+            cors: vec![],
+            soft_delete_policy: SoftDeletePolicy::default(),
+            labels: Default::default(),
+            time_created: chrono::Utc::now(),
+            updated: chrono::Utc::now(),
+            soft_delete_time: None,
+            hard_delete_time: None,
+            generation: 1,
+            metageneration: 1,
         }
     }
 
@@ -254,39 +649,27 @@ mod tests {
     #[tokio::test]
     async fn return_all_passed_buckets() {
         // Arrange
-        let attr1 = StorageBucketAttr {
-            name: "test_bucket_1".to_string(),
-            versioning: false,
-            default_event_based_hold: false,
-            location: "US-EAST1".into(),
-            time_created: chrono::Local::now(),
-            updated: chrono::Local::now(),
-        };
+        let attr1 = bucket_attr("test_bucket_1");
         let bucket1 = Arc::new(Mutex::new(OnMemoryStorageBucket {
             attr: attr1.clone(),
             objects: DashMap::new(),
+            notification_configs: vec![],
         }));
-        let attr2 = StorageBucketAttr {
-            name: "test_bucket_2".to_string(),
-            versioning: false,
-            default_event_based_hold: false,
-            location: "US-EAST1".into(),
-            time_created: chrono::Local::now(),
-            updated: chrono::Local::now(),
-        };
+        let attr2 = bucket_attr("test_bucket_2");
         let bucket2 = Arc::new(Mutex::new(OnMemoryStorageBucket {
             attr: attr2.clone(),
             objects: DashMap::new(),
+            notification_configs: vec![],
         }));
 
         let map = DashMap::new();
         map.insert("test_bucket_1".to_string(), bucket1);
         map.insert("test_bucket_2".to_string(), bucket2);
 
-        let storage = Storage(Arc::new(map));
+        let storage = Storage::with_buckets(map);
 
         // Act
-        let mut res = storage.list().await;
+        let mut res = storage.list("test-project").await;
         // To avoid flaky tests
         res.sort_by(|a, b| a.name.cmp(&b.name));
 
@@ -294,40 +677,113 @@ mod tests {
         assert_that!(res, eq(&vec![attr1, attr2]));
     }
 
+    #[googletest::test]
+    #[tokio::test]
+    async fn return_only_buckets_owned_by_given_project() {
+        // Arrange
+        let storage = Storage::new(
+            ProjectRegistry::new(
+                [
+                    ("project-a".to_string(), 100),
+                    ("project-b".to_string(), 200),
+                ]
+                .into(),
+                1,
+            ),
+            Clock::default(),
+        );
+        for (name, project) in [
+            ("bucket_a", "project-a"),
+            ("bucket_b", "200"),
+            ("bucket_c", "unmapped-project"),
+        ] {
+            let attr = CreateBucketAttr {
+                project: project.into(),
+                versioning: false,
+                default_event_based_hold: false,
+                location: "US-EAST1".into(),
+                ..Default::default()
+            };
+            let _ = storage.create(name, attr).await;
+        }
+
+        // Act
+        let by_id = storage.list("project-b").await;
+        let by_number = storage.list("100").await;
+        let by_default_number = storage.list("1").await;
+
+        // Assert
+        assert_that!(
+            by_id,
+            elements_are![field!(StorageBucketAttr.name, eq("bucket_b"))]
+        );
+        expect_that!(
+            by_id[0].project,
+            eq(&Project {
+                id: "project-b".into(),
+                number: 200
+            })
+        );
+        assert_that!(
+            by_number,
+            elements_are![field!(StorageBucketAttr.name, eq("bucket_a"))]
+        );
+        assert_that!(
+            by_default_number,
+            elements_are![field!(StorageBucketAttr.name, eq("bucket_c"))]
+        );
+    }
+
+    #[googletest::test]
+    #[tokio::test]
+    async fn keep_buckets_of_unmapped_projects_apart() {
+        // Arrange
+        let storage = Storage::default();
+        for (name, project) in [("bucket_a", "project-a"), ("bucket_b", "project-b")] {
+            let attr = CreateBucketAttr {
+                project: project.into(),
+                ..Default::default()
+            };
+            let _ = storage.create(name, attr).await;
+        }
+
+        // Act
+        let listed_a = storage.list("project-a").await;
+        let listed_b = storage.list("project-b").await;
+
+        // Assert
+        expect_that!(
+            listed_a,
+            elements_are![field!(StorageBucketAttr.name, eq("bucket_a"))]
+        );
+        assert_that!(
+            listed_b,
+            elements_are![field!(StorageBucketAttr.name, eq("bucket_b"))]
+        );
+    }
+
     #[googletest::test]
     #[tokio::test]
     async fn return_specific_bucket() {
         // Arrange
-        let attr1 = StorageBucketAttr {
-            name: "test_bucket_1".to_string(),
-            versioning: false,
-            default_event_based_hold: false,
-            time_created: chrono::Local::now(),
-            location: "US-EAST1".into(),
-            updated: chrono::Local::now(),
-        };
+        let attr1 = bucket_attr("test_bucket_1");
         let bucket1 = Arc::new(Mutex::new(OnMemoryStorageBucket {
             attr: attr1.clone(),
             objects: DashMap::new(),
+            notification_configs: vec![],
         }));
-        let attr2 = StorageBucketAttr {
-            name: "test_bucket_2".to_string(),
-            versioning: false,
-            default_event_based_hold: false,
-            location: "US-EAST1".into(),
-            time_created: chrono::Local::now(),
-            updated: chrono::Local::now(),
-        };
+        let attr2 = bucket_attr("test_bucket_2");
         let bucket2 = Arc::new(Mutex::new(OnMemoryStorageBucket {
             attr: attr2.clone(),
             objects: DashMap::new(),
+            notification_configs: vec![],
         }));
 
         let map = DashMap::new();
         map.insert("test_bucket_1".to_string(), bucket1);
         map.insert("test_bucket_2".to_string(), bucket2);
 
-        let storage = Storage(Arc::new(map));
+        let storage = Storage::with_buckets(map);
 
         // Act
         let res = storage.get("test_bucket_2").await;
@@ -340,36 +796,24 @@ mod tests {
     #[tokio::test]
     async fn return_no_bucket_if_passed_non_exist_bucket_name() {
         // Arrange
-        let attr1 = StorageBucketAttr {
-            name: "test_bucket_1".to_string(),
-            versioning: false,
-            default_event_based_hold: false,
-            location: "US-EAST1".into(),
-            time_created: chrono::Local::now(),
-            updated: chrono::Local::now(),
-        };
+        let attr1 = bucket_attr("test_bucket_1");
         let bucket1 = Arc::new(Mutex::new(OnMemoryStorageBucket {
             attr: attr1.clone(),
             objects: DashMap::new(),
+            notification_configs: vec![],
         }));
-        let attr2 = StorageBucketAttr {
-            name: "test_bucket_2".to_string(),
-            versioning: false,
-            default_event_based_hold: false,
-            location: "US-EAST1".into(),
-            time_created: chrono::Local::now(),
-            updated: chrono::Local::now(),
-        };
+        let attr2 = bucket_attr("test_bucket_2");
         let bucket2 = Arc::new(Mutex::new(OnMemoryStorageBucket {
             attr: attr2.clone(),
             objects: DashMap::new(),
+            notification_configs: vec![],
         }));
 
         let map = DashMap::new();
         map.insert("test_bucket_1".to_string(), bucket1);
         map.insert("test_bucket_2".to_string(), bucket2);
 
-        let storage = Storage(Arc::new(map));
+        let storage = Storage::with_buckets(map);
 
         // Act
         let res = storage.get("non-exist").await;
@@ -383,9 +827,11 @@ mod tests {
     async fn return_create_bucket_after_creating_new_bucket() {
         // Arrange
         let attr = CreateBucketAttr {
+            project: "test-project".into(),
             versioning: true,
             default_event_based_hold: false,
             location: "US-EAST1".into(),
+            ..Default::default()
         };
         let storage = Storage::empty();
 
@@ -406,9 +852,11 @@ mod tests {
     async fn return_conflict_error_when_bucket_already_exists() {
         // Arrange
         let attr = CreateBucketAttr {
+            project: "test-project".into(),
             versioning: true,
             default_event_based_hold: false,
             location: "US-EAST1".into(),
+            ..Default::default()
         };
         let storage = Storage::empty();
         let _ = storage.create("test_new_bucket", attr.clone()).await;
@@ -425,9 +873,11 @@ mod tests {
     async fn return_updated_bucket_after_updating_existence_bucket() {
         // Arrange
         let attr = CreateBucketAttr {
+            project: "test-project".into(),
             versioning: true,
             default_event_based_hold: false,
             location: "US-EAST1".into(),
+            ..Default::default()
         };
         let storage = Storage::empty();
         let _ = storage.create("test_new_bucket", attr).await;
@@ -438,7 +888,8 @@ mod tests {
                 "test_new_bucket",
                 crate::storage::UpdateBucketAttr {
                     versioning: Some(false),
-                    default_event_based_hold: true,
+                    default_event_based_hold: Some(true),
+                    ..Default::default()
                 },
             )
             .await;
@@ -450,14 +901,49 @@ mod tests {
         expect_that!(res.default_event_based_hold, eq(true));
     }
 
+    #[googletest::test]
+    #[tokio::test]
+    async fn keep_untouched_fields_while_updating_bucket() {
+        // Arrange
+        let attr = CreateBucketAttr {
+            project: "test-project".into(),
+            versioning: false,
+            default_event_based_hold: true,
+            location: "US-EAST1".into(),
+            ..Default::default()
+        };
+        let storage = Storage::empty();
+        let _ = storage.create("test_new_bucket", attr).await;
+
+        // Act
+        let res = storage
+            .update(
+                "test_new_bucket",
+                crate::storage::UpdateBucketAttr {
+                    versioning: Some(true),
+                    default_event_based_hold: None,
+                    ..Default::default()
+                },
+            )
+            .await;
+
+        // Assert
+        assert_pred!(res.is_ok());
+        let res = res.unwrap();
+        expect_that!(res.versioning, eq(true));
+        expect_that!(res.default_event_based_hold, eq(true));
+    }
+
     #[googletest::test]
     #[tokio::test]
     async fn return_not_found_error_while_updating_bucket() {
         // Arrange
         let attr = CreateBucketAttr {
+            project: "test-project".into(),
             versioning: true,
             default_event_based_hold: false,
             location: "US-EAST1".into(),
+            ..Default::default()
         };
         let storage = Storage::empty();
         let _ = storage.create("test_new_bucket", attr).await;
@@ -468,7 +954,8 @@ mod tests {
                 "non_exist_bucket",
                 crate::storage::UpdateBucketAttr {
                     versioning: Some(false),
-                    default_event_based_hold: true,
+                    default_event_based_hold: Some(true),
+                    ..Default::default()
                 },
             )
             .await;
@@ -482,9 +969,11 @@ mod tests {
     async fn can_delete_existing_bucket() {
         // Arrange
         let attr = CreateBucketAttr {
+            project: "test-project".into(),
             versioning: true,
             default_event_based_hold: false,
             location: "US-EAST1".into(),
+            ..Default::default()
         };
         let storage = Storage::empty();
         let _ = storage.create("test_new_bucket", attr).await;
@@ -503,9 +992,11 @@ mod tests {
     async fn return_not_found_error_while_deleting_non_existing_bucket() {
         // Arrange
         let attr = CreateBucketAttr {
+            project: "test-project".into(),
             versioning: true,
             default_event_based_hold: false,
             location: "US-EAST1".into(),
+            ..Default::default()
         };
         let storage = Storage::empty();
         let _ = storage.create("test_new_bucket", attr).await;
@@ -516,4 +1007,28 @@ mod tests {
         // Assert
         assert_that!(res, err(matches_pattern!(Errors::BucketNotFound { .. })));
     }
+
+    #[googletest::test]
+    #[tokio::test]
+    async fn take_timestamps_from_clock() {
+        // Arrange
+        let time = chrono::DateTime::parse_from_rfc3339("2024-01-01T00:00:00Z")
+            .unwrap()
+            .to_utc();
+        let storage = Storage::new(ProjectRegistry::default(), Clock::fixed(time));
+        let _ = storage
+            .create("test_bucket", CreateBucketAttr::default())
+            .await;
+        storage.clock().advance(chrono::Duration::hours(1));
+
+        // Act
+        let res = storage
+            .update("test_bucket", UpdateBucketAttr::default())
+            .await;
+
+        // Assert
+        let res = res.unwrap();
+        expect_that!(res.time_created, eq(time));
+        assert_that!(res.updated, eq(time + chrono::Duration::hours(1)));
+    }
 }
@@ -0,0 +1,637 @@
+use std::{
+    collections::BTreeMap,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
+
+use base64::{prelude::BASE64_URL_SAFE_NO_PAD, Engine};
+use bytes::{Bytes, BytesMut};
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use md5::{Digest, Md5};
+
+use crate::libs::errors::{AppResult, Errors};
+
+use super::{
+    encryption::{self, CustomerEncryption, EncryptionKey},
+    CreateObjectAttr, ObjectStorageExt, Preconditions, Storage, StorageObjectAttr,
+};
+
+/// The range of part numbers a client can upload.
+const PART_NUMBERS: std::ops::RangeInclusive<u32> = 1..=10000;
+
+/// Every part but the last one has to be at least 5 MiB.
+const MIN_PART_SIZE: u64 = 5 * 1024 * 1024;
+
+/// An upload in progress of the XML API multipart upload, which becomes an object once completed.
+/// https://cloud.google.com/storage/docs/multipart-uploads
+#[derive(Debug, Clone, PartialEq)]
+pub struct MultipartUpload {
+    pub upload_id: String,
+    pub bucket: String,
+    /// Metadata given on initiation, which is applied to the completed object.
+    pub attr: CreateObjectAttr,
+    /// Tells which customer-supplied encryption key the upload was initiated with. The key itself
+    /// isn't kept, so each part and the completion have to be given the same key.
+    pub customer_encryption: Option<CustomerEncryption>,
+    pub initiated: DateTime<Utc>,
+    pub parts: BTreeMap<u32, Part>,
+}
+
+impl MultipartUpload {
+    fn is_for(&self, bucket: &str, name: &str) -> bool {
+        self.bucket == bucket && self.attr.name == name
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Part {
+    pub part_number: u32,
+    /// The MD5 hash of the part in hex.
+    pub etag: String,
+    pub size: u64,
+    pub last_modified: DateTime<Utc>,
+    /// Tells which customer-supplied encryption key the content is encrypted by.
+    pub customer_encryption: Option<CustomerEncryption>,
+    /// Encrypted at rest when the upload is encrypted by a customer-supplied encryption key.
+    content: Bytes,
+}
+
+/// A part listed in the request completing an upload.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompletedPart {
+    pub part_number: u32,
+    /// Either quoted or not.
+    pub etag: String,
+}
+
+/// Uploads in progress keyed by their upload ID.
+#[derive(Debug, Clone, Default)]
+pub struct MultipartUploads {
+    uploads: Arc<DashMap<String, MultipartUpload>>,
+    next_upload_number: Arc<AtomicU64>,
+}
+
+impl MultipartUploads {
+    /// Drops the uploads to a deleted bucket, which can't be completed anymore.
+    pub(super) fn remove_bucket(&self, bucket: &str) {
+        self.uploads.retain(|_, u| u.bucket != bucket);
+    }
+}
+
+/// Aggregates operations for multipart uploads.
+pub trait MultipartStorageExt {
+    /// Corresponds to `POST Object` with `uploads`: https://cloud.google.com/storage/docs/xml-api/post-object-multipart
+    async fn initiate_multipart_upload(
+        &self,
+        bucket: &str,
+        attr: CreateObjectAttr,
+    ) -> AppResult<MultipartUpload, Errors>;
+
+    /// Corresponds to `PUT Object` with `partNumber` and `uploadId`: https://cloud.google.com/storage/docs/xml-api/put-object-multipart
+    /// Uploading a part of the same number replaces the previous one.
+    async fn upload_part(
+        &self,
+        bucket: &str,
+        name: &str,
+        upload_id: &str,
+        part_number: u32,
+        content: Bytes,
+        encryption_key: Option<&EncryptionKey>,
+    ) -> AppResult<Part, Errors>;
+
+    /// Corresponds to `GET Object` with `uploadId`: https://cloud.google.com/storage/docs/xml-api/get-object-multipart
+    async fn list_parts(
+        &self,
+        bucket: &str,
+        name: &str,
+        upload_id: &str,
+    ) -> AppResult<MultipartUpload, Errors>;
+
+    /// Corresponds to `POST Object` with `uploadId`: https://cloud.google.com/storage/docs/xml-api/post-object-complete
+    /// Returns the created object along with the entity tag of the whole upload.
+    async fn complete_multipart_upload(
+        &self,
+        bucket: &str,
+        name: &str,
+        upload_id: &str,
+        parts: Vec<CompletedPart>,
+        conditions: Preconditions,
+        encryption_key: Option<EncryptionKey>,
+    ) -> AppResult<(StorageObjectAttr, String), Errors>;
+
+    /// Corresponds to `DELETE Object` with `uploadId`: https://cloud.google.com/storage/docs/xml-api/delete-multipart
+    async fn abort_multipart_upload(
+        &self,
+        bucket: &str,
+        name: &str,
+        upload_id: &str,
+    ) -> AppResult<(), Errors>;
+}
+
+impl MultipartStorageExt for Storage {
+    async fn initiate_multipart_upload(
+        &self,
+        bucket: &str,
+        attr: CreateObjectAttr,
+    ) -> AppResult<MultipartUpload, Errors> {
+        self.bucket(bucket)?;
+        if attr.name.is_empty() {
+            return Err(Errors::BadRequest {
+                message: "Object name is required".into(),
+            });
+        }
+        let upload = MultipartUpload {
+            upload_id: BASE64_URL_SAFE_NO_PAD.encode(format!(
+                "{bucket}/{}/{}",
+                attr.name,
+                self.multipart_uploads
+                    .next_upload_number
+                    .fetch_add(1, Ordering::Relaxed)
+            )),
+            bucket: bucket.to_string(),
+            customer_encryption: attr
+                .encryption_key
+                .as_ref()
+                .map(EncryptionKey::customer_encryption),
+            // Hashes of the whole object aren't known until the parts are uploaded.
+            attr: CreateObjectAttr {
+                md5_hash: None,
+                crc32c: None,
+                encryption_key: None,
+                ..attr
+            },
+            initiated: self.clock.now(),
+            parts: BTreeMap::new(),
+        };
+        self.multipart_uploads
+            .uploads
+            .insert(upload.upload_id.clone(), upload.clone());
+        Ok(upload)
+    }
+
+    async fn upload_part(
+        &self,
+        bucket: &str,
+        name: &str,
+        upload_id: &str,
+        part_number: u32,
+        content: Bytes,
+        encryption_key: Option<&EncryptionKey>,
+    ) -> AppResult<Part, Errors> {
+        if !PART_NUMBERS.contains(&part_number) {
+            return Err(Errors::BadRequest {
+                message: format!(
+                    "Part number must be an integer between 1 and 10000: {part_number}"
+                ),
+            });
+        }
+        let mut upload = self
+            .multipart_uploads
+            .uploads
+            .get_mut(upload_id)
+            .filter(|u| u.is_for(bucket, name))
+            .ok_or_else(|| upload_not_found(upload_id))?;
+        encryption::verify_key(upload.customer_encryption.as_ref(), encryption_key)?;
+        let part = Part {
+            part_number,
+            etag: hex::encode(Md5::digest(&content)),
+            size: content.len() as u64,
+            last_modified: self.clock.now(),
+            customer_encryption: encryption_key.map(EncryptionKey::customer_encryption),
+            content: match encryption_key {
+                Some(key) => key.encrypt(&content),
+                None => content,
+            },
+        };
+        upload.parts.insert(part_number, part.clone());
+        Ok(part)
+    }
+
+    async fn list_parts(
+        &self,
+        bucket: &str,
+        name: &str,
+        upload_id: &str,
+    ) -> AppResult<MultipartUpload, Errors> {
+        self.multipart_uploads
+            .uploads
+            .get(upload_id)
+            .filter(|u| u.is_for(bucket, name))
+            .map(|u| u.clone())
+            .ok_or_else(|| upload_not_found(upload_id))
+    }
+
+    async fn complete_multipart_upload(
+        &self,
+        bucket: &str,
+        name: &str,
+        upload_id: &str,
+        parts: Vec<CompletedPart>,
+        conditions: Preconditions,
+        encryption_key: Option<EncryptionKey>,
+    ) -> AppResult<(StorageObjectAttr, String), Errors> {
+        let upload = self.list_parts(bucket, name, upload_id).await?;
+        encryption::verify_key(upload.customer_encryption.as_ref(), encryption_key.as_ref())?;
+        if parts.is_empty() {
+            return Err(Errors::BadRequest {
+                message: "The request must list at least one part".into(),
+            });
+        }
+        if parts
+            .windows(2)
+            .any(|p| p[0].part_number >= p[1].part_number)
+        {
+            return Err(Errors::BadRequest {
+                message: "The list of parts was not in ascending order".into(),
+            });
+        }
+
+        let mut content = BytesMut::new();
+        let mut hasher = Md5::new();
+        for (i, completed) in parts.iter().enumerate() {
+            let part = upload
+                .parts
+                .get(&completed.part_number)
+                .filter(|p| p.etag == completed.etag.trim_matches('"'))
+                .ok_or_else(|| Errors::BadRequest {
+                    message: format!(
+                        "One or more of the specified parts could not be found: {}",
+                        completed.part_number
+                    ),
+                })?;
+            if i + 1 < parts.len() && part.size < MIN_PART_SIZE {
+                return Err(Errors::BadRequest {
+                    message: format!(
+                        "Your proposed upload is smaller than the minimum allowed size: part {}",
+                        part.part_number
+                    ),
+                });
+            }
+            let part_content = encryption::decrypt_content(
+                part.customer_encryption.as_ref(),
+                encryption_key.as_ref(),
+                &part.content,
+            )?;
+            content.extend_from_slice(&part_content);
+            hasher.update(Md5::digest(&part_content));
+        }
+
+        let attr = CreateObjectAttr {
+            encryption_key,
+            ..upload.attr
+        };
+        let object = self
+            .create_object(bucket, attr, content.freeze(), conditions)
+            .await?;
+        self.multipart_uploads.uploads.remove(upload_id);
+        // Like S3, the entity tag is the MD5 hash of the MD5 hashes of the parts followed by the number of parts.
+        let etag = format!("{}-{}", hex::encode(hasher.finalize()), parts.len());
+        Ok((object, etag))
+    }
+
+    async fn abort_multipart_upload(
+        &self,
+        bucket: &str,
+        name: &str,
+        upload_id: &str,
+    ) -> AppResult<(), Errors> {
+        self.multipart_uploads
+            .uploads
+            .remove_if(upload_id, |_, u| u.is_for(bucket, name))
+            .map(|_| ())
+            .ok_or_else(|| upload_not_found(upload_id))
+    }
+}
+
+fn upload_not_found(upload_id: &str) -> Errors {
+    Errors::UploadNotFound {
+        message: format!("The requested upload was not found: {upload_id}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use base64::{prelude::BASE64_STANDARD, Engine};
+    use bytes::Bytes;
+    use googletest::prelude::*;
+    use sha2::{Digest, Sha256};
+
+    use crate::{
+        libs::errors::Errors,
+        storage::{
+            encryption::EncryptionKey,
+            multipart::{CompletedPart, MultipartStorageExt, MIN_PART_SIZE},
+            BucketStorageExt, CreateBucketAttr, CreateObjectAttr, ObjectStorageExt,
+            OnMemoryStorageObject, Preconditions, Storage,
+        },
+    };
+
+    fn encryption_key(seed: u8) -> EncryptionKey {
+        let key = [seed; 32];
+        EncryptionKey::decode(
+            "AES256",
+            &BASE64_STANDARD.encode(key),
+            &BASE64_STANDARD.encode(Sha256::digest(key)),
+        )
+        .unwrap()
+    }
+
+    async fn storage_with_bucket() -> Storage {
+        let storage = Storage::default();
+        let attr = CreateBucketAttr {
+            project: "test-project".into(),
+            ..Default::default()
+        };
+        let _ = storage.create("test_bucket", attr).await;
+        storage
+    }
+
+    fn object_attr(name: &str) -> CreateObjectAttr {
+        CreateObjectAttr {
+            name: name.into(),
+            ..Default::default()
+        }
+    }
+
+    #[googletest::test]
+    #[tokio::test]
+    async fn complete_upload_concatenates_parts() {
+        // Arrange
+        let storage = storage_with_bucket().await;
+        let upload = storage
+            .initiate_multipart_upload("test_bucket", object_attr("a"))
+            .await
+            .unwrap();
+        let first = Bytes::from(vec![b'a'; MIN_PART_SIZE as usize]);
+        let mut completed = vec![];
+        for (part_number, content) in [(2, Bytes::from("b")), (1, first.clone())] {
+            let part = storage
+                .upload_part(
+                    "test_bucket",
+                    "a",
+                    &upload.upload_id,
+                    part_number,
+                    content,
+                    None,
+                )
+                .await
+                .unwrap();
+            completed.push(CompletedPart {
+                part_number,
+                etag: format!("\"{}\"", part.etag),
+            });
+        }
+        completed.reverse();
+
+        // Act
+        let (object, etag) = storage
+            .complete_multipart_upload(
+                "test_bucket",
+                "a",
+                &upload.upload_id,
+                completed,
+                Preconditions::default(),
+                None,
+            )
+            .await
+            .unwrap();
+
+        // Assert
+        let content = storage
+            .get_object("test_bucket", "a", None, Preconditions::default())
+            .await
+            .unwrap()
+            .content;
+        expect_that!(object.size, eq(MIN_PART_SIZE + 1));
+        expect_that!(content, eq(&[first, Bytes::from("b")].concat()));
+        expect_that!(etag, ends_with("-2"));
+        assert_that!(
+            storage
+                .list_parts("test_bucket", "a", &upload.upload_id)
+                .await,
+            err(matches_pattern!(Errors::UploadNotFound { .. }))
+        );
+    }
+
+    #[googletest::test]
+    #[tokio::test]
+    async fn reject_small_part_and_mismatched_etag() {
+        // Arrange
+        let storage = storage_with_bucket().await;
+        let upload = storage
+            .initiate_multipart_upload("test_bucket", object_attr("a"))
+            .await
+            .unwrap();
+        let mut etags = vec![];
+        for part_number in [1, 2] {
+            let part = storage
+                .upload_part(
+                    "test_bucket",
+                    "a",
+                    &upload.upload_id,
+                    part_number,
+                    "x".into(),
+                    None,
+                )
+                .await
+                .unwrap();
+            etags.push(part.etag);
+        }
+        let complete = |parts: Vec<(u32, &str)>| {
+            let parts = parts
+                .into_iter()
+                .map(|(part_number, etag)| CompletedPart {
+                    part_number,
+                    etag: etag.into(),
+                })
+                .collect();
+            storage.complete_multipart_upload(
+                "test_bucket",
+                "a",
+                &upload.upload_id,
+                parts,
+                Preconditions::default(),
+                None,
+            )
+        };
+
+        // Act
+        let too_small = complete(vec![(1, &etags[0]), (2, &etags[1])]).await;
+        let mismatched = complete(vec![(1, "unknown")]).await;
+
+        // Assert
+        expect_that!(too_small, err(matches_pattern!(Errors::BadRequest { .. })));
+        assert_that!(mismatched, err(matches_pattern!(Errors::BadRequest { .. })));
+    }
+
+    #[googletest::test]
+    #[tokio::test]
+    async fn encrypted_upload_requires_same_key() {
+        // Arrange
+        let storage = storage_with_bucket().await;
+        let attr = CreateObjectAttr {
+            encryption_key: Some(encryption_key(1)),
+            ..object_attr("a")
+        };
+        let upload = storage
+            .initiate_multipart_upload("test_bucket", attr)
+            .await
+            .unwrap();
+        let upload_part = |encryption_key: Option<EncryptionKey>| {
+            let storage = storage.clone();
+            let upload_id = upload.upload_id.clone();
+            async move {
+                storage
+                    .upload_part(
+                        "test_bucket",
+                        "a",
+                        &upload_id,
+                        1,
+                        "x".into(),
+                        encryption_key.as_ref(),
+                    )
+                    .await
+            }
+        };
+        let without_key = upload_part(None).await;
+        let wrong_key = upload_part(Some(encryption_key(2))).await;
+        let part = upload_part(Some(encryption_key(1))).await.unwrap();
+        let complete = |encryption_key: Option<EncryptionKey>| {
+            storage.complete_multipart_upload(
+                "test_bucket",
+                "a",
+                &upload.upload_id,
+                vec![CompletedPart {
+                    part_number: 1,
+                    etag: part.etag.clone(),
+                }],
+                Preconditions::default(),
+                encryption_key,
+            )
+        };
+
+        // Act
+        let completed_without_key = complete(None).await;
+        let completed = complete(Some(encryption_key(1))).await;
+
+        // Assert
+        let stored = storage
+            .get_object("test_bucket", "a", None, Preconditions::default())
+            .await
+            .unwrap();
+        expect_that!(upload.attr.encryption_key, none());
+        expect_that!(
+            without_key,
+            err(matches_pattern!(Errors::BadRequest { .. }))
+        );
+        expect_that!(wrong_key, err(matches_pattern!(Errors::BadRequest { .. })));
+        expect_that!(
+            completed_without_key,
+            err(matches_pattern!(Errors::BadRequest { .. }))
+        );
+        expect_that!(completed, ok(anything()));
+        assert_that!(
+            stored.decrypt(Some(&encryption_key(1))),
+            ok(field!(OnMemoryStorageObject.content, eq(&Bytes::from("x"))))
+        );
+    }
+
+    #[googletest::test]
+    #[tokio::test]
+    async fn encrypt_parts_at_rest() {
+        // Arrange
+        let storage = storage_with_bucket().await;
+        let attr = CreateObjectAttr {
+            encryption_key: Some(encryption_key(1)),
+            ..object_attr("a")
+        };
+        let upload = storage
+            .initiate_multipart_upload("test_bucket", attr)
+            .await
+            .unwrap();
+
+        // Act
+        let part = storage
+            .upload_part(
+                "test_bucket",
+                "a",
+                &upload.upload_id,
+                1,
+                "hello".into(),
+                Some(&encryption_key(1)),
+            )
+            .await
+            .unwrap();
+        let stored = storage
+            .list_parts("test_bucket", "a", &upload.upload_id)
+            .await
+            .unwrap()
+            .parts
+            .remove(&1)
+            .unwrap();
+
+        // Assert
+        expect_that!(part.size, eq(5));
+        expect_that!(
+            stored.customer_encryption,
+            some(eq(&encryption_key(1).customer_encryption()))
+        );
+        expect_that!(stored.content, not(eq(&Bytes::from("hello"))));
+        assert_that!(stored.content.windows(5).any(|w| w == b"hello"), eq(false));
+    }
+
+    #[googletest::test]
+    #[tokio::test]
+    async fn abort_upload_requires_same_object() {
+        // Arrange
+        let storage = storage_with_bucket().await;
+        let upload = storage
+            .initiate_multipart_upload("test_bucket", object_attr("a"))
+            .await
+            .unwrap();
+
+        // Act
+        let other = storage
+            .abort_multipart_upload("test_bucket", "b", &upload.upload_id)
+            .await;
+        let aborted = storage
+            .abort_multipart_upload("test_bucket", "a", &upload.upload_id)
+            .await;
+
+        // Assert
+        expect_that!(other, err(matches_pattern!(Errors::UploadNotFound { .. })));
+        assert_that!(aborted, ok(anything()));
+    }
+
+    #[googletest::test]
+    #[tokio::test]
+    async fn remove_uploads_with_deleted_bucket() {
+        // Arrange
+        let storage = storage_with_bucket().await;
+        let upload = storage
+            .initiate_multipart_upload("test_bucket", object_attr("a"))
+            .await
+            .unwrap();
+
+        // Act
+        let _ = storage.delete("test_bucket").await;
+        let _ = storage
+            .create(
+                "test_bucket",
+                CreateBucketAttr {
+                    project: "test-project".into(),
+                    ..Default::default()
+                },
+            )
+            .await;
+        let res = storage
+            .list_parts("test_bucket", "a", &upload.upload_id)
+            .await;
+
+        // Assert
+        assert_that!(res, err(matches_pattern!(Errors::UploadNotFound { .. })));
+    }
+}
@@ -0,0 +1,466 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use tokio::sync::broadcast;
+
+use crate::libs::errors::{AppResult, Errors};
+
+use super::{OnMemoryStorageBucket, Storage, StorageObjectAttr};
+
+/// A notification configuration of a bucket, which publishes object changes to a Pub/Sub topic.
+/// https://cloud.google.com/storage/docs/pubsub-notifications
+#[derive(Debug, Clone, PartialEq)]
+pub struct NotificationConfig {
+    pub id: String,
+    /// The full resource name, e.g. `//pubsub.googleapis.com/projects/my-project/topics/my-topic`.
+    pub topic: String,
+    /// Every event type is published when empty.
+    pub event_types: Vec<EventType>,
+    pub custom_attributes: HashMap<String, String>,
+    pub object_name_prefix: Option<String>,
+    pub payload_format: PayloadFormat,
+}
+
+impl NotificationConfig {
+    fn matches(&self, event_type: EventType, object: &StorageObjectAttr) -> bool {
+        (self.event_types.is_empty() || self.event_types.contains(&event_type))
+            && self
+                .object_name_prefix
+                .as_ref()
+                .is_none_or(|p| object.name.starts_with(p))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EventType {
+    /// A new object, or a new generation of an existing object, is created.
+    Finalize,
+    /// The metadata of an existing object changes.
+    MetadataUpdate,
+    /// An object is permanently deleted, including being overwritten in an unversioned bucket.
+    Delete,
+    /// The live version of an object becomes noncurrent in a versioned bucket.
+    Archive,
+}
+
+impl EventType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EventType::Finalize => "OBJECT_FINALIZE",
+            EventType::MetadataUpdate => "OBJECT_METADATA_UPDATE",
+            EventType::Delete => "OBJECT_DELETE",
+            EventType::Archive => "OBJECT_ARCHIVE",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum PayloadFormat {
+    #[default]
+    JsonApiV1,
+    None,
+}
+
+impl PayloadFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PayloadFormat::JsonApiV1 => "JSON_API_V1",
+            PayloadFormat::None => "NONE",
+        }
+    }
+}
+
+/// Fields given on `insert` notification configuration.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CreateNotificationConfigAttr {
+    pub topic: String,
+    pub event_types: Vec<EventType>,
+    pub custom_attributes: HashMap<String, String>,
+    pub object_name_prefix: Option<String>,
+    pub payload_format: PayloadFormat,
+}
+
+/// A message published to the topic of a notification configuration.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Notification {
+    pub topic: String,
+    pub message_id: String,
+    pub publish_time: DateTime<Utc>,
+    pub attributes: HashMap<String, String>,
+    /// The object sent as the message data, unless the payload format is `NONE`.
+    pub payload: Option<StorageObjectAttr>,
+}
+
+/// Messages published so far, which are kept in memory and broadcast to delivery workers.
+#[derive(Debug, Clone)]
+pub struct Notifications {
+    published: Arc<Mutex<VecDeque<Notification>>>,
+    sender: broadcast::Sender<Notification>,
+    next_message_id: Arc<AtomicU64>,
+}
+
+/// The number of messages kept in memory, beyond which the oldest ones are dropped.
+const MAX_PUBLISHED_NOTIFICATIONS: usize = 1000;
+
+impl Default for Notifications {
+    fn default() -> Self {
+        Notifications {
+            published: Arc::new(Mutex::new(VecDeque::new())),
+            sender: broadcast::channel(MAX_PUBLISHED_NOTIFICATIONS).0,
+            next_message_id: Arc::new(AtomicU64::new(1)),
+        }
+    }
+}
+
+impl Notifications {
+    /// Receives messages published from now on.
+    pub fn subscribe(&self) -> broadcast::Receiver<Notification> {
+        self.sender.subscribe()
+    }
+
+    /// Publishes a message for every notification configuration of the bucket matching the event.
+    pub(super) fn publish(
+        &self,
+        bucket: &OnMemoryStorageBucket,
+        event_type: EventType,
+        object: &StorageObjectAttr,
+        overwrite_generation: Option<u64>,
+        now: DateTime<Utc>,
+    ) {
+        for config in bucket
+            .notification_configs
+            .iter()
+            .filter(|c| c.matches(event_type, object))
+        {
+            let mut attributes = config.custom_attributes.clone();
+            attributes.extend(
+                [
+                    (
+                        "notificationConfig",
+                        format!(
+                            "projects/_/buckets/{}/notificationConfigs/{}",
+                            bucket.attr.name, config.id
+                        ),
+                    ),
+                    ("eventType", event_type.as_str().to_string()),
+                    ("payloadFormat", config.payload_format.as_str().to_string()),
+                    ("bucketId", bucket.attr.name.clone()),
+                    ("objectId", object.name.clone()),
+                    ("objectGeneration", object.generation.to_string()),
+                    (
+                        "eventTime",
+                        now.to_rfc3339_opts(SecondsFormat::Micros, true),
+                    ),
+                ]
+                .map(|(k, v)| (k.to_string(), v)),
+            );
+            if let Some(generation) = overwrite_generation {
+                let key = match event_type {
+                    EventType::Finalize => "overwroteGeneration",
+                    _ => "overwrittenByGeneration",
+                };
+                attributes.insert(key.to_string(), generation.to_string());
+            }
+            let notification = Notification {
+                topic: config.topic.clone(),
+                message_id: self
+                    .next_message_id
+                    .fetch_add(1, Ordering::Relaxed)
+                    .to_string(),
+                publish_time: now,
+                attributes,
+                payload: (config.payload_format == PayloadFormat::JsonApiV1)
+                    .then(|| object.clone()),
+            };
+
+            let mut published = self.published.lock().unwrap();
+            if published.len() >= MAX_PUBLISHED_NOTIFICATIONS {
+                published.pop_front();
+            }
+            published.push_back(notification.clone());
+            // Nobody may be listening, which is fine since the message is kept in memory anyway.
+            let _ = self.sender.send(notification);
+        }
+    }
+}
+
+/// Topics are given either as `projects/{project}/topics/{topic}` or with the `//pubsub.googleapis.com/` prefix.
+pub fn normalize_topic(topic: &str) -> AppResult<String, Errors> {
+    let path = topic
+        .strip_prefix("//pubsub.googleapis.com/")
+        .unwrap_or(topic);
+    match path.split('/').collect::<Vec<_>>()[..] {
+        ["projects", project, "topics", name] if !project.is_empty() && !name.is_empty() => {
+            Ok(format!("//pubsub.googleapis.com/{path}"))
+        }
+        _ => Err(Errors::BadRequest {
+            message: format!("Invalid topic name: {topic}"),
+        }),
+    }
+}
+
+/// Aggregates operations for notification configurations.
+pub trait NotificationStorageExt {
+    /// Corresponds to `list` operation: https://cloud.google.com/storage/docs/json_api/v1/notifications/list
+    async fn list_notification_configs(
+        &self,
+        bucket: &str,
+    ) -> AppResult<Vec<NotificationConfig>, Errors>;
+
+    /// Corresponds to `get` operation: https://cloud.google.com/storage/docs/json_api/v1/notifications/get
+    async fn get_notification_config(
+        &self,
+        bucket: &str,
+        id: &str,
+    ) -> AppResult<NotificationConfig, Errors>;
+
+    /// Corresponds to `insert` operation: https://cloud.google.com/storage/docs/json_api/v1/notifications/insert
+    async fn create_notification_config(
+        &self,
+        bucket: &str,
+        attr: CreateNotificationConfigAttr,
+    ) -> AppResult<NotificationConfig, Errors>;
+
+    /// Corresponds to `delete` operation: https://cloud.google.com/storage/docs/json_api/v1/notifications/delete
+    async fn delete_notification_config(&self, bucket: &str, id: &str) -> AppResult<(), Errors>;
+
+    /// Returns messages published so far, from the oldest one.
+    async fn published_notifications(&self) -> Vec<Notification>;
+
+    /// Forgets messages published so far.
+    async fn clear_notifications(&self);
+}
+
+impl NotificationStorageExt for Storage {
+    async fn list_notification_configs(
+        &self,
+        bucket: &str,
+    ) -> AppResult<Vec<NotificationConfig>, Errors> {
+        let bucket = self.bucket(bucket)?;
+        let configs = bucket.lock().unwrap().notification_configs.clone();
+        Ok(configs)
+    }
+
+    async fn get_notification_config(
+        &self,
+        bucket: &str,
+        id: &str,
+    ) -> AppResult<NotificationConfig, Errors> {
+        let bucket = self.bucket(bucket)?;
+        let bucket = bucket.lock().unwrap();
+        bucket
+            .notification_configs
+            .iter()
+            .find(|c| c.id == id)
+            .cloned()
+            .ok_or_else(|| not_found(&bucket, id))
+    }
+
+    async fn create_notification_config(
+        &self,
+        bucket: &str,
+        attr: CreateNotificationConfigAttr,
+    ) -> AppResult<NotificationConfig, Errors> {
+        let topic = normalize_topic(&attr.topic)?;
+        let bucket = self.bucket(bucket)?;
+        let mut bucket = bucket.lock().unwrap();
+        let id = bucket
+            .notification_configs
+            .iter()
+            .filter_map(|c| c.id.parse::<u64>().ok())
+            .max()
+            .unwrap_or_default()
+            + 1;
+        let config = NotificationConfig {
+            id: id.to_string(),
+            topic,
+            event_types: attr.event_types,
+            custom_attributes: attr.custom_attributes,
+            object_name_prefix: attr.object_name_prefix.filter(|p| !p.is_empty()),
+            payload_format: attr.payload_format,
+        };
+        bucket.notification_configs.push(config.clone());
+        Ok(config)
+    }
+
+    async fn delete_notification_config(&self, bucket: &str, id: &str) -> AppResult<(), Errors> {
+        let bucket = self.bucket(bucket)?;
+        let mut bucket = bucket.lock().unwrap();
+        let before = bucket.notification_configs.len();
+        bucket.notification_configs.retain(|c| c.id != id);
+        if bucket.notification_configs.len() == before {
+            return Err(not_found(&bucket, id));
+        }
+        Ok(())
+    }
+
+    async fn published_notifications(&self) -> Vec<Notification> {
+        let published = self.notifications.published.lock().unwrap();
+        published.iter().cloned().collect()
+    }
+
+    async fn clear_notifications(&self) {
+        self.notifications.published.lock().unwrap().clear();
+    }
+}
+
+fn not_found(bucket: &OnMemoryStorageBucket, id: &str) -> Errors {
+    Errors::NotificationConfigNotFound {
+        message: format!(
+            "No such notification configuration: {}/{id}",
+            bucket.attr.name
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+    use googletest::prelude::*;
+
+    use crate::{
+        libs::errors::Errors,
+        storage::{
+            notification::{
+                CreateNotificationConfigAttr, EventType, Notification, NotificationStorageExt,
+            },
+            BucketStorageExt, CreateBucketAttr, CreateObjectAttr, ObjectStorageExt, Preconditions,
+            Storage,
+        },
+    };
+
+    async fn storage_with_bucket(versioning: bool) -> Storage {
+        let storage = Storage::default();
+        let attr = CreateBucketAttr {
+            project: "test-project".into(),
+            versioning,
+            ..Default::default()
+        };
+        let _ = storage.create("test_bucket", attr).await;
+        storage
+    }
+
+    async fn upload(storage: &Storage, name: &str) -> u64 {
+        storage
+            .create_object(
+                "test_bucket",
+                CreateObjectAttr {
+                    name: name.into(),
+                    ..Default::default()
+                },
+                Bytes::new(),
+                Preconditions::default(),
+            )
+            .await
+            .unwrap()
+            .generation
+    }
+
+    fn event_type(notification: &Notification) -> &str {
+        &notification.attributes["eventType"]
+    }
+
+    #[googletest::test]
+    #[tokio::test]
+    async fn publish_archive_and_finalize_when_overwriting_in_versioned_bucket() {
+        // Arrange
+        let storage = storage_with_bucket(true).await;
+        let _ = storage
+            .create_notification_config(
+                "test_bucket",
+                CreateNotificationConfigAttr {
+                    topic: "projects/test-project/topics/changes".into(),
+                    ..Default::default()
+                },
+            )
+            .await;
+        let first = upload(&storage, "a").await;
+
+        // Act
+        let second = upload(&storage, "a").await;
+        let published = storage.published_notifications().await;
+
+        // Assert
+        let events = published.iter().map(event_type).collect::<Vec<_>>();
+        assert_that!(
+            events,
+            elements_are![
+                eq(&"OBJECT_FINALIZE"),
+                eq(&"OBJECT_ARCHIVE"),
+                eq(&"OBJECT_FINALIZE")
+            ]
+        );
+        expect_that!(
+            published[1].attributes.get("overwrittenByGeneration"),
+            some(eq(&second.to_string()))
+        );
+        expect_that!(
+            published[2].attributes.get("overwroteGeneration"),
+            some(eq(&first.to_string()))
+        );
+        assert_that!(
+            published[2].topic,
+            eq("//pubsub.googleapis.com/projects/test-project/topics/changes")
+        );
+    }
+
+    #[googletest::test]
+    #[tokio::test]
+    async fn publish_only_matching_event_types_and_prefix() {
+        // Arrange
+        let storage = storage_with_bucket(false).await;
+        let _ = storage
+            .create_notification_config(
+                "test_bucket",
+                CreateNotificationConfigAttr {
+                    topic: "projects/test-project/topics/deletes".into(),
+                    event_types: vec![EventType::Delete],
+                    object_name_prefix: Some("logs/".into()),
+                    ..Default::default()
+                },
+            )
+            .await;
+        upload(&storage, "logs/a").await;
+        upload(&storage, "b").await;
+
+        // Act
+        for name in ["logs/a", "b"] {
+            let _ = storage
+                .delete_object("test_bucket", name, None, Preconditions::default())
+                .await;
+        }
+        let published = storage.published_notifications().await;
+
+        // Assert
+        assert_that!(published.len(), eq(1));
+        expect_that!(event_type(&published[0]), eq("OBJECT_DELETE"));
+        assert_that!(published[0].attributes["objectId"], eq("logs/a"));
+    }
+
+    #[googletest::test]
+    #[tokio::test]
+    async fn reject_invalid_topic_name() {
+        // Arrange
+        let storage = storage_with_bucket(false).await;
+
+        // Act
+        let res = storage
+            .create_notification_config(
+                "test_bucket",
+                CreateNotificationConfigAttr {
+                    topic: "my-topic".into(),
+                    ..Default::default()
+                },
+            )
+            .await;
+
+        // Assert
+        assert_that!(res, err(matches_pattern!(Errors::BadRequest { .. })));
+    }
+}
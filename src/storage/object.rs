@@ -0,0 +1,955 @@
+use std::collections::{BTreeSet, HashMap};
+
+use base64::{prelude::BASE64_STANDARD, Engine};
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use md5::{Digest, Md5};
+
+use crate::libs::errors::{AppResult, Errors};
+
+use super::{
+    acl::{self, AclSpec},
+    encryption::EncryptionKey,
+    notification::EventType,
+    retention::{self, check_object_retention, check_retention, ObjectRetention},
+    soft_delete::{discard_object, is_restorable},
+    ObjectGeneration, ObjectName, OnMemoryStorageBucket, OnMemoryStorageObject, Storage,
+    StorageObjectAttr,
+};
+
+/// Attributes of a new object given on `insert`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CreateObjectAttr {
+    pub name: String,
+    pub content_type: Option<String>,
+    pub content_encoding: Option<String>,
+    pub content_disposition: Option<String>,
+    pub content_language: Option<String>,
+    pub cache_control: Option<String>,
+    pub metadata: HashMap<String, String>,
+    /// Hashes computed by the client, which are verified against the uploaded content.
+    pub md5_hash: Option<String>,
+    pub crc32c: Option<String>,
+    /// The default object ACL of the bucket is applied when `None`.
+    pub acl: Option<AclSpec>,
+    /// The default event-based hold of the bucket is applied when `None`.
+    pub event_based_hold: Option<bool>,
+    pub temporary_hold: bool,
+    pub retention: Option<ObjectRetention>,
+    /// Encrypts the content at rest when given.
+    pub encryption_key: Option<EncryptionKey>,
+}
+
+/// Fields to overwrite on an existing object.
+/// `None` leaves the current value untouched and `Some(None)` clears it.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct UpdateObjectAttr {
+    pub content_type: Option<Option<String>>,
+    pub content_encoding: Option<Option<String>>,
+    pub content_disposition: Option<Option<String>>,
+    pub content_language: Option<Option<String>>,
+    pub cache_control: Option<Option<String>>,
+    pub metadata: Option<MetadataUpdate>,
+    pub acl: Option<AclSpec>,
+    pub event_based_hold: Option<bool>,
+    pub temporary_hold: Option<bool>,
+    pub retention: Option<Option<ObjectRetention>>,
+    /// Allows reducing or removing an unlocked retention.
+    pub override_unlocked_retention: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MetadataUpdate {
+    /// Replaces every custom metadata entry, as `update` does.
+    Replace(HashMap<String, String>),
+    /// Upserts the given entries and removes the ones set to `None`, as `patch` does.
+    Merge(HashMap<String, Option<String>>),
+}
+
+impl MetadataUpdate {
+    pub(super) fn apply(self, entries: &mut HashMap<String, String>) {
+        match self {
+            MetadataUpdate::Replace(replacement) => *entries = replacement,
+            MetadataUpdate::Merge(update) => {
+                for (key, value) in update {
+                    match value {
+                        Some(value) => entries.insert(key, value),
+                        None => entries.remove(&key),
+                    };
+                }
+            }
+        }
+    }
+}
+
+/// Conditions given by `ifGenerationMatch` and friends.
+/// A generation of `0` stands for an object that doesn't exist.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Preconditions {
+    pub if_generation_match: Option<u64>,
+    pub if_generation_not_match: Option<u64>,
+    pub if_metageneration_match: Option<u64>,
+    pub if_metageneration_not_match: Option<u64>,
+}
+
+impl Preconditions {
+    pub(super) fn check(&self, current: Option<&StorageObjectAttr>) -> AppResult<(), Errors> {
+        let generation = current.map_or(0, |o| o.generation);
+        let metageneration = current.map(|o| o.metageneration);
+        let failed = self.if_generation_match.is_some_and(|g| g != generation)
+            || self
+                .if_generation_not_match
+                .is_some_and(|g| g == generation)
+            || self
+                .if_metageneration_match
+                .is_some_and(|m| metageneration != Some(m))
+            || self
+                .if_metageneration_not_match
+                .is_some_and(|m| metageneration == Some(m));
+        if failed {
+            return Err(Errors::PreconditionFailed {
+                message: "At least one of the pre-conditions you specified did not hold.".into(),
+            });
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ListObjectsAttr {
+    pub prefix: Option<String>,
+    pub delimiter: Option<String>,
+    /// Includes noncurrent versions when `true`.
+    pub versions: bool,
+    pub start_offset: Option<String>,
+    pub end_offset: Option<String>,
+    pub include_trailing_delimiter: bool,
+    /// Lists only soft-deleted objects when `true`.
+    pub soft_deleted: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ObjectList {
+    pub items: Vec<StorageObjectAttr>,
+    /// Common prefixes rolled up by the delimiter.
+    pub prefixes: Vec<String>,
+}
+
+/// Aggregates operations for an object.
+pub trait ObjectStorageExt {
+    /// Corresponds to `list` operation: https://cloud.google.com/storage/docs/json_api/v1/objects/list
+    async fn list_objects(
+        &self,
+        bucket: &str,
+        attr: ListObjectsAttr,
+    ) -> AppResult<ObjectList, Errors>;
+
+    /// Corresponds to `get` operation: https://cloud.google.com/storage/docs/json_api/v1/objects/get
+    /// The live version is returned unless `generation` is given.
+    async fn get_object(
+        &self,
+        bucket: &str,
+        name: &str,
+        generation: Option<u64>,
+        conditions: Preconditions,
+    ) -> AppResult<OnMemoryStorageObject, Errors>;
+
+    /// Corresponds to `insert` operation: https://cloud.google.com/storage/docs/json_api/v1/objects/insert
+    /// The live version is overwritten, or kept as noncurrent if the bucket enables versioning.
+    async fn create_object(
+        &self,
+        bucket: &str,
+        attr: CreateObjectAttr,
+        content: Bytes,
+        conditions: Preconditions,
+    ) -> AppResult<StorageObjectAttr, Errors>;
+
+    /// Corresponds to `patch` and `update` operation.
+    /// Patch: https://cloud.google.com/storage/docs/json_api/v1/objects/patch
+    /// Update: https://cloud.google.com/storage/docs/json_api/v1/objects/update
+    async fn update_object(
+        &self,
+        bucket: &str,
+        name: &str,
+        generation: Option<u64>,
+        attr: UpdateObjectAttr,
+        conditions: Preconditions,
+    ) -> AppResult<StorageObjectAttr, Errors>;
+
+    /// Corresponds to `delete` operation: https://cloud.google.com/storage/docs/json_api/v1/objects/delete
+    /// Deleting the live version of an object in a versioned bucket makes it noncurrent instead.
+    async fn delete_object(
+        &self,
+        bucket: &str,
+        name: &str,
+        generation: Option<u64>,
+        conditions: Preconditions,
+    ) -> AppResult<StorageObjectAttr, Errors>;
+}
+
+impl ObjectStorageExt for Storage {
+    async fn list_objects(
+        &self,
+        bucket: &str,
+        attr: ListObjectsAttr,
+    ) -> AppResult<ObjectList, Errors> {
+        let bucket = self.bucket(bucket)?;
+        let bucket = bucket.lock().unwrap();
+
+        let now = self.clock.now();
+        let prefix = attr.prefix.unwrap_or_default();
+        let mut objects = bucket
+            .objects
+            .iter()
+            .map(|o| o.value().attr.clone())
+            .filter(|o| match attr.soft_deleted {
+                true => o.is_soft_deleted() && is_restorable(o.hard_delete_time, now),
+                false => !o.is_soft_deleted() && (attr.versions || o.is_live()),
+            })
+            .filter(|o| o.name.starts_with(&prefix))
+            .filter(|o| attr.start_offset.as_ref().is_none_or(|s| &o.name >= s))
+            .filter(|o| attr.end_offset.as_ref().is_none_or(|e| &o.name < e))
+            .collect::<Vec<_>>();
+        objects.sort_by(|a, b| (&a.name, a.generation).cmp(&(&b.name, b.generation)));
+
+        let delimiter = attr.delimiter.filter(|d| !d.is_empty());
+        let mut items = Vec::new();
+        let mut prefixes = BTreeSet::new();
+        for object in objects {
+            if let Some(delimiter) = &delimiter {
+                let rest = &object.name[prefix.len()..];
+                if let Some(idx) = rest.find(delimiter.as_str()) {
+                    let common_prefix = format!("{prefix}{}", &rest[..idx + delimiter.len()]);
+                    let is_trailing =
+                        attr.include_trailing_delimiter && common_prefix == object.name;
+                    prefixes.insert(common_prefix);
+                    if !is_trailing {
+                        continue;
+                    }
+                }
+            }
+            items.push(object);
+        }
+
+        Ok(ObjectList {
+            items,
+            prefixes: prefixes.into_iter().collect(),
+        })
+    }
+
+    async fn get_object(
+        &self,
+        bucket: &str,
+        name: &str,
+        generation: Option<u64>,
+        conditions: Preconditions,
+    ) -> AppResult<OnMemoryStorageObject, Errors> {
+        let bucket = self.bucket(bucket)?;
+        let bucket = bucket.lock().unwrap();
+        let object = find_object(&bucket, name, generation)?;
+        conditions.check(Some(&object.attr))?;
+        Ok(object)
+    }
+
+    async fn create_object(
+        &self,
+        bucket: &str,
+        attr: CreateObjectAttr,
+        content: Bytes,
+        conditions: Preconditions,
+    ) -> AppResult<StorageObjectAttr, Errors> {
+        if attr.name.is_empty() {
+            return Err(Errors::BadRequest {
+                message: "Object name is required".into(),
+            });
+        }
+
+        let bucket = self.bucket(bucket)?;
+        let bucket = bucket.lock().unwrap();
+        let current = find_object(&bucket, &attr.name, None).ok();
+        conditions.check(current.as_ref().map(|o| &o.attr))?;
+
+        let md5_hash = BASE64_STANDARD.encode(Md5::digest(&content));
+        let crc32c = BASE64_STANDARD.encode(crc32c::crc32c(&content).to_be_bytes());
+        if attr.md5_hash.as_ref().is_some_and(|h| h != &md5_hash) {
+            return Err(Errors::BadRequest {
+                message: "Provided MD5 hash doesn't match calculated MD5 hash".into(),
+            });
+        }
+        if attr.crc32c.as_ref().is_some_and(|c| c != &crc32c) {
+            return Err(Errors::BadRequest {
+                message: "Provided CRC32C doesn't match calculated CRC32C".into(),
+            });
+        }
+
+        let acl = match attr.acl {
+            Some(spec) => {
+                let acl = spec.resolve(&bucket.attr.project);
+                bucket.attr.iam_configuration.check_acl(&acl)?;
+                acl
+            }
+            None => bucket.attr.default_object_acl.clone(),
+        };
+
+        check_object_retention(&bucket, attr.retention.as_ref())?;
+
+        let now = self.clock.now();
+        let replaced = current
+            .map(|current| replace_live_object(&bucket, &current.attr, now))
+            .transpose()?;
+
+        // Hashes and the size describe the content as it's uploaded rather than as it's stored.
+        let size = content.len() as u64;
+        let (content, customer_encryption) = match attr.encryption_key {
+            Some(key) => (key.encrypt(&content), Some(key.customer_encryption())),
+            None => (content, None),
+        };
+        let event_based_hold = attr
+            .event_based_hold
+            .unwrap_or(bucket.attr.default_event_based_hold);
+        let generation = next_generation(&bucket, &attr.name, now);
+        let object = StorageObjectAttr {
+            name: attr.name.clone(),
+            bucket_name: bucket.attr.name.clone(),
+            size,
+            md5_hash,
+            crc32c,
+            etag: etag(generation, 1),
+            acl,
+            owner: acl::project_owner(&bucket.attr.project),
+            content_type: attr
+                .content_type
+                .unwrap_or_else(|| DEFAULT_CONTENT_TYPE.to_string()),
+            content_encoding: attr.content_encoding,
+            content_disposition: attr.content_disposition,
+            content_language: attr.content_language,
+            cache_control: attr.cache_control,
+            customer_encryption,
+            time_created: now,
+            updated: now,
+            time_deleted: None,
+            soft_delete_time: None,
+            hard_delete_time: None,
+            event_based_hold,
+            temporary_hold: attr.temporary_hold,
+            // The retention of an object under an event-based hold starts once it's released.
+            retention_expiration_time: if event_based_hold {
+                None
+            } else {
+                retention::retention_expiration(bucket.attr.retention_policy.as_ref(), now)
+            },
+            retention: attr.retention,
+            storage_class: DEFAULT_STORAGE_CLASS.to_string(),
+            generation,
+            metageneration: 1,
+            metadata: attr.metadata,
+        };
+        bucket.objects.insert(
+            (ObjectName(attr.name), ObjectGeneration(generation)),
+            OnMemoryStorageObject {
+                attr: object.clone(),
+                content,
+            },
+        );
+        publish_finalize(self, &bucket, &object, replaced, now);
+        Ok(object)
+    }
+
+    async fn update_object(
+        &self,
+        bucket: &str,
+        name: &str,
+        generation: Option<u64>,
+        attr: UpdateObjectAttr,
+        conditions: Preconditions,
+    ) -> AppResult<StorageObjectAttr, Errors> {
+        let bucket = self.bucket(bucket)?;
+        let bucket = bucket.lock().unwrap();
+        let current = find_object(&bucket, name, generation)?;
+        conditions.check(Some(&current.attr))?;
+
+        let mut object = current.attr;
+        if let Some(content_type) = attr.content_type {
+            object.content_type = content_type.unwrap_or_else(|| DEFAULT_CONTENT_TYPE.to_string());
+        }
+        if let Some(content_encoding) = attr.content_encoding {
+            object.content_encoding = content_encoding;
+        }
+        if let Some(content_disposition) = attr.content_disposition {
+            object.content_disposition = content_disposition;
+        }
+        if let Some(content_language) = attr.content_language {
+            object.content_language = content_language;
+        }
+        if let Some(cache_control) = attr.cache_control {
+            object.cache_control = cache_control;
+        }
+        if let Some(metadata) = attr.metadata {
+            metadata.apply(&mut object.metadata);
+        }
+        if let Some(acl) = attr.acl {
+            let acl = acl.resolve(&bucket.attr.project);
+            bucket.attr.iam_configuration.check_acl(&acl)?;
+            object.acl = acl;
+        }
+        let now = self.clock.now();
+        if let Some(event_based_hold) = attr.event_based_hold {
+            if object.event_based_hold && !event_based_hold {
+                // The retention period of the bucket starts when the event-based hold is released.
+                object.retention_expiration_time =
+                    retention::retention_expiration(bucket.attr.retention_policy.as_ref(), now);
+            }
+            object.event_based_hold = event_based_hold;
+        }
+        if let Some(temporary_hold) = attr.temporary_hold {
+            object.temporary_hold = temporary_hold;
+        }
+        if let Some(retention) = attr.retention {
+            check_object_retention(&bucket, retention.as_ref())?;
+            object.retention = ObjectRetention::replace(
+                object.retention.as_ref(),
+                retention,
+                attr.override_unlocked_retention,
+            )?;
+        }
+        object.metageneration += 1;
+        object.etag = etag(object.generation, object.metageneration);
+        object.updated = now;
+
+        let key = (
+            ObjectName(object.name.clone()),
+            ObjectGeneration(object.generation),
+        );
+        if let Some(mut stored) = bucket.objects.get_mut(&key) {
+            stored.attr = object.clone();
+        }
+        self.publish_object_event(&bucket, EventType::MetadataUpdate, &object, None, now);
+        Ok(object)
+    }
+
+    async fn delete_object(
+        &self,
+        bucket: &str,
+        name: &str,
+        generation: Option<u64>,
+        conditions: Preconditions,
+    ) -> AppResult<StorageObjectAttr, Errors> {
+        let bucket = self.bucket(bucket)?;
+        let bucket = bucket.lock().unwrap();
+        let current = find_object(&bucket, name, generation)?;
+        conditions.check(Some(&current.attr))?;
+        check_hold(&bucket, &current.attr)?;
+        let now = self.clock.now();
+        check_retention(&bucket, &current.attr, now)?;
+
+        let key = (
+            ObjectName(current.attr.name.clone()),
+            ObjectGeneration(current.attr.generation),
+        );
+        let event_type = if generation.is_none() && bucket.attr.versioning {
+            if let Some(mut noncurrent) = bucket.objects.get_mut(&key) {
+                noncurrent.attr.time_deleted = Some(now);
+            }
+            EventType::Archive
+        } else {
+            discard_object(&bucket, &key, now);
+            EventType::Delete
+        };
+        let deleted = bucket
+            .objects
+            .get(&key)
+            .map_or(current.attr, |o| o.attr.clone());
+        self.publish_object_event(&bucket, event_type, &deleted, None, now);
+        Ok(deleted)
+    }
+}
+
+const DEFAULT_CONTENT_TYPE: &str = "application/octet-stream";
+const DEFAULT_STORAGE_CLASS: &str = "STANDARD";
+
+/// Looks up the given generation of an object, or its live version if `generation` is `None`.
+/// Soft-deleted objects are never returned.
+pub(super) fn find_object(
+    bucket: &OnMemoryStorageBucket,
+    name: &str,
+    generation: Option<u64>,
+) -> AppResult<OnMemoryStorageObject, Errors> {
+    let object = match generation {
+        Some(generation) => bucket
+            .objects
+            .get(&(ObjectName(name.to_string()), ObjectGeneration(generation)))
+            .map(|o| o.value().clone())
+            .filter(|o| !o.attr.is_soft_deleted()),
+        None => bucket
+            .objects
+            .iter()
+            .find(|o| o.key().0 .0 == name && o.attr.is_live())
+            .map(|o| o.value().clone()),
+    };
+    object.ok_or_else(|| Errors::ObjectNotFound {
+        message: format!("No such object: {}/{name}", bucket.attr.name),
+    })
+}
+
+/// Rejects deleting or overwriting an object under an event-based or temporary hold.
+pub(super) fn check_hold(
+    bucket: &OnMemoryStorageBucket,
+    object: &StorageObjectAttr,
+) -> AppResult<(), Errors> {
+    let hold = match (object.event_based_hold, object.temporary_hold) {
+        (true, _) => "Event-Based",
+        (_, true) => "Temporary",
+        _ => return Ok(()),
+    };
+    Err(Errors::Forbidden {
+        message: format!(
+            "Object '{}/{}' is under active {hold} hold and cannot be deleted, overwritten or archived until hold is removed.",
+            bucket.attr.name, object.name
+        ),
+    })
+}
+
+/// Makes room for a new live version of an object, which `insert` and `restore` create.
+/// The current live version becomes noncurrent in a versioned bucket, and is discarded otherwise.
+/// Returns the replaced version along with the event it went through.
+pub(super) fn replace_live_object(
+    bucket: &OnMemoryStorageBucket,
+    current: &StorageObjectAttr,
+    now: DateTime<Utc>,
+) -> AppResult<(EventType, StorageObjectAttr), Errors> {
+    check_hold(bucket, current)?;
+    check_retention(bucket, current, now)?;
+    let key = (
+        ObjectName(current.name.clone()),
+        ObjectGeneration(current.generation),
+    );
+    let event_type = if bucket.attr.versioning {
+        if let Some(mut noncurrent) = bucket.objects.get_mut(&key) {
+            noncurrent.attr.time_deleted = Some(now);
+        }
+        EventType::Archive
+    } else {
+        discard_object(bucket, &key, now);
+        EventType::Delete
+    };
+    let replaced = bucket
+        .objects
+        .get(&key)
+        .map_or_else(|| current.clone(), |o| o.attr.clone());
+    Ok((event_type, replaced))
+}
+
+/// Publishes `OBJECT_FINALIZE` of a new live version, following the event of the version it replaced.
+pub(super) fn publish_finalize(
+    storage: &Storage,
+    bucket: &OnMemoryStorageBucket,
+    object: &StorageObjectAttr,
+    replaced: Option<(EventType, StorageObjectAttr)>,
+    now: DateTime<Utc>,
+) {
+    let overwrote = replaced.map(|(event_type, replaced)| {
+        storage.publish_object_event(bucket, event_type, &replaced, Some(object.generation), now);
+        replaced.generation
+    });
+    storage.publish_object_event(bucket, EventType::Finalize, object, overwrote, now);
+}
+
+/// Generations are timestamps in microseconds like GCS, but kept increasing per object name.
+pub(super) fn next_generation(
+    bucket: &OnMemoryStorageBucket,
+    name: &str,
+    now: DateTime<Utc>,
+) -> u64 {
+    let latest = bucket
+        .objects
+        .iter()
+        .filter(|o| o.key().0 .0 == name)
+        .map(|o| o.key().1 .0)
+        .max()
+        .unwrap_or_default();
+    (now.timestamp_micros() as u64).max(latest + 1)
+}
+
+pub(super) fn etag(generation: u64, metageneration: u64) -> String {
+    BASE64_STANDARD.encode(format!("{generation}/{metageneration}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use bytes::Bytes;
+    use googletest::{assert_pred, prelude::*};
+
+    use crate::{
+        libs::errors::Errors,
+        storage::{
+            BucketStorageExt, CreateBucketAttr, CreateObjectAttr, ListObjectsAttr, MetadataUpdate,
+            ObjectStorageExt, OnMemoryStorageObject, Preconditions, Storage, StorageObjectAttr,
+            UpdateObjectAttr,
+        },
+    };
+
+    async fn storage_with_bucket(versioning: bool) -> Storage {
+        let storage = Storage::default();
+        let attr = CreateBucketAttr {
+            project: "test-project".into(),
+            versioning,
+            default_event_based_hold: false,
+            location: "US-EAST1".into(),
+            ..Default::default()
+        };
+        let _ = storage.create("test_bucket", attr).await;
+        storage
+    }
+
+    fn object_attr(name: &str) -> CreateObjectAttr {
+        CreateObjectAttr {
+            name: name.into(),
+            ..Default::default()
+        }
+    }
+
+    #[googletest::test]
+    #[tokio::test]
+    async fn return_created_object_with_its_content() {
+        // Arrange
+        let storage = storage_with_bucket(false).await;
+        let attr = CreateObjectAttr {
+            content_type: Some("text/plain".into()),
+            ..object_attr("dir/hello.txt")
+        };
+
+        // Act
+        let created = storage
+            .create_object(
+                "test_bucket",
+                attr,
+                Bytes::from("hello"),
+                Preconditions::default(),
+            )
+            .await;
+        let res = storage
+            .get_object(
+                "test_bucket",
+                "dir/hello.txt",
+                None,
+                Preconditions::default(),
+            )
+            .await;
+
+        // Assert
+        assert_pred!(created.is_ok());
+        assert_pred!(res.is_ok());
+        let res = res.unwrap();
+        expect_that!(res.content, eq(&Bytes::from("hello")));
+        expect_that!(res.attr.size, eq(5));
+        expect_that!(res.attr.content_type, eq("text/plain"));
+        expect_that!(res.attr.md5_hash, eq("XUFAKrxLKna5cZ2REBfFkg=="));
+        expect_that!(res.attr.crc32c, eq("mnG7TA=="));
+    }
+
+    #[googletest::test]
+    #[tokio::test]
+    async fn keep_only_latest_generation_when_versioning_is_disabled() {
+        // Arrange
+        let storage = storage_with_bucket(false).await;
+        let first = storage
+            .create_object(
+                "test_bucket",
+                object_attr("a"),
+                Bytes::from("1"),
+                Preconditions::default(),
+            )
+            .await
+            .unwrap();
+
+        // Act
+        let second = storage
+            .create_object(
+                "test_bucket",
+                object_attr("a"),
+                Bytes::from("2"),
+                Preconditions::default(),
+            )
+            .await
+            .unwrap();
+        let res = storage
+            .list_objects(
+                "test_bucket",
+                ListObjectsAttr {
+                    versions: true,
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+
+        // Assert
+        expect_that!(second.generation, gt(first.generation));
+        assert_that!(
+            res.items,
+            elements_are![field!(StorageObjectAttr.generation, eq(&second.generation))]
+        );
+    }
+
+    #[googletest::test]
+    #[tokio::test]
+    async fn keep_noncurrent_versions_when_versioning_is_enabled() {
+        // Arrange
+        let storage = storage_with_bucket(true).await;
+        let first = storage
+            .create_object(
+                "test_bucket",
+                object_attr("a"),
+                Bytes::from("1"),
+                Preconditions::default(),
+            )
+            .await
+            .unwrap();
+        let _ = storage
+            .create_object(
+                "test_bucket",
+                object_attr("a"),
+                Bytes::from("2"),
+                Preconditions::default(),
+            )
+            .await;
+
+        // Act
+        let deleted = storage
+            .delete_object("test_bucket", "a", None, Preconditions::default())
+            .await;
+        let live = storage
+            .list_objects("test_bucket", ListObjectsAttr::default())
+            .await
+            .unwrap();
+        let versions = storage
+            .list_objects(
+                "test_bucket",
+                ListObjectsAttr {
+                    versions: true,
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        let old = storage
+            .get_object(
+                "test_bucket",
+                "a",
+                Some(first.generation),
+                Preconditions::default(),
+            )
+            .await;
+
+        // Assert
+        assert_pred!(deleted.is_ok());
+        expect_that!(live.items, empty());
+        expect_that!(versions.items.len(), eq(2));
+        assert_that!(
+            old,
+            ok(field!(OnMemoryStorageObject.content, eq(&Bytes::from("1"))))
+        );
+    }
+
+    #[googletest::test]
+    #[tokio::test]
+    async fn roll_up_names_into_prefixes_by_delimiter() {
+        // Arrange
+        let storage = storage_with_bucket(false).await;
+        for name in ["a/1.txt", "a/b/2.txt", "a/c/", "a/3.txt", "d/4.txt"] {
+            let _ = storage
+                .create_object(
+                    "test_bucket",
+                    object_attr(name),
+                    Bytes::new(),
+                    Preconditions::default(),
+                )
+                .await;
+        }
+
+        // Act
+        let res = storage
+            .list_objects(
+                "test_bucket",
+                ListObjectsAttr {
+                    prefix: Some("a/".into()),
+                    delimiter: Some("/".into()),
+                    include_trailing_delimiter: true,
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+
+        // Assert
+        assert_that!(
+            res.items,
+            elements_are![
+                field!(StorageObjectAttr.name, eq("a/1.txt")),
+                field!(StorageObjectAttr.name, eq("a/3.txt")),
+                field!(StorageObjectAttr.name, eq("a/c/")),
+            ]
+        );
+        assert_that!(res.prefixes, elements_are![eq("a/b/"), eq("a/c/")]);
+    }
+
+    #[googletest::test]
+    #[tokio::test]
+    async fn return_precondition_failed_when_object_already_exists() {
+        // Arrange
+        let storage = storage_with_bucket(false).await;
+        let _ = storage
+            .create_object(
+                "test_bucket",
+                object_attr("a"),
+                Bytes::new(),
+                Preconditions::default(),
+            )
+            .await;
+
+        // Act
+        let res = storage
+            .create_object(
+                "test_bucket",
+                object_attr("a"),
+                Bytes::new(),
+                Preconditions {
+                    if_generation_match: Some(0),
+                    ..Default::default()
+                },
+            )
+            .await;
+
+        // Assert
+        assert_that!(
+            res,
+            err(matches_pattern!(Errors::PreconditionFailed { .. }))
+        );
+    }
+
+    #[googletest::test]
+    #[tokio::test]
+    async fn merge_metadata_and_bump_metageneration_while_updating_object() {
+        // Arrange
+        let storage = storage_with_bucket(false).await;
+        let attr = CreateObjectAttr {
+            metadata: HashMap::from([
+                ("keep".to_string(), "1".to_string()),
+                ("remove".to_string(), "2".to_string()),
+            ]),
+            ..object_attr("a")
+        };
+        let _ = storage
+            .create_object("test_bucket", attr, Bytes::new(), Preconditions::default())
+            .await;
+
+        // Act
+        let res = storage
+            .update_object(
+                "test_bucket",
+                "a",
+                None,
+                UpdateObjectAttr {
+                    content_language: Some(Some("en".into())),
+                    metadata: Some(MetadataUpdate::Merge(HashMap::from([
+                        ("remove".to_string(), None),
+                        ("add".to_string(), Some("3".to_string())),
+                    ]))),
+                    ..Default::default()
+                },
+                Preconditions::default(),
+            )
+            .await;
+
+        // Assert
+        assert_pred!(res.is_ok());
+        let res = res.unwrap();
+        expect_that!(res.metageneration, eq(2));
+        expect_that!(res.content_language, some(eq("en")));
+        expect_that!(
+            res.metadata,
+            eq(&HashMap::from([
+                ("keep".to_string(), "1".to_string()),
+                ("add".to_string(), "3".to_string()),
+            ]))
+        );
+    }
+
+    #[googletest::test]
+    #[tokio::test]
+    async fn return_conflict_error_while_deleting_non_empty_bucket() {
+        // Arrange
+        let storage = storage_with_bucket(false).await;
+        let _ = storage
+            .create_object(
+                "test_bucket",
+                object_attr("a"),
+                Bytes::new(),
+                Preconditions::default(),
+            )
+            .await;
+
+        // Act
+        let res = storage.delete("test_bucket").await;
+
+        // Assert
+        assert_that!(res, err(matches_pattern!(Errors::BucketNotEmpty { .. })));
+    }
+
+    #[googletest::test]
+    #[tokio::test]
+    async fn reject_deleting_held_object_until_hold_is_released() {
+        // Arrange
+        let storage = Storage::default();
+        let _ = storage
+            .create(
+                "test_bucket",
+                CreateBucketAttr {
+                    project: "test-project".into(),
+                    default_event_based_hold: true,
+                    ..Default::default()
+                },
+            )
+            .await;
+        let created = storage
+            .create_object(
+                "test_bucket",
+                object_attr("a"),
+                Bytes::new(),
+                Preconditions::default(),
+            )
+            .await
+            .unwrap();
+
+        // Act
+        let held = storage
+            .delete_object("test_bucket", "a", None, Preconditions::default())
+            .await;
+        let released = storage
+            .update_object(
+                "test_bucket",
+                "a",
+                None,
+                UpdateObjectAttr {
+                    event_based_hold: Some(false),
+                    ..Default::default()
+                },
+                Preconditions::default(),
+            )
+            .await;
+        let deleted = storage
+            .delete_object("test_bucket", "a", None, Preconditions::default())
+            .await;
+
+        // Assert
+        expect_that!(created.event_based_hold, eq(true));
+        expect_that!(held, err(matches_pattern!(Errors::Forbidden { .. })));
+        expect_that!(
+            released,
+            ok(field!(StorageObjectAttr.retention_expiration_time, none()))
+        );
+        assert_that!(deleted, ok(anything()));
+    }
+}
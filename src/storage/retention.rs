@@ -0,0 +1,413 @@
+use chrono::{DateTime, Duration, Utc};
+
+use crate::libs::errors::{AppResult, Errors};
+
+use super::{event::Action, OnMemoryStorageBucket, Storage, StorageBucketAttr, StorageObjectAttr};
+
+/// The `retentionPolicy` of a bucket.
+/// https://cloud.google.com/storage/docs/bucket-lock
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetentionPolicy {
+    /// The minimum age of objects in seconds.
+    pub retention_period: u64,
+    pub effective_time: DateTime<Utc>,
+    pub is_locked: bool,
+}
+
+impl RetentionPolicy {
+    pub fn period(&self) -> Duration {
+        Duration::seconds(self.retention_period as i64)
+    }
+
+    /// Replaces the policy with the given period, or removes it with `None`.
+    /// A locked policy can neither be removed nor shortened.
+    pub fn replace(
+        current: Option<&RetentionPolicy>,
+        retention_period: Option<u64>,
+        now: DateTime<Utc>,
+    ) -> AppResult<Option<RetentionPolicy>, Errors> {
+        match (current, retention_period) {
+            (Some(current), period)
+                if current.is_locked && period.is_none_or(|p| p < current.retention_period) =>
+            {
+                Err(Errors::Forbidden {
+                    message: "Cannot reduce the retention period of a locked retention policy"
+                        .into(),
+                })
+            }
+            (Some(current), Some(period)) if current.retention_period == period => {
+                Ok(Some(current.clone()))
+            }
+            (current, Some(period)) => Ok(Some(RetentionPolicy {
+                retention_period: period,
+                effective_time: now,
+                is_locked: current.is_some_and(|c| c.is_locked),
+            })),
+            (_, None) => Ok(None),
+        }
+    }
+}
+
+/// The `retention` of an object, which is available in buckets created with `enableObjectRetention`.
+/// https://cloud.google.com/storage/docs/object-lock
+#[derive(Debug, Clone, PartialEq)]
+pub struct ObjectRetention {
+    pub mode: RetentionMode,
+    pub retain_until_time: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RetentionMode {
+    Unlocked,
+    Locked,
+}
+
+impl ObjectRetention {
+    /// Replaces the retention of an object, or removes it with `None`.
+    /// A retention can always be extended, while reducing or removing an unlocked one requires
+    /// `overrideUnlockedRetention`, and a locked one can't be reduced at all.
+    pub fn replace(
+        current: Option<&ObjectRetention>,
+        retention: Option<ObjectRetention>,
+        override_unlocked_retention: bool,
+    ) -> AppResult<Option<ObjectRetention>, Errors> {
+        let Some(current) = current else {
+            return Ok(retention);
+        };
+        let extended = retention.as_ref().is_some_and(|r| {
+            r.retain_until_time >= current.retain_until_time
+                && (r.mode == current.mode || r.mode == RetentionMode::Locked)
+        });
+        match current.mode {
+            _ if extended => Ok(retention),
+            RetentionMode::Unlocked if override_unlocked_retention => Ok(retention),
+            RetentionMode::Unlocked => Err(Errors::Forbidden {
+                message:
+                    "Reducing or removing an unlocked retention requires overrideUnlockedRetention"
+                        .into(),
+            }),
+            RetentionMode::Locked => Err(Errors::Forbidden {
+                message: "A locked retention can only be extended".into(),
+            }),
+        }
+    }
+}
+
+/// The time until which an object whose retention starts at `start` is retained.
+pub(super) fn retention_expiration(
+    policy: Option<&RetentionPolicy>,
+    start: DateTime<Utc>,
+) -> Option<DateTime<Utc>> {
+    policy.map(|p| start + p.period())
+}
+
+/// Recomputes `retentionExpirationTime` of every object after the policy of the bucket is replaced.
+/// Objects under an event-based hold are left alone, since their retention starts once it's released.
+pub(super) fn apply_retention_policy(
+    bucket: &OnMemoryStorageBucket,
+    previous: Option<&RetentionPolicy>,
+) {
+    let policy = bucket.attr.retention_policy.as_ref();
+    for mut object in bucket.objects.iter_mut() {
+        let attr = &mut object.attr;
+        if attr.event_based_hold {
+            continue;
+        }
+        let start = match (attr.retention_expiration_time, previous) {
+            (Some(expiration), Some(previous)) => expiration - previous.period(),
+            (Some(expiration), None) => expiration,
+            (None, _) => attr.time_created,
+        };
+        attr.retention_expiration_time = retention_expiration(policy, start);
+    }
+}
+
+/// Rejects deleting or overwriting an object younger than the retention period,
+/// or retained by its own retention.
+pub(super) fn check_retention(
+    bucket: &OnMemoryStorageBucket,
+    object: &StorageObjectAttr,
+    now: DateTime<Utc>,
+) -> AppResult<(), Errors> {
+    let (subject, until) = match (&object.retention, object.retention_expiration_time) {
+        (Some(retention), _) if retention.retain_until_time > now => {
+            ("object retention", retention.retain_until_time)
+        }
+        (_, Some(expiration)) if expiration > now => ("bucket's retention policy", expiration),
+        _ => return Ok(()),
+    };
+    Err(Errors::Forbidden {
+        message: format!(
+            "Object '{}/{}' is subject to {subject} and cannot be deleted or overwritten until {}",
+            bucket.attr.name,
+            object.name,
+            until.to_utc().to_rfc3339()
+        ),
+    })
+}
+
+/// Rejects the retention of an object unless the bucket enables object retention.
+pub(super) fn check_object_retention(
+    bucket: &OnMemoryStorageBucket,
+    retention: Option<&ObjectRetention>,
+) -> AppResult<(), Errors> {
+    if retention.is_some() && !bucket.attr.object_retention {
+        return Err(Errors::BadRequest {
+            message: format!(
+                "Object retention is not enabled on the bucket: {}",
+                bucket.attr.name
+            ),
+        });
+    }
+    Ok(())
+}
+
+/// Aggregates operations for the retention policy of a bucket.
+pub trait RetentionStorageExt {
+    /// Corresponds to `lockRetentionPolicy` operation: https://cloud.google.com/storage/docs/json_api/v1/buckets/lockRetentionPolicy
+    /// The metageneration must match the current one, so that the policy the caller has seen is locked.
+    async fn lock_retention_policy(
+        &self,
+        bucket: &str,
+        if_metageneration_match: u64,
+    ) -> AppResult<StorageBucketAttr, Errors>;
+}
+
+impl RetentionStorageExt for Storage {
+    async fn lock_retention_policy(
+        &self,
+        bucket: &str,
+        if_metageneration_match: u64,
+    ) -> AppResult<StorageBucketAttr, Errors> {
+        let bucket = self.bucket(bucket)?;
+        let mut bucket = bucket.lock().unwrap();
+        if bucket.attr.metageneration != if_metageneration_match {
+            return Err(Errors::PreconditionFailed {
+                message: "At least one of the pre-conditions you specified did not hold.".into(),
+            });
+        }
+        let Some(policy) = bucket.attr.retention_policy.as_mut() else {
+            return Err(Errors::BadRequest {
+                message: "The bucket has no retention policy to lock".into(),
+            });
+        };
+        if !policy.is_locked {
+            policy.is_locked = true;
+            bucket.attr.metageneration += 1;
+            bucket.attr.updated = self.clock.now();
+            self.events
+                .publish_bucket(Action::Update, &bucket.attr, bucket.attr.updated);
+        }
+        Ok(bucket.attr.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+    use googletest::prelude::*;
+
+    use crate::{
+        libs::errors::Errors,
+        storage::{
+            retention::{ObjectRetention, RetentionMode, RetentionStorageExt},
+            BucketStorageExt, CreateBucketAttr, CreateObjectAttr, ObjectStorageExt, Preconditions,
+            Storage, UpdateBucketAttr, UpdateObjectAttr,
+        },
+    };
+
+    async fn storage_with_bucket(retention_period: u64) -> Storage {
+        let storage = Storage::default();
+        let attr = CreateBucketAttr {
+            project: "test-project".into(),
+            retention_period: Some(retention_period),
+            ..Default::default()
+        };
+        let _ = storage.create("test_bucket", attr).await;
+        storage
+    }
+
+    #[googletest::test]
+    #[tokio::test]
+    async fn reject_deleting_object_younger_than_retention_period() {
+        // Arrange
+        let storage = storage_with_bucket(3600).await;
+        let created = storage
+            .create_object(
+                "test_bucket",
+                CreateObjectAttr {
+                    name: "a".into(),
+                    ..Default::default()
+                },
+                Bytes::new(),
+                Preconditions::default(),
+            )
+            .await
+            .unwrap();
+
+        // Act
+        let res = storage
+            .delete_object("test_bucket", "a", None, Preconditions::default())
+            .await;
+
+        // Assert
+        expect_that!(
+            created.retention_expiration_time,
+            some(eq(created.time_created + chrono::Duration::hours(1)))
+        );
+        assert_that!(res, err(matches_pattern!(Errors::Forbidden { .. })));
+    }
+
+    #[googletest::test]
+    #[tokio::test]
+    async fn start_retention_period_when_hold_is_released() {
+        // Arrange
+        let storage = storage_with_bucket(3600).await;
+        let created = storage
+            .create_object(
+                "test_bucket",
+                CreateObjectAttr {
+                    name: "a".into(),
+                    event_based_hold: Some(true),
+                    ..Default::default()
+                },
+                Bytes::new(),
+                Preconditions::default(),
+            )
+            .await
+            .unwrap();
+
+        // Act
+        let released = storage
+            .update_object(
+                "test_bucket",
+                "a",
+                None,
+                UpdateObjectAttr {
+                    event_based_hold: Some(false),
+                    ..Default::default()
+                },
+                Preconditions::default(),
+            )
+            .await
+            .unwrap();
+
+        // Assert
+        expect_that!(created.retention_expiration_time, none());
+        assert_that!(
+            released.retention_expiration_time,
+            some(eq(released.updated + chrono::Duration::hours(1)))
+        );
+    }
+
+    #[googletest::test]
+    #[tokio::test]
+    async fn allow_only_extending_locked_retention_policy() {
+        // Arrange
+        let storage = storage_with_bucket(3600).await;
+        let metageneration = storage.get("test_bucket").await.unwrap().metageneration;
+
+        // Act
+        let stale = storage
+            .lock_retention_policy("test_bucket", metageneration + 1)
+            .await;
+        let locked = storage
+            .lock_retention_policy("test_bucket", metageneration)
+            .await;
+        let reduced = storage
+            .update(
+                "test_bucket",
+                UpdateBucketAttr {
+                    retention_period: Some(Some(60)),
+                    ..Default::default()
+                },
+            )
+            .await;
+        let extended = storage
+            .update(
+                "test_bucket",
+                UpdateBucketAttr {
+                    retention_period: Some(Some(7200)),
+                    ..Default::default()
+                },
+            )
+            .await;
+
+        // Assert
+        expect_that!(
+            stale,
+            err(matches_pattern!(Errors::PreconditionFailed { .. }))
+        );
+        expect_that!(locked, ok(anything()));
+        expect_that!(reduced, err(matches_pattern!(Errors::Forbidden { .. })));
+        let policy = extended.unwrap().retention_policy.unwrap();
+        expect_that!(policy.retention_period, eq(7200));
+        assert_that!(policy.is_locked, eq(true));
+    }
+
+    #[googletest::test]
+    #[tokio::test]
+    async fn require_override_to_remove_unlocked_object_retention() {
+        // Arrange
+        let storage = Storage::default();
+        let _ = storage
+            .create(
+                "test_bucket",
+                CreateBucketAttr {
+                    project: "test-project".into(),
+                    object_retention: true,
+                    ..Default::default()
+                },
+            )
+            .await;
+        let retention = ObjectRetention {
+            mode: RetentionMode::Unlocked,
+            retain_until_time: chrono::Utc::now() + chrono::Duration::days(1),
+        };
+        let _ = storage
+            .create_object(
+                "test_bucket",
+                CreateObjectAttr {
+                    name: "a".into(),
+                    retention: Some(retention),
+                    ..Default::default()
+                },
+                Bytes::new(),
+                Preconditions::default(),
+            )
+            .await;
+        let remove = |override_unlocked_retention| UpdateObjectAttr {
+            retention: Some(None),
+            override_unlocked_retention,
+            ..Default::default()
+        };
+
+        // Act
+        let retained = storage
+            .delete_object("test_bucket", "a", None, Preconditions::default())
+            .await;
+        let denied = storage
+            .update_object(
+                "test_bucket",
+                "a",
+                None,
+                remove(false),
+                Preconditions::default(),
+            )
+            .await;
+        let removed = storage
+            .update_object(
+                "test_bucket",
+                "a",
+                None,
+                remove(true),
+                Preconditions::default(),
+            )
+            .await;
+
+        // Assert
+        expect_that!(retained, err(matches_pattern!(Errors::Forbidden { .. })));
+        expect_that!(denied, err(matches_pattern!(Errors::Forbidden { .. })));
+        assert_that!(removed.map(|o| o.retention), ok(none()));
+    }
+}
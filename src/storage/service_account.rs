@@ -0,0 +1,114 @@
+use std::sync::Arc;
+
+use dashmap::DashMap;
+use rsa::RsaPublicKey;
+
+use crate::libs::registry::Project;
+
+use super::Storage;
+
+/// A public key of a service account, which verifies signed URLs made with its private key.
+/// https://cloud.google.com/storage/docs/access-control/signed-urls
+#[derive(Debug, Clone, PartialEq)]
+pub struct ServiceAccountKey {
+    pub service_account_email: String,
+    pub project: Project,
+    pub public_key: RsaPublicKey,
+}
+
+impl ServiceAccountKey {
+    /// The member signed requests are authorized as.
+    pub fn principal(&self) -> String {
+        format!("serviceAccount:{}", self.service_account_email)
+    }
+}
+
+/// Public keys keyed by the email of their service account, which can have several keys.
+#[derive(Debug, Clone, Default)]
+pub struct ServiceAccountKeys {
+    keys: Arc<DashMap<String, Vec<ServiceAccountKey>>>,
+}
+
+/// Aggregates operations for public keys of service accounts.
+pub trait ServiceAccountKeyStorageExt {
+    /// Registers a public key of a service account, e.g. by `--service-account-key`.
+    async fn import_service_account_key(
+        &self,
+        project: &str,
+        service_account_email: &str,
+        public_key: RsaPublicKey,
+    ) -> ServiceAccountKey;
+
+    /// Lists the keys a request signed by the service account can be verified with.
+    async fn service_account_keys(&self, service_account_email: &str) -> Vec<ServiceAccountKey>;
+}
+
+impl ServiceAccountKeyStorageExt for Storage {
+    async fn import_service_account_key(
+        &self,
+        project: &str,
+        service_account_email: &str,
+        public_key: RsaPublicKey,
+    ) -> ServiceAccountKey {
+        let key = ServiceAccountKey {
+            service_account_email: service_account_email.to_string(),
+            project: self.projects.resolve(project),
+            public_key,
+        };
+        self.service_account_keys
+            .keys
+            .entry(service_account_email.to_string())
+            .or_default()
+            .push(key.clone());
+        key
+    }
+
+    async fn service_account_keys(&self, service_account_email: &str) -> Vec<ServiceAccountKey> {
+        self.service_account_keys
+            .keys
+            .get(service_account_email)
+            .map(|k| k.clone())
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use googletest::prelude::*;
+    use rsa::{RsaPrivateKey, RsaPublicKey};
+
+    use crate::storage::{
+        service_account::{ServiceAccountKey, ServiceAccountKeyStorageExt},
+        Storage,
+    };
+
+    const SERVICE_ACCOUNT: &str = "sa@test-project.iam.gserviceaccount.com";
+
+    #[googletest::test]
+    #[tokio::test]
+    async fn list_keys_of_service_account() {
+        // Arrange
+        let storage = Storage::default();
+        let private_key = RsaPrivateKey::new(&mut rand::thread_rng(), 1024).unwrap();
+        let public_key = RsaPublicKey::from(&private_key);
+        for _ in 0..2 {
+            storage
+                .import_service_account_key("test-project", SERVICE_ACCOUNT, public_key.clone())
+                .await;
+        }
+
+        // Act
+        let keys = storage.service_account_keys(SERVICE_ACCOUNT).await;
+        let unknown = storage
+            .service_account_keys("unknown@test-project.iam.gserviceaccount.com")
+            .await;
+
+        // Assert
+        expect_that!(
+            keys,
+            each(field!(ServiceAccountKey.public_key, eq(&public_key)))
+        );
+        expect_that!(keys, len(eq(2)));
+        assert_that!(unknown, empty());
+    }
+}
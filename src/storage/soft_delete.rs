@@ -0,0 +1,451 @@
+use chrono::{DateTime, Duration, Utc};
+use dashmap::mapref::entry::Entry;
+
+use crate::libs::errors::{AppResult, Errors};
+
+use super::{
+    event::Action,
+    object::{etag, next_generation, publish_finalize, replace_live_object, Preconditions},
+    retention, ObjectGeneration, ObjectKey, ObjectName, OnMemoryStorageBucket,
+    OnMemoryStorageObject, Storage, StorageBucketAttr, StorageObjectAttr,
+};
+
+/// The `softDeletePolicy` of a bucket.
+/// https://cloud.google.com/storage/docs/soft-delete
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SoftDeletePolicy {
+    /// How long deleted objects and buckets are kept in seconds, where `0` disables soft delete.
+    pub retention_duration_seconds: u64,
+    pub effective_time: DateTime<Utc>,
+}
+
+/// GCS accepts durations between 7 and 90 days besides `0`.
+const MIN_RETENTION_DURATION: u64 = 7 * 24 * 60 * 60;
+const MAX_RETENTION_DURATION: u64 = 90 * 24 * 60 * 60;
+
+/// The duration of a bucket created without `softDeletePolicy`, which is 7 days as in GCS.
+pub const DEFAULT_RETENTION_DURATION: u64 = MIN_RETENTION_DURATION;
+
+impl SoftDeletePolicy {
+    pub fn new(retention_duration_seconds: u64, now: DateTime<Utc>) -> AppResult<Self, Errors> {
+        if retention_duration_seconds != 0
+            && !(MIN_RETENTION_DURATION..=MAX_RETENTION_DURATION)
+                .contains(&retention_duration_seconds)
+        {
+            return Err(Errors::BadRequest {
+                message: format!(
+                    "Soft delete retention duration must be 0 or between {MIN_RETENTION_DURATION} and {MAX_RETENTION_DURATION} seconds"
+                ),
+            });
+        }
+        Ok(SoftDeletePolicy {
+            retention_duration_seconds,
+            effective_time: now,
+        })
+    }
+
+    /// Keeps the effective time unless the duration changes.
+    pub fn replace(
+        &self,
+        retention_duration_seconds: u64,
+        now: DateTime<Utc>,
+    ) -> AppResult<Self, Errors> {
+        if self.retention_duration_seconds == retention_duration_seconds {
+            return Ok(self.clone());
+        }
+        Self::new(retention_duration_seconds, now)
+    }
+
+    /// The duration deleted objects are kept for, or `None` if soft delete is disabled.
+    pub fn retention_duration(&self) -> Option<Duration> {
+        (self.retention_duration_seconds > 0)
+            .then(|| Duration::seconds(self.retention_duration_seconds as i64))
+    }
+}
+
+/// Removes a version of an object, which is kept as soft-deleted while the bucket enables soft delete.
+pub(super) fn discard_object(bucket: &OnMemoryStorageBucket, key: &ObjectKey, now: DateTime<Utc>) {
+    let Some(duration) = bucket.attr.soft_delete_policy.retention_duration() else {
+        bucket.objects.remove(key);
+        return;
+    };
+    if let Some(mut object) = bucket.objects.get_mut(key) {
+        object.attr.time_deleted.get_or_insert(now);
+        object.attr.soft_delete_time = Some(now);
+        object.attr.hard_delete_time = Some(now + duration);
+    }
+}
+
+/// Whether a soft-deleted object or bucket can still be restored.
+pub(super) fn is_restorable(hard_delete_time: Option<DateTime<Utc>>, now: DateTime<Utc>) -> bool {
+    hard_delete_time.is_some_and(|t| t > now)
+}
+
+fn find_soft_deleted_object(
+    bucket: &OnMemoryStorageBucket,
+    name: &str,
+    generation: u64,
+    now: DateTime<Utc>,
+) -> AppResult<OnMemoryStorageObject, Errors> {
+    bucket
+        .objects
+        .get(&(ObjectName(name.to_string()), ObjectGeneration(generation)))
+        .map(|o| o.value().clone())
+        .filter(|o| is_restorable(o.attr.hard_delete_time, now))
+        .ok_or_else(|| Errors::ObjectNotFound {
+            message: format!(
+                "No such soft-deleted object: {}/{name}#{generation}",
+                bucket.attr.name
+            ),
+        })
+}
+
+/// Aggregates operations for soft-deleted objects and buckets.
+pub trait SoftDeleteStorageExt {
+    /// Corresponds to `list` bucket operation with `softDeleted=true`.
+    async fn list_soft_deleted_buckets(&self, project: &str) -> Vec<StorageBucketAttr>;
+
+    /// Corresponds to `get` bucket operation with `softDeleted=true`.
+    async fn get_soft_deleted_bucket(
+        &self,
+        name: &str,
+        generation: u64,
+    ) -> AppResult<StorageBucketAttr, Errors>;
+
+    /// Corresponds to `restore` bucket operation: https://cloud.google.com/storage/docs/json_api/v1/buckets/restore
+    /// Objects soft-deleted in the bucket stay soft-deleted, and are restored one by one.
+    async fn restore_bucket(
+        &self,
+        name: &str,
+        generation: u64,
+    ) -> AppResult<StorageBucketAttr, Errors>;
+
+    /// Corresponds to `get` object operation with `softDeleted=true`.
+    async fn get_soft_deleted_object(
+        &self,
+        bucket: &str,
+        name: &str,
+        generation: u64,
+    ) -> AppResult<StorageObjectAttr, Errors>;
+
+    /// Corresponds to `restore` object operation: https://cloud.google.com/storage/docs/json_api/v1/objects/restore
+    /// The object is restored as the live version with a new generation, like `insert` does.
+    /// The default object ACL of the bucket is applied unless `copy_source_acl` is set.
+    async fn restore_object(
+        &self,
+        bucket: &str,
+        name: &str,
+        generation: u64,
+        copy_source_acl: bool,
+        conditions: Preconditions,
+    ) -> AppResult<StorageObjectAttr, Errors>;
+
+    /// Permanently removes objects and buckets whose `hardDeleteTime` has passed.
+    /// Returns the number of removed objects and buckets.
+    async fn purge_soft_deleted(&self) -> usize;
+}
+
+impl SoftDeleteStorageExt for Storage {
+    async fn list_soft_deleted_buckets(&self, project: &str) -> Vec<StorageBucketAttr> {
+        let now = self.clock.now();
+        let mut buckets = self
+            .soft_deleted_buckets
+            .iter()
+            .map(|b| b.value().lock().unwrap().attr.clone())
+            .filter(|b| {
+                self.projects.matches(project, &b.project) && is_restorable(b.hard_delete_time, now)
+            })
+            .collect::<Vec<_>>();
+        buckets.sort_by(|a, b| (&a.name, a.generation).cmp(&(&b.name, b.generation)));
+        buckets
+    }
+
+    async fn get_soft_deleted_bucket(
+        &self,
+        name: &str,
+        generation: u64,
+    ) -> AppResult<StorageBucketAttr, Errors> {
+        let now = self.clock.now();
+        self.soft_deleted_buckets
+            .get(&(name.to_string(), generation))
+            .map(|b| b.value().lock().unwrap().attr.clone())
+            .filter(|b| is_restorable(b.hard_delete_time, now))
+            .ok_or_else(|| Errors::BucketNotFound {
+                message: format!("No such soft-deleted bucket: {name}#{generation}"),
+            })
+    }
+
+    async fn restore_bucket(
+        &self,
+        name: &str,
+        generation: u64,
+    ) -> AppResult<StorageBucketAttr, Errors> {
+        self.get_soft_deleted_bucket(name, generation).await?;
+        // The name is held until the bucket is restored, so that a bucket created meanwhile isn't overwritten.
+        let Entry::Vacant(entry) = self.buckets.entry(name.to_string()) else {
+            return Err(Errors::AlreadyExists {
+                message: format!("A bucket named {name} already exists"),
+            });
+        };
+        let (_, bucket) = self
+            .soft_deleted_buckets
+            .remove(&(name.to_string(), generation))
+            .ok_or_else(|| Errors::BucketNotFound {
+                message: format!("No such soft-deleted bucket: {name}#{generation}"),
+            })?;
+        let attr = {
+            let mut restored = bucket.lock().unwrap();
+            restored.attr.soft_delete_time = None;
+            restored.attr.hard_delete_time = None;
+            restored.attr.clone()
+        };
+        entry.insert(bucket);
+        self.events
+            .publish_bucket(Action::Create, &attr, self.clock.now());
+        Ok(attr)
+    }
+
+    async fn get_soft_deleted_object(
+        &self,
+        bucket: &str,
+        name: &str,
+        generation: u64,
+    ) -> AppResult<StorageObjectAttr, Errors> {
+        let bucket = self.bucket(bucket)?;
+        let bucket = bucket.lock().unwrap();
+        find_soft_deleted_object(&bucket, name, generation, self.clock.now()).map(|o| o.attr)
+    }
+
+    async fn restore_object(
+        &self,
+        bucket: &str,
+        name: &str,
+        generation: u64,
+        copy_source_acl: bool,
+        conditions: Preconditions,
+    ) -> AppResult<StorageObjectAttr, Errors> {
+        let bucket = self.bucket(bucket)?;
+        let bucket = bucket.lock().unwrap();
+        let now = self.clock.now();
+        let source = find_soft_deleted_object(&bucket, name, generation, now)?;
+        let current = super::object::find_object(&bucket, name, None).ok();
+        conditions.check(current.as_ref().map(|o| &o.attr))?;
+        let replaced = current
+            .map(|current| replace_live_object(&bucket, &current.attr, now))
+            .transpose()?;
+
+        let generation = next_generation(&bucket, name, now);
+        let object = StorageObjectAttr {
+            acl: if copy_source_acl {
+                source.attr.acl.clone()
+            } else {
+                bucket.attr.default_object_acl.clone()
+            },
+            etag: etag(generation, 1),
+            time_created: now,
+            updated: now,
+            time_deleted: None,
+            soft_delete_time: None,
+            hard_delete_time: None,
+            retention_expiration_time: if source.attr.event_based_hold {
+                None
+            } else {
+                retention::retention_expiration(bucket.attr.retention_policy.as_ref(), now)
+            },
+            generation,
+            metageneration: 1,
+            ..source.attr.clone()
+        };
+        bucket.objects.remove(&(
+            ObjectName(source.attr.name.clone()),
+            ObjectGeneration(source.attr.generation),
+        ));
+        bucket.objects.insert(
+            (ObjectName(name.to_string()), ObjectGeneration(generation)),
+            OnMemoryStorageObject {
+                attr: object.clone(),
+                content: source.content,
+            },
+        );
+        publish_finalize(self, &bucket, &object, replaced, now);
+        Ok(object)
+    }
+
+    async fn purge_soft_deleted(&self) -> usize {
+        let now = self.clock.now();
+        let buckets = self
+            .buckets
+            .iter()
+            .map(|b| b.value().clone())
+            .collect::<Vec<_>>();
+        let mut purged = 0;
+        for bucket in buckets {
+            let bucket = bucket.lock().unwrap();
+            let before = bucket.objects.len();
+            bucket.objects.retain(|_, o| {
+                !o.attr.is_soft_deleted() || is_restorable(o.attr.hard_delete_time, now)
+            });
+            purged += before - bucket.objects.len();
+        }
+        let before = self.soft_deleted_buckets.len();
+        self.soft_deleted_buckets
+            .retain(|_, b| is_restorable(b.lock().unwrap().attr.hard_delete_time, now));
+        purged + before - self.soft_deleted_buckets.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+    use chrono::{Duration, Utc};
+    use googletest::prelude::*;
+
+    use crate::{
+        libs::{clock::Clock, errors::Errors, registry::ProjectRegistry},
+        storage::{
+            soft_delete::SoftDeleteStorageExt, BucketStorageExt, CreateBucketAttr,
+            CreateObjectAttr, ListObjectsAttr, ObjectStorageExt, Preconditions, Storage,
+            StorageObjectAttr,
+        },
+    };
+
+    const WEEK: u64 = 7 * 24 * 60 * 60;
+
+    async fn storage_with_object() -> (Storage, StorageObjectAttr) {
+        let storage = Storage::new(ProjectRegistry::default(), Clock::fixed(Utc::now()));
+        let attr = CreateBucketAttr {
+            project: "test-project".into(),
+            soft_delete_retention_duration: WEEK,
+            ..Default::default()
+        };
+        let _ = storage.create("test_bucket", attr).await;
+        let object = storage
+            .create_object(
+                "test_bucket",
+                CreateObjectAttr {
+                    name: "a".into(),
+                    ..Default::default()
+                },
+                Bytes::from("hello"),
+                Preconditions::default(),
+            )
+            .await
+            .unwrap();
+        (storage, object)
+    }
+
+    #[googletest::test]
+    #[tokio::test]
+    async fn restore_soft_deleted_object_as_new_generation() {
+        // Arrange
+        let (storage, created) = storage_with_object().await;
+        let _ = storage
+            .delete_object("test_bucket", "a", None, Preconditions::default())
+            .await;
+
+        // Act
+        let live = storage
+            .list_objects("test_bucket", ListObjectsAttr::default())
+            .await
+            .unwrap();
+        let soft_deleted = storage
+            .list_objects(
+                "test_bucket",
+                ListObjectsAttr {
+                    soft_deleted: true,
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        let restored = storage
+            .restore_object(
+                "test_bucket",
+                "a",
+                created.generation,
+                false,
+                Preconditions::default(),
+            )
+            .await
+            .unwrap();
+        let res = storage
+            .get_object("test_bucket", "a", None, Preconditions::default())
+            .await
+            .unwrap();
+
+        // Assert
+        expect_that!(live.items, empty());
+        expect_that!(
+            soft_deleted.items,
+            elements_are![field!(
+                StorageObjectAttr.hard_delete_time,
+                some(eq(&(created.time_created + Duration::weeks(1))))
+            )]
+        );
+        expect_that!(restored.generation, gt(created.generation));
+        assert_that!(res.content, eq(&Bytes::from("hello")));
+    }
+
+    #[googletest::test]
+    #[tokio::test]
+    async fn purge_soft_deleted_object_after_retention_duration() {
+        // Arrange
+        let (storage, created) = storage_with_object().await;
+        let _ = storage
+            .delete_object("test_bucket", "a", None, Preconditions::default())
+            .await;
+
+        // Act
+        let kept = storage.purge_soft_deleted().await;
+        storage.clock().advance(Duration::weeks(1));
+        let purged = storage.purge_soft_deleted().await;
+        let res = storage
+            .restore_object(
+                "test_bucket",
+                "a",
+                created.generation,
+                false,
+                Preconditions::default(),
+            )
+            .await;
+
+        // Assert
+        expect_that!(kept, eq(0));
+        expect_that!(purged, eq(1));
+        assert_that!(res, err(matches_pattern!(Errors::ObjectNotFound { .. })));
+    }
+
+    #[googletest::test]
+    #[tokio::test]
+    async fn restore_soft_deleted_bucket_unless_name_is_taken() {
+        // Arrange
+        let (storage, _) = storage_with_object().await;
+        let _ = storage
+            .delete_object("test_bucket", "a", None, Preconditions::default())
+            .await;
+        let deleted = storage.delete("test_bucket").await.unwrap();
+        let _ = storage
+            .create(
+                "test_bucket",
+                CreateBucketAttr {
+                    project: "test-project".into(),
+                    ..Default::default()
+                },
+            )
+            .await;
+
+        // Act
+        let taken = storage
+            .restore_bucket("test_bucket", deleted.generation)
+            .await;
+        let _ = storage.delete("test_bucket").await;
+        let restored = storage
+            .restore_bucket("test_bucket", deleted.generation)
+            .await;
+
+        // Assert
+        expect_that!(deleted.soft_delete_time, some(anything()));
+        expect_that!(taken, err(matches_pattern!(Errors::AlreadyExists { .. })));
+        assert_that!(restored.map(|b| b.soft_delete_time), ok(none()));
+    }
+}